@@ -16,8 +16,11 @@ use anyhow::Context as _;
 use buck2_audit::AuditCommand;
 use buck2_client::args::expand_argfiles_with_context;
 use buck2_client::commands::build::BuildCommand;
+use buck2_client::commands::bxl::expand_bxl_alias;
 use buck2_client::commands::bxl::BxlCommand;
 use buck2_client::commands::clean::CleanCommand;
+use buck2_client::commands::completion::CompleteCommand;
+use buck2_client::commands::completion::CompletionCommand;
 use buck2_client::commands::ctargets::ConfiguredTargetsCommand;
 use buck2_client::commands::debug::DebugCommand;
 use buck2_client::commands::help_env::HelpEnvCommand;
@@ -227,6 +230,7 @@ pub fn exec(process: ProcessContext<'_>) -> ExitResult {
     }
 
     let clap = Opt::clap();
+    let expanded_args = expand_bxl_alias(&clap, expanded_args, &immediate_config);
     let matches = clap.get_matches_from(&expanded_args);
     let opt: Opt = Opt::from_clap(&matches);
 
@@ -237,7 +241,11 @@ pub fn exec(process: ProcessContext<'_>) -> ExitResult {
     }
 
     match &opt.cmd {
-        CommandKind::Clean(..) | CommandKind::Daemon(..) | CommandKind::Forkserver(..) => {}
+        CommandKind::Clean(..)
+        | CommandKind::Completion(..)
+        | CommandKind::Complete(..)
+        | CommandKind::Daemon(..)
+        | CommandKind::Forkserver(..) => {}
         _ => {
             check_user_allowed()?;
         }
@@ -292,6 +300,9 @@ pub(crate) enum CommandKind {
     #[clap(hide(true))] // @oss-enable
     Rage(RageCommand),
     Clean(CleanCommand),
+    Completion(CompletionCommand),
+    #[clap(setting(AppSettings::Hidden))]
+    Complete(CompleteCommand),
     #[clap(subcommand)]
     Log(LogCommand),
     Lsp(LspCommand),
@@ -378,6 +389,8 @@ impl CommandKind {
             CommandKind::Kill(cmd) => cmd.exec(matches, command_ctx),
             CommandKind::Killall(cmd) => cmd.exec(matches, command_ctx),
             CommandKind::Clean(cmd) => cmd.exec(matches, command_ctx),
+            CommandKind::Completion(cmd) => cmd.exec(matches, command_ctx).into(),
+            CommandKind::Complete(cmd) => cmd.exec(matches, command_ctx, &Opt::clap()),
             CommandKind::Root(cmd) => cmd.exec(matches, command_ctx).into(),
             CommandKind::Query(cmd) => {
                 buck2_client_ctx::eprintln!(
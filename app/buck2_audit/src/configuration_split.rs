@@ -0,0 +1,47 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use async_trait::async_trait;
+use buck2_client_ctx::common::CommonCommandOptions;
+
+use crate::AuditSubcommand;
+
+#[derive(Debug, clap::Parser, serde::Serialize, serde::Deserialize)]
+#[clap(
+    name = "audit-configuration-split",
+    about = "Explain why a target is configured in more than one configuration",
+    long_about = "Lists every configuration the target appears in within the configured graph \
+    of the universe, with what produced it: the last transition or exec dependency on the way \
+    from a universe root, or else the target platform and modifiers the roots are configured \
+    with. For each configuration, also prints the chain of dependencies (and the transitions on \
+    them) from a universe root which introduced it, and how its constraints differ from the \
+    other configurations."
+)]
+pub struct AuditConfigurationSplitCommand {
+    #[clap(flatten)]
+    common_opts: CommonCommandOptions,
+
+    #[clap(name = "TARGET", help = "Target to explain the configurations of")]
+    pub target: String,
+
+    #[clap(
+        long,
+        multiple_values = true,
+        help = "Target patterns whose transitive deps are searched for configurations of TARGET. \
+        Defaults to TARGET itself."
+    )]
+    pub universe: Vec<String>,
+}
+
+#[async_trait]
+impl AuditSubcommand for AuditConfigurationSplitCommand {
+    fn common_opts(&self) -> &CommonCommandOptions {
+        &self.common_opts
+    }
+}
@@ -27,13 +27,16 @@ use classpath::AuditClasspathCommand;
 use crate::analysis_queries::AuditAnalysisQueriesCommand;
 use crate::cell::AuditCellCommand;
 use crate::config::AuditConfigCommand;
+use crate::configuration_split::AuditConfigurationSplitCommand;
 use crate::configurations::AuditConfigurationsCommand;
 use crate::deferred_materializer::DeferredMaterializerCommand;
 use crate::dep_files::AuditDepFilesCommand;
 use crate::execution_platform_resolution::AuditExecutionPlatformResolutionCommand;
 use crate::includes::AuditIncludesCommand;
+use crate::modifiers::AuditModifiersCommand;
 use crate::output::command::AuditOutputCommand;
 use crate::output::parse::AuditParseCommand;
+use crate::owners::AuditOwnersCommand;
 use crate::package_values::PackageValuesCommand;
 use crate::prelude::AuditPreludeCommand;
 use crate::providers::AuditProvidersCommand;
@@ -45,12 +48,15 @@ pub mod analysis_queries;
 pub mod cell;
 pub mod classpath;
 pub mod config;
+pub mod configuration_split;
 pub mod configurations;
 pub mod deferred_materializer;
 pub mod dep_files;
 pub mod execution_platform_resolution;
 pub mod includes;
+pub mod modifiers;
 pub mod output;
+pub mod owners;
 pub mod package_values;
 pub mod prelude;
 pub mod providers;
@@ -65,7 +71,10 @@ pub enum AuditCommand {
     Classpath(AuditClasspathCommand),
     Config(AuditConfigCommand),
     Configurations(AuditConfigurationsCommand),
+    ConfigurationSplit(AuditConfigurationSplitCommand),
     Includes(AuditIncludesCommand),
+    Modifiers(AuditModifiersCommand),
+    Owners(AuditOwnersCommand),
     Prelude(AuditPreludeCommand),
     Providers(AuditProvidersCommand),
     Subtargets(AuditSubtargetsCommand),
@@ -101,7 +110,10 @@ impl AuditCommand {
             AuditCommand::Classpath(cmd) => cmd,
             AuditCommand::Config(cmd) => cmd,
             AuditCommand::Configurations(cmd) => cmd,
+            AuditCommand::ConfigurationSplit(cmd) => cmd,
             AuditCommand::Includes(cmd) => cmd,
+            AuditCommand::Modifiers(cmd) => cmd,
+            AuditCommand::Owners(cmd) => cmd,
             AuditCommand::Prelude(cmd) => cmd,
             AuditCommand::Providers(cmd) => cmd,
             AuditCommand::Subtargets(cmd) => cmd,
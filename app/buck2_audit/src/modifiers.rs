@@ -0,0 +1,47 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use async_trait::async_trait;
+use buck2_client_ctx::common::CommonCommandOptions;
+
+use crate::AuditSubcommand;
+
+#[derive(Debug, clap::Parser, serde::Serialize, serde::Deserialize)]
+#[clap(
+    name = "audit-modifiers",
+    about = "List the values which can be passed to `--modifier`",
+    long_about = "Lists constraint settings with their constraint values, and the `[alias]` \
+    entries which refer to configuration targets. Settings are discovered from the aliases, \
+    from configuration targets matching PATTERNS, and from the target platform configuration \
+    of other targets matching PATTERNS. All constraint values declared in the packages of \
+    these settings are listed."
+)]
+pub struct AuditModifiersCommand {
+    #[clap(flatten)]
+    pub common_opts: CommonCommandOptions,
+
+    #[clap(
+        name = "PATTERNS",
+        help = "Configuration targets to list, or targets whose configuration is inspected"
+    )]
+    pub patterns: Vec<String>,
+
+    #[clap(
+        long,
+        help = "Only print the values accepted by `--modifier`, one per line (used for shell completion)"
+    )]
+    pub values: bool,
+}
+
+#[async_trait]
+impl AuditSubcommand for AuditModifiersCommand {
+    fn common_opts(&self) -> &CommonCommandOptions {
+        &self.common_opts
+    }
+}
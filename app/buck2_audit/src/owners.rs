@@ -0,0 +1,55 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use async_trait::async_trait;
+use buck2_client_ctx::common::CommonCommandOptions;
+
+use crate::AuditSubcommand;
+
+#[derive(Debug, clap::Parser, serde::Serialize, serde::Deserialize)]
+#[clap(
+    name = "audit-owners",
+    about = "Print the owners of targets and files",
+    long_about = "Prints the owners of targets and files, as declared in owners files. \
+    The owners of a path are found in the closest owners file in its directory or one of its \
+    parents (up to the root of its cell, like `PACKAGE` files), and the owners of a target are the \
+    owners of its build file. For files, the targets owning them (as with the `owner()` query \
+    function) are printed too.\n\n\
+    Owners files are named `OWNERS` by default, which is configured with `owners.file_name` \
+    in the root buckconfig. With `owners.format = plain` (the default), an owners file lists \
+    one owner per line. With `owners.format = codeowners`, each line is a `CODEOWNERS` style \
+    path pattern (relative to the directory of the file) followed by owners, and the last \
+    matching line wins. Empty lines and lines starting with `#` are ignored."
+)]
+pub struct AuditOwnersCommand {
+    #[clap(flatten)]
+    common_opts: CommonCommandOptions,
+
+    #[clap(
+        name = "TARGETS_OR_FILES",
+        help = "Target patterns (containing `:` or ending with `...`) or file paths"
+    )]
+    pub args: Vec<String>,
+
+    #[clap(
+        long,
+        help = "Fail if a package of the targets matching the patterns has no owner"
+    )]
+    pub check: bool,
+
+    #[clap(long = "json", help = "Output in JSON format")]
+    pub json: bool,
+}
+
+#[async_trait]
+impl AuditSubcommand for AuditOwnersCommand {
+    fn common_opts(&self) -> &CommonCommandOptions {
+        &self.common_opts
+    }
+}
@@ -15,7 +15,14 @@ use crate::AuditSubcommand;
 #[derive(Debug, clap::Parser, serde::Serialize, serde::Deserialize)]
 #[clap(
     name = "audit-visibility",
-    about = "Verify the visibility for transitive deps of the specified target(s) on the unconfigured target graph"
+    about = "Verify the visibility for transitive deps of the specified target(s) on the unconfigured target graph",
+    long_about = "Verify the visibility for transitive deps of the specified target(s) on the \
+    unconfigured target graph.\n\n\
+    With `--all`, every dependency edge in the transitive closure of the targets matching the \
+    patterns is checked against the `visibility` of the dependency, and all violations are \
+    reported at once, grouped by the package of the dependent. Targets which fail to load \
+    (including targets which depend on something outside of their `within_view`) are reported \
+    along with the violations instead of aborting the command."
 )]
 pub struct AuditVisibilityCommand {
     #[clap(flatten)]
@@ -23,6 +30,19 @@ pub struct AuditVisibilityCommand {
 
     #[clap(name = "TARGET_PATTERNS", help = "Target pattern(s) to analyze.")]
     pub patterns: Vec<String>,
+
+    #[clap(
+        long,
+        help = "Report all violations of the dependency edges of the targets, grouped by package"
+    )]
+    pub all: bool,
+
+    #[clap(
+        long = "json",
+        requires = "all",
+        help = "Output the `--all` report in JSON format"
+    )]
+    pub json: bool,
 }
 
 #[async_trait]
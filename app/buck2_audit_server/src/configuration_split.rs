@@ -0,0 +1,510 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::Write;
+
+use async_trait::async_trait;
+use buck2_audit::configuration_split::AuditConfigurationSplitCommand;
+use buck2_cli_proto::ClientContext;
+use buck2_core::configuration::cfg_diff::cfg_diff;
+use buck2_core::configuration::compatibility::MaybeCompatible;
+use buck2_core::configuration::data::ConfigurationData;
+use buck2_core::pattern::pattern_type::TargetPatternExtra;
+use buck2_core::target::configured_target_label::ConfiguredTargetLabel;
+use buck2_core::target::label::TargetLabel;
+use buck2_node::load_patterns::load_patterns;
+use buck2_node::load_patterns::MissingTargetBehavior;
+use buck2_node::nodes::configured::ConfiguredTargetNode;
+use buck2_node::nodes::configured_edge::ConfiguredEdgeKind;
+use buck2_node::nodes::configured_frontend::ConfiguredTargetNodeCalculation;
+use buck2_node::target_calculation::ConfiguredTargetCalculation;
+use buck2_server_ctx::ctx::ServerCommandContextTrait;
+use buck2_server_ctx::ctx::ServerCommandDiceContext;
+use buck2_server_ctx::partial_result_dispatcher::PartialResultDispatcher;
+use buck2_server_ctx::pattern::global_cfg_options_from_client_context;
+use buck2_server_ctx::pattern::PatternParser;
+use buck2_util::indent::indent;
+use dice::DiceTransaction;
+use dupe::Dupe;
+use futures::FutureExt;
+use gazebo::prelude::*;
+
+use crate::AuditSubcommand;
+
+#[derive(Debug, buck2_error::Error)]
+#[buck2(user)]
+enum AuditConfigurationSplitError {
+    #[error("`{0}` is not in the configured graph of the universe")]
+    NotInUniverse(TargetLabel),
+}
+
+#[async_trait]
+impl AuditSubcommand for AuditConfigurationSplitCommand {
+    async fn server_execute(
+        &self,
+        server_ctx: &dyn ServerCommandContextTrait,
+        stdout: PartialResultDispatcher<buck2_cli_proto::StdoutBytes>,
+        client_ctx: ClientContext,
+    ) -> anyhow::Result<()> {
+        server_ctx
+            .with_dice_ctx(move |server_ctx, ctx| {
+                server_execute_with_dice(self, client_ctx, server_ctx, stdout, ctx)
+            })
+            .await
+    }
+}
+
+/// The configured graph reachable from the universe roots, with the edge through which each node
+/// was first reached in breadth first order, so that the chains are the shortest ones.
+struct UniverseGraph {
+    /// For each reached node, its dependent and how the node got its configuration from it, or
+    /// `None` for the roots.
+    parents: HashMap<ConfiguredTargetLabel, Option<(ConfiguredTargetNode, ConfiguredEdgeKind)>>,
+    /// Nodes of the audited target, in the order they were reached.
+    splits: Vec<ConfiguredTargetNode>,
+}
+
+impl UniverseGraph {
+    fn new(roots: Vec<ConfiguredTargetNode>, target: &TargetLabel) -> Self {
+        let mut parents = HashMap::new();
+        let mut splits = Vec::new();
+        let mut queue = VecDeque::new();
+        for root in roots {
+            if !parents.contains_key(root.label()) {
+                parents.insert(root.label().dupe(), None);
+                queue.push_back(root);
+            }
+        }
+        while let Some(node) = queue.pop_front() {
+            // Forward nodes only redirect to the target configured by the incoming transition,
+            // which is then reached through them.
+            if node.label().unconfigured() == target && node.forward_target().is_none() {
+                splits.push(node.dupe());
+            }
+            for dep in node.deps() {
+                if !parents.contains_key(dep.label()) {
+                    parents.insert(
+                        dep.label().dupe(),
+                        Some((node.dupe(), node.dep_edge_kind(dep))),
+                    );
+                    queue.push_back(dep.dupe());
+                }
+            }
+        }
+        Self { parents, splits }
+    }
+
+    /// The universe root through which the node was reached, and the edges from it to the node.
+    fn path(&self, node: &ConfiguredTargetNode) -> (ConfiguredTargetLabel, Vec<Edge>) {
+        let mut edges = Vec::new();
+        let mut label = node.label().dupe();
+        while let Some(Some((parent, kind))) = self.parents.get(&label) {
+            edges.push(Edge {
+                from: parent.label().dupe(),
+                to: label,
+                kind: kind.clone(),
+            });
+            label = parent.label().dupe();
+        }
+        edges.reverse();
+        (label, edges)
+    }
+
+    /// The chain from a universe root to the node, one line per node.
+    fn chain(&self, node: &ConfiguredTargetNode) -> Vec<String> {
+        let (root, edges) = self.path(node);
+        let mut lines = vec![format!("{} (universe root)", root)];
+        for edge in edges {
+            let transition = match edge.kind.transition() {
+                Some(_) => "  <- configuration changed here",
+                None => "",
+            };
+            lines.push(format!("-> {} ({}){}", edge.to, edge.kind, transition));
+        }
+        lines
+    }
+}
+
+struct Edge {
+    from: ConfiguredTargetLabel,
+    to: ConfiguredTargetLabel,
+    kind: ConfiguredEdgeKind,
+}
+
+/// How the universe roots are configured, from the command line.
+#[derive(Debug, Clone, PartialEq)]
+struct RootConfiguration {
+    target_platform: Option<TargetLabel>,
+    cli_modifiers: Vec<String>,
+}
+
+impl fmt::Display for RootConfiguration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.target_platform, self.cli_modifiers.as_slice()) {
+            (None, []) => write!(f, "default target platform"),
+            (Some(platform), []) => write!(f, "target platform `{}`", platform),
+            (platform, modifiers) => {
+                write!(f, "modifiers `{}`", modifiers.join("`, `"))?;
+                if let Some(platform) = platform {
+                    write!(f, " on target platform `{}`", platform)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// What produced a configuration of the audited target: the last edge on the chain from the
+/// universe root which changed the configuration, or else the configuration of the root.
+#[derive(Debug, PartialEq)]
+enum Provenance {
+    /// A transition on the dependency `to` of `from`.
+    Transition {
+        from: ConfiguredTargetLabel,
+        to: ConfiguredTargetLabel,
+        kind: ConfiguredEdgeKind,
+    },
+    /// The execution platform of `from`, for its exec dependency.
+    ExecutionPlatform { from: ConfiguredTargetLabel },
+    /// The configuration of the universe root.
+    Root {
+        root: ConfiguredTargetLabel,
+        configuration: RootConfiguration,
+    },
+}
+
+impl Provenance {
+    fn new(
+        root: ConfiguredTargetLabel,
+        edges: Vec<Edge>,
+        configuration: &RootConfiguration,
+    ) -> Self {
+        for edge in edges.into_iter().rev() {
+            match edge.kind {
+                ConfiguredEdgeKind::Exec => {
+                    return Provenance::ExecutionPlatform { from: edge.from };
+                }
+                ConfiguredEdgeKind::OutgoingTransition(_)
+                | ConfiguredEdgeKind::IncomingTransition(_) => {
+                    return Provenance::Transition {
+                        from: edge.from,
+                        to: edge.to,
+                        kind: edge.kind,
+                    };
+                }
+                ConfiguredEdgeKind::Dep | ConfiguredEdgeKind::Toolchain => {}
+            }
+        }
+        Provenance::Root {
+            root,
+            configuration: configuration.clone(),
+        }
+    }
+}
+
+impl fmt::Display for Provenance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Provenance::Transition { from, to, kind } => {
+                write!(
+                    f,
+                    "{} on `{}` -> `{}`",
+                    kind,
+                    from.unconfigured(),
+                    to.unconfigured()
+                )
+            }
+            Provenance::ExecutionPlatform { from } => {
+                write!(f, "execution platform of `{}`", from.unconfigured())
+            }
+            Provenance::Root {
+                root,
+                configuration,
+            } => write!(
+                f,
+                "{} of universe root `{}`",
+                configuration,
+                root.unconfigured()
+            ),
+        }
+    }
+}
+
+/// One row per configuration: its number, the configuration and what produced it.
+fn write_summary(
+    rows: &[(ConfigurationData, Provenance)],
+    mut stdout: impl Write,
+) -> anyhow::Result<()> {
+    let cfgs: Vec<String> = rows.iter().map(|(cfg, _)| cfg.to_string()).collect();
+    let width = cfgs
+        .iter()
+        .map(|cfg| cfg.len())
+        .chain(std::iter::once("CONFIGURATION".len()))
+        .max()
+        .unwrap_or_default();
+    writeln!(stdout, "#  {:width$}  PRODUCED BY", "CONFIGURATION")?;
+    for (i, (cfg, (_, provenance))) in cfgs.iter().zip(rows).enumerate() {
+        writeln!(stdout, "{:<2} {:width$}  {}", i + 1, cfg, provenance)?;
+    }
+    Ok(())
+}
+
+async fn server_execute_with_dice(
+    command: &AuditConfigurationSplitCommand,
+    client_ctx: ClientContext,
+    server_ctx: &dyn ServerCommandContextTrait,
+    mut stdout: PartialResultDispatcher<buck2_cli_proto::StdoutBytes>,
+    mut ctx: DiceTransaction,
+) -> anyhow::Result<()> {
+    let global_cfg_options =
+        global_cfg_options_from_client_context(&client_ctx, server_ctx, &mut ctx).await?;
+
+    let parser = PatternParser::new(&mut ctx, server_ctx.working_dir()).await?;
+    let target = parser
+        .parse_pattern::<TargetPatternExtra>(&command.target)?
+        .as_target_label(&command.target)?;
+    let universe = if command.universe.is_empty() {
+        vec![command.target.clone()]
+    } else {
+        command.universe.clone()
+    };
+    let universe = universe.try_map(|p| parser.parse_pattern::<TargetPatternExtra>(p))?;
+
+    let loaded = load_patterns(&mut ctx, universe, MissingTargetBehavior::Fail).await?;
+    let mut labels = Vec::new();
+    for node in loaded.iter_loaded_targets() {
+        labels.push(node?.label().dupe());
+    }
+
+    let global_cfg_options = &global_cfg_options;
+    let roots = ctx
+        .compute_join(labels, |ctx: &mut _, label| {
+            async move {
+                let label = ctx
+                    .get_configured_target(&label, global_cfg_options)
+                    .await?;
+                ctx.get_configured_target_node(&label).await
+            }
+            .boxed()
+        })
+        .await;
+    let mut compatible_roots = Vec::new();
+    for root in roots {
+        // Like cquery, incompatible targets are not part of the universe.
+        if let MaybeCompatible::Compatible(root) = root? {
+            compatible_roots.push(root);
+        }
+    }
+
+    let graph = UniverseGraph::new(compatible_roots, &target);
+    if graph.splits.is_empty() {
+        return Err(AuditConfigurationSplitError::NotInUniverse(target).into());
+    }
+
+    let root_configuration = RootConfiguration {
+        target_platform: global_cfg_options.target_platform.dupe(),
+        cli_modifiers: global_cfg_options.cli_modifiers.as_ref().clone(),
+    };
+    let rows: Vec<(ConfigurationData, Provenance)> = graph
+        .splits
+        .iter()
+        .map(|node| {
+            let (root, edges) = graph.path(node);
+            (
+                node.label().cfg().dupe(),
+                Provenance::new(root, edges, &root_configuration),
+            )
+        })
+        .collect();
+
+    let mut stdout = stdout.as_writer();
+    writeln!(
+        stdout,
+        "{} has {} configuration(s) in the universe",
+        target,
+        graph.splits.len()
+    )?;
+    writeln!(stdout)?;
+    write_summary(&rows, &mut stdout)?;
+    for (i, node) in graph.splits.iter().enumerate() {
+        writeln!(stdout)?;
+        writeln!(stdout, "Configuration {}: {}", i + 1, node.label().cfg())?;
+        writeln!(stdout, "  Produced by: {}", rows[i].1)?;
+        writeln!(stdout, "  Introduced by:")?;
+        write!(stdout, "{}", indent("    ", &graph.chain(node).join("\n")))?;
+        writeln!(stdout)?;
+        for (j, other) in graph.splits.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+            // `-` lines are constraints of the other configuration, `+` lines of this one.
+            if let Err(diff) = cfg_diff(other.label().cfg(), node.label().cfg()) {
+                writeln!(stdout, "  Differs from configuration {}:", j + 1)?;
+                write!(stdout, "{}", indent("    ", &diff))?;
+            }
+        }
+    }
+    stdout.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use buck2_core::bzl::ImportPath;
+    use buck2_core::configuration::transition::id::TransitionId;
+
+    use super::*;
+
+    fn label(label: &str) -> ConfiguredTargetLabel {
+        TargetLabel::testing_parse(label).configure(ConfigurationData::testing_new())
+    }
+
+    fn edge(from: &str, to: &str, kind: ConfiguredEdgeKind) -> Edge {
+        Edge {
+            from: label(from),
+            to: label(to),
+            kind,
+        }
+    }
+
+    fn transition() -> ConfiguredEdgeKind {
+        ConfiguredEdgeKind::OutgoingTransition(Arc::new(TransitionId {
+            path: ImportPath::testing_new("root//:tr.bzl"),
+            name: "tr".to_owned(),
+        }))
+    }
+
+    fn default_configuration() -> RootConfiguration {
+        RootConfiguration {
+            target_platform: None,
+            cli_modifiers: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_root_configuration() {
+        assert_eq!(
+            "default target platform",
+            default_configuration().to_string()
+        );
+        let platform = RootConfiguration {
+            target_platform: Some(TargetLabel::testing_parse("root//platforms:linux")),
+            cli_modifiers: Vec::new(),
+        };
+        assert_eq!(
+            "target platform `root//platforms:linux`",
+            platform.to_string()
+        );
+        assert_eq!(
+            "modifiers `root//mode:dev`, `root//os:linux` on target platform `root//platforms:linux`",
+            RootConfiguration {
+                cli_modifiers: vec!["root//mode:dev".to_owned(), "root//os:linux".to_owned()],
+                ..platform
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn test_provenance_root() {
+        let provenance = Provenance::new(
+            label("root//a:a"),
+            vec![
+                edge("root//a:a", "root//b:b", ConfiguredEdgeKind::Dep),
+                edge("root//b:b", "root//c:c", ConfiguredEdgeKind::Toolchain),
+            ],
+            &default_configuration(),
+        );
+        assert_eq!(
+            Provenance::Root {
+                root: label("root//a:a"),
+                configuration: default_configuration(),
+            },
+            provenance
+        );
+        assert_eq!(
+            "default target platform of universe root `root//a:a`",
+            provenance.to_string()
+        );
+    }
+
+    #[test]
+    fn test_provenance_last_change_wins() {
+        let provenance = Provenance::new(
+            label("root//a:a"),
+            vec![
+                edge("root//a:a", "root//b:b", transition()),
+                edge("root//b:b", "root//c:c", ConfiguredEdgeKind::Exec),
+                edge("root//c:c", "root//d:d", ConfiguredEdgeKind::Dep),
+            ],
+            &default_configuration(),
+        );
+        assert_eq!("execution platform of `root//b:b`", provenance.to_string());
+
+        let provenance = Provenance::new(
+            label("root//a:a"),
+            vec![
+                edge("root//a:a", "root//b:b", ConfiguredEdgeKind::Exec),
+                edge("root//b:b", "root//c:c", transition()),
+                edge("root//c:c", "root//d:d", ConfiguredEdgeKind::Dep),
+            ],
+            &default_configuration(),
+        );
+        assert_eq!(
+            "outgoing transition root//:tr.bzl#tr on `root//b:b` -> `root//c:c`",
+            provenance.to_string()
+        );
+    }
+
+    #[test]
+    fn test_summary() {
+        let linux = ConfigurationData::testing_new();
+        let exec = ConfigurationData::unspecified_exec();
+        let mut out = Vec::new();
+        write_summary(
+            &[
+                (
+                    linux.dupe(),
+                    Provenance::Root {
+                        root: label("root//a:a"),
+                        configuration: default_configuration(),
+                    },
+                ),
+                (
+                    exec.dupe(),
+                    Provenance::ExecutionPlatform {
+                        from: label("root//a:a"),
+                    },
+                ),
+            ],
+            &mut out,
+        )
+        .unwrap();
+        let width = [linux.to_string(), exec.to_string()]
+            .iter()
+            .map(|cfg| cfg.len())
+            .chain(["CONFIGURATION".len()])
+            .max()
+            .unwrap();
+        assert_eq!(
+            format!(
+                "#  {:width$}  PRODUCED BY\n\
+                 1  {:width$}  default target platform of universe root `root//a:a`\n\
+                 2  {:width$}  execution platform of `root//a:a`\n",
+                "CONFIGURATION", linux, exec
+            ),
+            String::from_utf8(out).unwrap()
+        );
+    }
+}
@@ -24,12 +24,15 @@ mod analysis_queries;
 mod cell;
 mod classpath;
 mod config;
+mod configuration_split;
 mod configurations;
 pub mod deferred_materializer;
 mod dep_files;
 mod execution_platform_resolution;
 mod includes;
+mod modifiers;
 pub mod output;
+mod owners;
 mod package_values;
 mod prelude;
 mod providers;
@@ -85,7 +88,10 @@ impl AuditCommandExt for AuditCommand {
             AuditCommand::Classpath(cmd) => cmd,
             AuditCommand::Config(cmd) => cmd,
             AuditCommand::Configurations(cmd) => cmd,
+            AuditCommand::ConfigurationSplit(cmd) => cmd,
             AuditCommand::Includes(cmd) => cmd,
+            AuditCommand::Modifiers(cmd) => cmd,
+            AuditCommand::Owners(cmd) => cmd,
             AuditCommand::Prelude(cmd) => cmd,
             AuditCommand::Providers(cmd) => cmd,
             AuditCommand::Subtargets(cmd) => cmd,
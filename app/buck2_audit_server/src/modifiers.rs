@@ -0,0 +1,361 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::io::Write;
+
+use async_trait::async_trait;
+use buck2_audit::modifiers::AuditModifiersCommand;
+use buck2_build_api::analysis::calculation::RuleAnalysisCalculation;
+use buck2_build_api::interpreter::rule_defs::provider::builtin::configuration_info::FrozenConfigurationInfo;
+use buck2_cli_proto::ClientContext;
+use buck2_common::target_aliases::HasTargetAliasResolver;
+use buck2_core::configuration::constraints::ConstraintKey;
+use buck2_core::configuration::constraints::ConstraintValue;
+use buck2_core::package::PackageLabel;
+use buck2_core::pattern::pattern_type::TargetPatternExtra;
+use buck2_core::target::label::TargetLabel;
+use buck2_core::target_aliases::TargetAliasResolver;
+use buck2_node::load_patterns::load_patterns;
+use buck2_node::load_patterns::MissingTargetBehavior;
+use buck2_node::nodes::frontend::TargetGraphCalculation;
+use buck2_node::nodes::unconfigured::RuleKind;
+use buck2_node::target_calculation::ConfiguredTargetCalculation;
+use buck2_server_ctx::ctx::ServerCommandContextTrait;
+use buck2_server_ctx::ctx::ServerCommandDiceContext;
+use buck2_server_ctx::partial_result_dispatcher::PartialResultDispatcher;
+use buck2_server_ctx::pattern::global_cfg_options_from_client_context;
+use buck2_server_ctx::pattern::PatternParser;
+use dice::DiceComputations;
+use dice::DiceTransaction;
+use dupe::Dupe;
+use futures::FutureExt;
+use gazebo::prelude::*;
+
+use crate::AuditSubcommand;
+
+#[async_trait]
+impl AuditSubcommand for AuditModifiersCommand {
+    async fn server_execute(
+        &self,
+        server_ctx: &dyn ServerCommandContextTrait,
+        stdout: PartialResultDispatcher<buck2_cli_proto::StdoutBytes>,
+        client_ctx: ClientContext,
+    ) -> anyhow::Result<()> {
+        server_ctx
+            .with_dice_ctx(move |server_ctx, ctx| {
+                server_execute_with_dice(self, client_ctx, server_ctx, stdout, ctx)
+            })
+            .await
+    }
+}
+
+/// Constraints set by a configuration target, or `None` if the target is not one.
+async fn configuration_constraints(
+    ctx: &mut DiceComputations<'_>,
+    label: &TargetLabel,
+) -> anyhow::Result<Option<BTreeMap<ConstraintKey, ConstraintValue>>> {
+    if ctx.get_target_node(label).await?.rule_kind() != RuleKind::Configuration {
+        return Ok(None);
+    }
+    let result = ctx.get_configuration_analysis_result(label).await?;
+    let constraints = result
+        .providers()
+        .provider_collection()
+        .builtin_provider::<FrozenConfigurationInfo>()
+        .map(|info| info.to_config_setting_data().constraints);
+    Ok(constraints)
+}
+
+/// If the target is a `constraint_value`, its constraint setting.
+async fn constraint_value_setting(
+    ctx: &mut DiceComputations<'_>,
+    label: &TargetLabel,
+) -> anyhow::Result<Option<ConstraintKey>> {
+    let Some(constraints) = configuration_constraints(ctx, label).await? else {
+        return Ok(None);
+    };
+    // A `config_setting` with a single constraint is not a value of that setting.
+    match constraints.into_iter().collect::<Vec<_>>().as_slice() {
+        [(setting, value)] if &value.0 == label => Ok(Some(setting.dupe())),
+        _ => Ok(None),
+    }
+}
+
+#[derive(Default)]
+struct Modifiers {
+    /// Constraint values by constraint setting.
+    settings: BTreeMap<ConstraintKey, BTreeSet<ConstraintValue>>,
+    /// Configuration targets which set several constraints, like `config_setting`.
+    config_settings: BTreeSet<TargetLabel>,
+    /// `[alias]` names of configuration targets.
+    aliases: BTreeMap<TargetLabel, Vec<String>>,
+}
+
+impl Modifiers {
+    fn add_constraints(
+        &mut self,
+        label: &TargetLabel,
+        constraints: BTreeMap<ConstraintKey, ConstraintValue>,
+    ) {
+        if constraints.len() != 1 || !constraints.values().any(|v| &v.0 == label) {
+            self.config_settings.insert(label.dupe());
+        }
+        for (setting, value) in constraints {
+            self.settings.entry(setting).or_default().insert(value);
+        }
+    }
+
+    fn aliases_of(&self, label: &TargetLabel) -> String {
+        match self.aliases.get(label) {
+            Some(aliases) => format!(" (alias: {})", aliases.join(", ")),
+            None => String::new(),
+        }
+    }
+
+    /// Write the listing, or with `values` only the values accepted by `--modifier`.
+    fn write(&self, values: bool, mut stdout: impl Write) -> anyhow::Result<()> {
+        if values {
+            let mut aliases: Vec<&String> = self.aliases.values().flatten().collect();
+            aliases.sort();
+            for alias in aliases {
+                writeln!(stdout, "{}", alias)?;
+            }
+            for value in self.settings.values().flatten() {
+                writeln!(stdout, "{}", value)?;
+            }
+            for config_setting in &self.config_settings {
+                writeln!(stdout, "{}", config_setting)?;
+            }
+        } else {
+            for (setting, values) in &self.settings {
+                writeln!(stdout, "{}", setting)?;
+                for value in values {
+                    writeln!(stdout, "  {}{}", value, self.aliases_of(&value.0))?;
+                }
+            }
+            if !self.config_settings.is_empty() {
+                writeln!(stdout, "Configuration targets setting several constraints:")?;
+                for config_setting in &self.config_settings {
+                    writeln!(
+                        stdout,
+                        "  {}{}",
+                        config_setting,
+                        self.aliases_of(config_setting)
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+async fn server_execute_with_dice(
+    command: &AuditModifiersCommand,
+    client_ctx: ClientContext,
+    server_ctx: &dyn ServerCommandContextTrait,
+    mut stdout: PartialResultDispatcher<buck2_cli_proto::StdoutBytes>,
+    mut ctx: DiceTransaction,
+) -> anyhow::Result<()> {
+    let global_cfg_options =
+        global_cfg_options_from_client_context(&client_ctx, server_ctx, &mut ctx).await?;
+    let parser = PatternParser::new(&mut ctx, server_ctx.working_dir()).await?;
+    let alias_resolver = ctx
+        .target_alias_resolver_for_working_dir(server_ctx.working_dir())
+        .await?;
+
+    let mut modifiers = Modifiers::default();
+    let mut labels = Vec::new();
+    for alias in alias_resolver.aliases() {
+        // Aliases may also name patterns or non-target values, or be broken, and none of those
+        // are modifiers.
+        let Ok(Some(value)) = alias_resolver.get(alias) else {
+            continue;
+        };
+        let Ok(label) = parser
+            .parse_pattern::<TargetPatternExtra>(value)
+            .and_then(|p| p.as_target_label(value))
+        else {
+            continue;
+        };
+        labels.push((label.dupe(), true));
+        modifiers
+            .aliases
+            .entry(label)
+            .or_default()
+            .push(alias.to_owned());
+    }
+
+    let patterns = command
+        .patterns
+        .try_map(|p| parser.parse_pattern::<TargetPatternExtra>(p))?;
+    let loaded = load_patterns(&mut ctx, patterns, MissingTargetBehavior::Fail).await?;
+    for node in loaded.iter_loaded_targets() {
+        labels.push((node?.label().dupe(), false));
+    }
+
+    let global_cfg_options = &global_cfg_options;
+    let constraints = ctx
+        .try_compute_join(labels, |ctx: &mut _, (label, from_alias)| {
+            async move {
+                let constraints = match configuration_constraints(ctx, &label).await {
+                    Ok(constraints) => constraints,
+                    // Like other broken aliases, aliases of targets which fail to load are
+                    // skipped rather than failing the whole listing.
+                    Err(_) if from_alias => return anyhow::Ok(None),
+                    Err(e) => return Err(e),
+                };
+                if let Some(constraints) = constraints {
+                    return Ok(Some((Some(label), constraints)));
+                }
+                if from_alias {
+                    return Ok(None);
+                }
+                // Not a configuration target, so report the constraints of the platform it is
+                // configured with.
+                let configured = ctx
+                    .get_configured_target(&label, global_cfg_options)
+                    .await?;
+                Ok(configured
+                    .cfg()
+                    .data()
+                    .ok()
+                    .map(|data| (None, data.constraints.clone())))
+            }
+            .boxed()
+        })
+        .await?;
+    let mut configuration_targets = BTreeSet::new();
+    for (label, constraints) in constraints.into_iter().flatten() {
+        match label {
+            Some(label) => {
+                modifiers.add_constraints(&label, constraints);
+                configuration_targets.insert(label);
+            }
+            None => {
+                for (setting, value) in constraints {
+                    modifiers.settings.entry(setting).or_default().insert(value);
+                }
+            }
+        }
+    }
+    modifiers
+        .aliases
+        .retain(|label, _| configuration_targets.contains(label));
+
+    // Constraint values are conventionally declared next to their setting, so list all values of
+    // the known settings from the packages where the setting and its known values live.
+    let packages: BTreeSet<PackageLabel> = modifiers
+        .settings
+        .iter()
+        .flat_map(|(setting, values)| {
+            std::iter::once(setting.0.pkg()).chain(values.iter().map(|v| v.0.pkg()))
+        })
+        .collect();
+    let candidates = ctx
+        .try_compute_join(packages, |ctx: &mut _, package| {
+            async move {
+                let result = ctx.get_interpreter_results(package).await?;
+                anyhow::Ok(
+                    result
+                        .targets()
+                        .values()
+                        .filter(|node| node.rule.rule_kind == RuleKind::Configuration)
+                        .map(|node| node.label().dupe())
+                        .collect::<Vec<_>>(),
+                )
+            }
+            .boxed()
+        })
+        .await?;
+    let values = ctx
+        .try_compute_join(candidates.into_iter().flatten(), |ctx: &mut _, label| {
+            async move {
+                let setting = constraint_value_setting(ctx, &label).await?;
+                anyhow::Ok(setting.map(|setting| (setting, label)))
+            }
+            .boxed()
+        })
+        .await?;
+    for (setting, label) in values.into_iter().flatten() {
+        if let Some(values) = modifiers.settings.get_mut(&setting) {
+            values.insert(ConstraintValue(label));
+        }
+    }
+
+    let mut stdout = stdout.as_writer();
+    modifiers.write(command.values, &mut stdout)?;
+    stdout.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn modifiers() -> Modifiers {
+        let mut modifiers = Modifiers::default();
+        let label = TargetLabel::testing_parse;
+        let constraint = |setting: &str, value: &str| {
+            (ConstraintKey(label(setting)), ConstraintValue(label(value)))
+        };
+        modifiers.add_constraints(
+            &label("root//mode:dev"),
+            BTreeMap::from([constraint("root//mode:mode", "root//mode:dev")]),
+        );
+        modifiers.add_constraints(
+            &label("root//mode:release"),
+            BTreeMap::from([constraint("root//mode:mode", "root//mode:release")]),
+        );
+        modifiers.add_constraints(
+            &label("root//mode:linux_release"),
+            BTreeMap::from([
+                constraint("root//mode:mode", "root//mode:release"),
+                constraint("root//os:os", "root//os:linux"),
+            ]),
+        );
+        modifiers
+            .aliases
+            .insert(label("root//mode:release"), vec!["release".to_owned()]);
+        modifiers
+    }
+
+    fn write(modifiers: &Modifiers, values: bool) -> String {
+        let mut out = Vec::new();
+        modifiers.write(values, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_listing() {
+        assert_eq!(
+            "root//mode:mode\n\
+             \x20 root//mode:dev\n\
+             \x20 root//mode:release (alias: release)\n\
+             root//os:os\n\
+             \x20 root//os:linux\n\
+             Configuration targets setting several constraints:\n\
+             \x20 root//mode:linux_release\n",
+            write(&modifiers(), false)
+        );
+    }
+
+    #[test]
+    fn test_values() {
+        assert_eq!(
+            "release\n\
+             root//mode:dev\n\
+             root//mode:release\n\
+             root//os:linux\n\
+             root//mode:linux_release\n",
+            write(&modifiers(), true)
+        );
+    }
+}
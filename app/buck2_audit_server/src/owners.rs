@@ -0,0 +1,448 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::io::Write;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use buck2_audit::owners::AuditOwnersCommand;
+use buck2_build_api::query::oneshot::QUERY_FRONTEND;
+use buck2_cli_proto::ClientContext;
+use buck2_common::dice::cells::HasCellResolver;
+use buck2_common::dice::file_ops::DiceFileComputations;
+use buck2_common::global_cfg_options::GlobalCfgOptions;
+use buck2_common::legacy_configs::dice::HasLegacyConfigs;
+use buck2_core::cells::cell_path::CellPath;
+use buck2_core::cells::cell_path::CellPathRef;
+use buck2_core::fs::paths::file_name::FileNameBuf;
+use buck2_core::fs::project_rel_path::ProjectRelativePath;
+use buck2_core::package::PackageLabel;
+use buck2_core::pattern::pattern_type::TargetPatternExtra;
+use buck2_core::pattern::query_file_literal::parse_query_file_literal;
+use buck2_node::load_patterns::load_patterns;
+use buck2_node::load_patterns::MissingTargetBehavior;
+use buck2_node::nodes::frontend::TargetGraphCalculation;
+use buck2_query::query::syntax::simple::eval::values::QueryEvaluationResult;
+use buck2_server_ctx::ctx::ServerCommandContextTrait;
+use buck2_server_ctx::ctx::ServerCommandDiceContext;
+use buck2_server_ctx::partial_result_dispatcher::PartialResultDispatcher;
+use buck2_server_ctx::pattern::PatternParser;
+use dice::DiceComputations;
+use dice::DiceTransaction;
+use dupe::Dupe;
+use globset::GlobBuilder;
+use globset::GlobMatcher;
+use itertools::Itertools;
+
+use crate::AuditSubcommand;
+
+#[derive(Debug, buck2_error::Error)]
+#[buck2(user)]
+enum AuditOwnersError {
+    #[error("Invalid `owners.format` `{0}`, expected `plain` or `codeowners`")]
+    InvalidFormat(String),
+    #[error("Line `{0}` of a `codeowners` owners file has no owners")]
+    MissingOwners(String),
+    #[error("Packages without owners:\n{}", .0.iter().map(|p| format!("  {}", p)).join("\n"))]
+    PackagesWithoutOwners(Vec<PackageLabel>),
+}
+
+#[derive(Debug, Clone, Copy, Dupe, PartialEq)]
+enum OwnersFileFormat {
+    /// One owner per line, for everything under the directory.
+    Plain,
+    /// `CODEOWNERS` style `<pattern> <owner>...` lines.
+    Codeowners,
+}
+
+impl FromStr for OwnersFileFormat {
+    type Err = AuditOwnersError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plain" => Ok(OwnersFileFormat::Plain),
+            "codeowners" => Ok(OwnersFileFormat::Codeowners),
+            _ => Err(AuditOwnersError::InvalidFormat(s.to_owned())),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum OwnersFile {
+    Plain(Vec<String>),
+    /// Rules in file order, the last matching one wins.
+    Codeowners(Vec<(GlobMatcher, Vec<String>)>),
+}
+
+/// Matches a `CODEOWNERS` pattern against paths relative to the directory of the owners file.
+/// A pattern without a `/` (other than a trailing one) matches at any depth, and a pattern
+/// matching a directory matches everything under it.
+fn codeowners_matcher(pattern: &str) -> anyhow::Result<GlobMatcher> {
+    let pattern = pattern.trim_end_matches('/');
+    let glob = if pattern.contains('/') {
+        pattern.trim_start_matches('/').to_owned()
+    } else {
+        format!("**/{}", pattern)
+    };
+    Ok(GlobBuilder::new(&format!("{{{},{}/**}}", glob, glob))
+        .literal_separator(true)
+        .build()?
+        .compile_matcher())
+}
+
+impl OwnersFile {
+    fn parse(format: OwnersFileFormat, contents: &str) -> anyhow::Result<OwnersFile> {
+        let lines = contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+        match format {
+            OwnersFileFormat::Plain => Ok(OwnersFile::Plain(lines.map(str::to_owned).collect())),
+            OwnersFileFormat::Codeowners => {
+                let mut rules = Vec::new();
+                for line in lines {
+                    let mut words = line.split_whitespace();
+                    let pattern = words.next().expect("line is not empty");
+                    let owners: Vec<String> = words.map(str::to_owned).collect();
+                    if owners.is_empty() {
+                        return Err(AuditOwnersError::MissingOwners(line.to_owned()).into());
+                    }
+                    let matcher = codeowners_matcher(pattern)
+                        .with_context(|| format!("Invalid pattern `{}`", pattern))?;
+                    rules.push((matcher, owners));
+                }
+                Ok(OwnersFile::Codeowners(rules))
+            }
+        }
+    }
+
+    /// Owners of a path relative to the directory of the owners file, or `None` if the file
+    /// does not say, in which case the owners files of the parent directories are consulted.
+    fn owners(&self, path: &str) -> Option<&[String]> {
+        match self {
+            OwnersFile::Plain(owners) if owners.is_empty() => None,
+            OwnersFile::Plain(owners) => Some(owners),
+            OwnersFile::Codeowners(rules) => rules
+                .iter()
+                .rev()
+                .find(|(matcher, _)| matcher.is_match(path))
+                .map(|(_, owners)| owners.as_slice()),
+        }
+    }
+}
+
+#[derive(Debug, Default, serde::Serialize)]
+struct Owners {
+    owners: Vec<String>,
+    /// The owners file the owners come from.
+    owners_file: Option<String>,
+    /// For files, the targets having them as inputs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    targets: Option<Vec<String>>,
+}
+
+/// The directories whose owners files are consulted for a path, innermost first.
+/// Like `PACKAGE` files, owners files do not apply across cells, so this stops at the cell root.
+fn owners_file_dirs(path: &CellPath) -> impl Iterator<Item = CellPathRef> {
+    path.ancestors().skip(1)
+}
+
+/// Finds the owners of paths by looking up owners files in their directory and its parents,
+/// the way `PACKAGE` files are looked up.
+struct OwnersLookup {
+    file_name: FileNameBuf,
+    format: OwnersFileFormat,
+    /// Parsed owners files by directory, `None` if the directory has no owners file.
+    files: HashMap<CellPath, Option<Arc<OwnersFile>>>,
+}
+
+impl OwnersLookup {
+    async fn new(ctx: &mut DiceComputations<'_>) -> anyhow::Result<OwnersLookup> {
+        let cell_resolver = ctx.get_cell_resolver().await?;
+        let root_cell = cell_resolver.root_cell();
+        let file_name = match ctx
+            .get_legacy_config_property(root_cell, "owners", "file_name")
+            .await?
+        {
+            Some(file_name) => FileNameBuf::try_from(file_name.to_string())?,
+            None => FileNameBuf::try_from("OWNERS".to_owned())?,
+        };
+        let format = ctx
+            .parse_legacy_config_property(root_cell, "owners", "format")
+            .await?
+            .unwrap_or(OwnersFileFormat::Plain);
+        Ok(OwnersLookup {
+            file_name,
+            format,
+            files: HashMap::new(),
+        })
+    }
+
+    async fn owners_file(
+        &mut self,
+        ctx: &mut DiceComputations<'_>,
+        dir: CellPathRef<'_>,
+    ) -> anyhow::Result<Option<Arc<OwnersFile>>> {
+        let dir = dir.to_owned();
+        if let Some(file) = self.files.get(&dir) {
+            return Ok(file.clone());
+        }
+        let file = if DiceFileComputations::read_dir(ctx, dir.as_ref())
+            .await?
+            .contains(&self.file_name)
+        {
+            let path = dir.join(&self.file_name);
+            let contents = DiceFileComputations::read_file(ctx, path.as_ref()).await?;
+            let file = OwnersFile::parse(self.format, &contents)
+                .with_context(|| format!("Parsing owners file `{}`", path))?;
+            Some(Arc::new(file))
+        } else {
+            None
+        };
+        self.files.insert(dir, file.clone());
+        Ok(file)
+    }
+
+    async fn owners(
+        &mut self,
+        ctx: &mut DiceComputations<'_>,
+        path: &CellPath,
+    ) -> anyhow::Result<Owners> {
+        for dir in owners_file_dirs(path) {
+            if let Some(file) = self.owners_file(ctx, dir).await? {
+                if let Some(owners) = file.owners(path.as_ref().strip_prefix(dir)?.as_str()) {
+                    return Ok(Owners {
+                        owners: owners.to_vec(),
+                        owners_file: Some(dir.join(&self.file_name).to_string()),
+                        targets: None,
+                    });
+                }
+            }
+        }
+        Ok(Owners::default())
+    }
+}
+
+/// Targets having each of the files as input, computed by the `owner()` query function.
+async fn owning_targets(
+    ctx: &mut DiceComputations<'_>,
+    working_dir: &ProjectRelativePath,
+    files: Vec<String>,
+) -> anyhow::Result<HashMap<String, Vec<String>>> {
+    if files.is_empty() {
+        return Ok(HashMap::new());
+    }
+    match QUERY_FRONTEND
+        .get()?
+        .eval_uquery(
+            ctx,
+            working_dir,
+            "owner(%s)",
+            &files,
+            GlobalCfgOptions::default(),
+        )
+        .await?
+    {
+        QueryEvaluationResult::Multiple(results) => results
+            .0
+            .into_iter()
+            .map(|(file, targets)| {
+                let targets = targets?.try_into_targets()?;
+                Ok((
+                    file,
+                    targets
+                        .iter()
+                        .map(|node| node.label().to_string())
+                        .collect(),
+                ))
+            })
+            .collect(),
+        QueryEvaluationResult::Single(_) => {
+            unreachable!("a query with `%s` has a result per argument")
+        }
+    }
+}
+
+fn is_target_pattern(arg: &str) -> bool {
+    arg.contains(':') || arg.ends_with("...")
+}
+
+#[async_trait]
+impl AuditSubcommand for AuditOwnersCommand {
+    async fn server_execute(
+        &self,
+        server_ctx: &dyn ServerCommandContextTrait,
+        stdout: PartialResultDispatcher<buck2_cli_proto::StdoutBytes>,
+        _client_ctx: ClientContext,
+    ) -> anyhow::Result<()> {
+        server_ctx
+            .with_dice_ctx(move |server_ctx, ctx| {
+                server_execute_with_dice(self, server_ctx, stdout, ctx)
+            })
+            .await
+    }
+}
+
+async fn server_execute_with_dice(
+    command: &AuditOwnersCommand,
+    server_ctx: &dyn ServerCommandContextTrait,
+    mut stdout: PartialResultDispatcher<buck2_cli_proto::StdoutBytes>,
+    mut ctx: DiceTransaction,
+) -> anyhow::Result<()> {
+    let mut lookup = OwnersLookup::new(&mut ctx).await?;
+    let cell_resolver = ctx.get_cell_resolver().await?;
+    let parser = PatternParser::new(&mut ctx, server_ctx.working_dir()).await?;
+    let cwd_cell = cell_resolver.get(cell_resolver.find(server_ctx.working_dir())?)?;
+    let working_dir_abs = server_ctx.project_root().resolve(server_ctx.working_dir());
+
+    let (patterns, files): (Vec<&String>, Vec<&String>) =
+        command.args.iter().partition(|arg| is_target_pattern(arg));
+
+    let patterns = patterns
+        .into_iter()
+        .map(|p| parser.parse_pattern::<TargetPatternExtra>(p))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let loaded = load_patterns(&mut ctx, patterns, MissingTargetBehavior::Fail).await?;
+    let mut targets = BTreeMap::new();
+    let mut packages_without_owners = Vec::new();
+    for (package, result) in loaded.iter_loaded_targets_by_package() {
+        let nodes = result?;
+        if command.check {
+            let buildfile = ctx.get_interpreter_results(package.dupe()).await?;
+            if lookup
+                .owners(&mut ctx, &buildfile.buildfile_path().path())
+                .await?
+                .owners
+                .is_empty()
+            {
+                packages_without_owners.push(package);
+            }
+        }
+        for node in nodes {
+            let owners = lookup
+                .owners(&mut ctx, &node.buildfile_path().path())
+                .await?;
+            targets.insert(node.label().to_string(), owners);
+        }
+    }
+    if !packages_without_owners.is_empty() {
+        return Err(AuditOwnersError::PackagesWithoutOwners(packages_without_owners).into());
+    }
+
+    let mut targets_by_file = owning_targets(
+        &mut ctx,
+        server_ctx.working_dir(),
+        files.iter().map(|file| (*file).clone()).collect(),
+    )
+    .await?;
+    let mut file_owners = BTreeMap::new();
+    for file in files {
+        let path = parse_query_file_literal(
+            file,
+            cwd_cell.cell_alias_resolver(),
+            &cell_resolver,
+            &working_dir_abs,
+            server_ctx.project_root(),
+        )?;
+        let mut owners = lookup.owners(&mut ctx, &path).await?;
+        owners.targets = Some(targets_by_file.remove(file).unwrap_or_default());
+        file_owners.insert(path.to_string(), owners);
+    }
+
+    let mut stdout = stdout.as_writer();
+    if command.json {
+        let output = serde_json::json!({
+            "targets": targets,
+            "files": file_owners,
+        });
+        writeln!(stdout, "{}", serde_json::to_string_pretty(&output)?)?;
+    } else {
+        for (name, owners) in targets.iter().chain(file_owners.iter()) {
+            writeln!(stdout, "{}", name)?;
+            if owners.owners.is_empty() {
+                writeln!(stdout, "  owners: (none)")?;
+            } else {
+                writeln!(stdout, "  owners: {}", owners.owners.join(", "))?;
+            }
+            if let Some(owners_file) = &owners.owners_file {
+                writeln!(stdout, "  owners file: {}", owners_file)?;
+            }
+            if let Some(targets) = &owners.targets {
+                writeln!(stdout, "  targets: {}", targets.join(", "))?;
+            }
+        }
+    }
+    stdout.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use buck2_core::cells::cell_path::CellPath;
+
+    use super::owners_file_dirs;
+    use super::OwnersFile;
+    use super::OwnersFileFormat;
+
+    #[test]
+    fn test_plain() {
+        let file = OwnersFile::parse(
+            OwnersFileFormat::Plain,
+            "# Owners of the compiler\nteam_compiler\n\n  alice  \n",
+        )
+        .unwrap();
+        assert_eq!(
+            Some(&["team_compiler".to_owned(), "alice".to_owned()][..]),
+            file.owners("src/lib.rs")
+        );
+
+        let file = OwnersFile::parse(OwnersFileFormat::Plain, "# Nobody\n").unwrap();
+        assert_eq!(None, file.owners("BUCK"));
+    }
+
+    #[test]
+    fn test_codeowners() {
+        let file = OwnersFile::parse(
+            OwnersFileFormat::Codeowners,
+            "* team_default\n*.md team_docs\n/src/ team_src\nsrc/gen/ team_gen bob\n",
+        )
+        .unwrap();
+        let owners = |path| file.owners(path).map(|owners| owners.join(" "));
+        assert_eq!(Some("team_default".to_owned()), owners("BUCK"));
+        assert_eq!(Some("team_docs".to_owned()), owners("docs/a/README.md"));
+        assert_eq!(Some("team_src".to_owned()), owners("src/lib.rs"));
+        assert_eq!(Some("team_src".to_owned()), owners("src/README.md"));
+        assert_eq!(Some("team_gen bob".to_owned()), owners("src/gen/x/y.rs"));
+
+        let file = OwnersFile::parse(OwnersFileFormat::Codeowners, "lib/*.rs team_lib\n").unwrap();
+        assert_eq!(None, file.owners("BUCK"));
+        assert_eq!(None, file.owners("lib/a/b.rs"));
+        assert_eq!(Some(&["team_lib".to_owned()][..]), file.owners("lib/b.rs"));
+
+        assert!(OwnersFile::parse(OwnersFileFormat::Codeowners, "src/\n").is_err());
+    }
+
+    #[test]
+    fn test_owners_file_dirs() {
+        let dirs = |path| {
+            owners_file_dirs(&CellPath::testing_new(path))
+                .map(|dir| dir.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vec!["cell//"], dirs("cell//BUCK"));
+        assert_eq!(
+            vec!["cell//a/b", "cell//a", "cell//"],
+            dirs("cell//a/b/c.txt")
+        );
+    }
+}
@@ -7,12 +7,21 @@
  * of this source tree.
  */
 
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::io::Write;
+
 use async_trait::async_trait;
 use buck2_audit::visibility::AuditVisibilityCommand;
 use buck2_cli_proto::ClientContext;
+use buck2_core::package::PackageLabel;
 use buck2_core::pattern::pattern_type::TargetPatternExtra;
+use buck2_core::target::label::TargetLabel;
 use buck2_node::load_patterns::load_patterns;
+use buck2_node::load_patterns::LoadedPatterns;
 use buck2_node::load_patterns::MissingTargetBehavior;
+use buck2_node::nodes::frontend::TargetGraphCalculation;
 use buck2_node::nodes::lookup::TargetNodeLookup;
 use buck2_node::nodes::unconfigured::TargetNode;
 use buck2_node::visibility::VisibilityError;
@@ -23,8 +32,11 @@ use buck2_server_ctx::ctx::ServerCommandContextTrait;
 use buck2_server_ctx::ctx::ServerCommandDiceContext;
 use buck2_server_ctx::partial_result_dispatcher::PartialResultDispatcher;
 use buck2_server_ctx::pattern::parse_patterns_from_cli_args;
+use dice::DiceComputations;
 use dice::DiceTransaction;
 use dupe::Dupe;
+use dupe::IterDupedExt;
+use futures::FutureExt;
 use gazebo::prelude::SliceExt;
 
 use crate::AuditSubcommand;
@@ -35,6 +47,149 @@ enum VisibilityCommandError {
         "Internal Error: The dependency `{0}` of the target `{1}` was not found during the traversal."
     )]
     DepNodeNotFound(String, String),
+    #[error("Found {0} visibility violations and {1} errors")]
+    #[buck2(user)]
+    Violations(usize, usize),
+}
+
+#[derive(serde::Serialize)]
+struct Violation {
+    target: TargetLabel,
+    dep: TargetLabel,
+    /// The `visibility` of the dependency.
+    visibility: serde_json::Value,
+}
+
+#[derive(Default, serde::Serialize)]
+struct PackageReport {
+    violations: Vec<Violation>,
+    /// Packages or dependencies which failed to load.
+    errors: Vec<String>,
+}
+
+/// Check every dependency edge in the transitive closure of the loaded targets, without stopping
+/// at the first violation or at targets which fail to load.
+///
+/// `within_view` is not checked here: a target which depends on something outside of its view
+/// fails to load, so it is reported as an error.
+async fn visibility_report(
+    ctx: &mut DiceComputations<'_>,
+    loaded: &LoadedPatterns<TargetPatternExtra>,
+) -> anyhow::Result<BTreeMap<PackageLabel, PackageReport>> {
+    let mut reports: BTreeMap<PackageLabel, PackageReport> = BTreeMap::new();
+    let mut nodes: BTreeMap<TargetLabel, TargetNode> = BTreeMap::new();
+    for (package, result) in loaded.iter() {
+        match result {
+            Ok(res) => nodes.extend(res.values().map(|n| (n.label().dupe(), n.to_owned()))),
+            Err(e) => reports
+                .entry(package)
+                .or_default()
+                .errors
+                .push(format!("{:#}", e)),
+        }
+    }
+
+    let mut failed: HashMap<TargetLabel, String> = HashMap::new();
+    let mut frontier: Vec<TargetNode> = nodes.values().duped().collect();
+    while !frontier.is_empty() {
+        let missing: BTreeSet<TargetLabel> = frontier
+            .iter()
+            .flat_map(|node| node.deps())
+            .filter(|dep| !nodes.contains_key(*dep) && !failed.contains_key(*dep))
+            .map(|dep| dep.dupe())
+            .collect();
+        let loaded: Vec<(TargetLabel, anyhow::Result<TargetNode>)> = ctx
+            .compute_join(missing, |ctx: &mut _, dep| {
+                async move {
+                    let node = ctx.get_target_node(&dep).await;
+                    (dep, node)
+                }
+                .boxed()
+            })
+            .await;
+        frontier.clear();
+        for (dep, node) in loaded {
+            match node {
+                Ok(node) => {
+                    nodes.insert(dep, node.dupe());
+                    frontier.push(node);
+                }
+                Err(e) => {
+                    failed.insert(dep, format!("{:#}", e));
+                }
+            }
+        }
+    }
+
+    check_edges(&nodes, &failed, &mut reports)?;
+    reports.retain(|_, report| !report.violations.is_empty() || !report.errors.is_empty());
+    Ok(reports)
+}
+
+/// Check the visibility of every dependency of `nodes`, which must contain every dependency that
+/// is not in `failed`.
+fn check_edges(
+    nodes: &BTreeMap<TargetLabel, TargetNode>,
+    failed: &HashMap<TargetLabel, String>,
+    reports: &mut BTreeMap<PackageLabel, PackageReport>,
+) -> anyhow::Result<()> {
+    for node in nodes.values() {
+        let report = reports.entry(node.label().pkg()).or_default();
+        let deps: BTreeSet<&TargetLabel> = node.deps().collect();
+        for dep in deps {
+            let dep_node = match nodes.get(dep) {
+                Some(dep_node) => dep_node,
+                None => {
+                    let error = failed.get(dep).ok_or_else(|| {
+                        VisibilityCommandError::DepNodeNotFound(
+                            dep.to_string(),
+                            node.label().to_string(),
+                        )
+                    })?;
+                    report.errors.push(format!(
+                        "Dependency `{}` of `{}` failed to load: {}",
+                        dep,
+                        node.label(),
+                        error
+                    ));
+                    continue;
+                }
+            };
+            if !dep_node.is_visible_to(node.label())? {
+                report.violations.push(Violation {
+                    target: node.label().dupe(),
+                    dep: dep.dupe(),
+                    visibility: dep_node.visibility()?.to_json(),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_report(
+    reports: &BTreeMap<PackageLabel, PackageReport>,
+    json: bool,
+    mut stdout: impl Write,
+) -> anyhow::Result<()> {
+    if json {
+        writeln!(stdout, "{}", serde_json::to_string_pretty(reports)?)?;
+        return Ok(());
+    }
+    for (package, report) in reports {
+        writeln!(stdout, "{}", package)?;
+        for violation in &report.violations {
+            writeln!(
+                stdout,
+                "  `{}` is not visible to `{}` (visibility: {})",
+                violation.dep, violation.target, violation.visibility
+            )?;
+        }
+        for error in &report.errors {
+            writeln!(stdout, "  error: {}", error)?;
+        }
+    }
+    Ok(())
 }
 
 async fn verify_visibility(
@@ -103,7 +258,7 @@ impl AuditSubcommand for AuditVisibilityCommand {
     async fn server_execute(
         &self,
         server_ctx: &dyn ServerCommandContextTrait,
-        _stdout: PartialResultDispatcher<buck2_cli_proto::StdoutBytes>,
+        mut stdout: PartialResultDispatcher<buck2_cli_proto::StdoutBytes>,
         _client_ctx: ClientContext,
     ) -> anyhow::Result<()> {
         server_ctx
@@ -120,6 +275,21 @@ impl AuditSubcommand for AuditVisibilityCommand {
                 let parsed_target_patterns =
                     load_patterns(&mut ctx, parsed_patterns, MissingTargetBehavior::Fail).await?;
 
+                if self.all {
+                    let reports = visibility_report(&mut ctx, &parsed_target_patterns).await?;
+                    let mut stdout = stdout.as_writer();
+                    write_report(&reports, self.json, &mut stdout)?;
+                    stdout.flush()?;
+
+                    let violations = reports.values().map(|r| r.violations.len()).sum();
+                    let errors = reports.values().map(|r| r.errors.len()).sum();
+                    if violations != 0 || errors != 0 {
+                        return Err(VisibilityCommandError::Violations(violations, errors).into());
+                    }
+                    buck2_client_ctx::eprintln!("audit visibility succeeded")?;
+                    return Ok(());
+                }
+
                 let mut nodes = TargetSet::<TargetNode>::new();
                 for (_package, result) in parsed_target_patterns.iter() {
                     let res = result.as_ref().map_err(Dupe::dupe)?;
@@ -132,3 +302,127 @@ impl AuditSubcommand for AuditVisibilityCommand {
             .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use buck2_core::bzl::ImportPath;
+    use buck2_core::plugins::PluginKindSet;
+    use buck2_core::provider::label::ProvidersLabel;
+    use buck2_core::provider::label::ProvidersName;
+    use buck2_core::target::name::TargetName;
+    use buck2_node::attrs::attr::Attribute;
+    use buck2_node::attrs::attr_type::list::ListLiteral;
+    use buck2_node::attrs::attr_type::AttrType;
+    use buck2_node::attrs::coerced_attr::CoercedAttr;
+    use buck2_node::nodes::unconfigured::testing::TargetNodeExt;
+    use buck2_node::provider_id_set::ProviderIdSet;
+    use buck2_node::rule_type::RuleType;
+    use buck2_node::rule_type::StarlarkRuleType;
+    use buck2_node::visibility::VisibilitySpecification;
+    use buck2_util::arc_str::ArcSlice;
+
+    use super::*;
+
+    fn label(target: &str) -> TargetLabel {
+        let (package, name) = target.split_once(':').unwrap();
+        TargetLabel::new(
+            PackageLabel::testing_parse(package),
+            TargetName::unchecked_new(name).as_ref(),
+        )
+    }
+
+    fn node(target: &str, visibility: &[&str], deps: &[&str]) -> TargetNode {
+        let rule_type = RuleType::Starlark(Arc::new(StarlarkRuleType {
+            import_path: ImportPath::testing_new("root//:defs.bzl"),
+            name: "some_rule".to_owned(),
+        }));
+        TargetNode::testing_new(
+            label(target),
+            rule_type,
+            vec![
+                (
+                    "visibility",
+                    Attribute::new(None, "", AttrType::visibility()),
+                    CoercedAttr::Visibility(VisibilitySpecification::testing_parse(visibility)),
+                ),
+                (
+                    "deps",
+                    Attribute::new(
+                        None,
+                        "",
+                        AttrType::list(AttrType::dep(ProviderIdSet::EMPTY, PluginKindSet::EMPTY)),
+                    ),
+                    CoercedAttr::List(ListLiteral(ArcSlice::from_iter(deps.iter().map(|dep| {
+                        CoercedAttr::Dep(ProvidersLabel::new(label(dep), ProvidersName::Default))
+                    })))),
+                ),
+            ],
+        )
+    }
+
+    fn report(nodes: &[TargetNode], failed: &[(&str, &str)]) -> String {
+        let nodes: BTreeMap<TargetLabel, TargetNode> =
+            nodes.iter().map(|n| (n.label().dupe(), n.dupe())).collect();
+        let failed: HashMap<TargetLabel, String> = failed
+            .iter()
+            .map(|(dep, error)| (label(dep), (*error).to_owned()))
+            .collect();
+        let mut reports: BTreeMap<PackageLabel, PackageReport> = BTreeMap::new();
+        check_edges(&nodes, &failed, &mut reports).unwrap();
+        reports.retain(|_, report| !report.violations.is_empty() || !report.errors.is_empty());
+        let mut out = Vec::new();
+        write_report(&reports, false, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_transitive_violations() {
+        assert_eq!(
+            "root//a\n\
+             \x20 `root//b:b` is not visible to `root//a:a` (visibility: [\"root//c:\"])\n\
+             root//b\n\
+             \x20 error: Dependency `root//d:d` of `root//b:b` failed to load: Boom\n\
+             root//c\n\
+             \x20 `root//e:e` is not visible to `root//c:c` (visibility: [])\n",
+            report(
+                &[
+                    node("root//a:a", &["PUBLIC"], &["root//b:b", "root//c:c"]),
+                    node("root//b:b", &["root//c:"], &["root//d:d"]),
+                    node(
+                        "root//c:c",
+                        &["PUBLIC"],
+                        &["root//b:b", "root//c:c2", "root//e:e"]
+                    ),
+                    node("root//c:c2", &[], &[]),
+                    node("root//e:e", &[], &[]),
+                ],
+                &[("root//d:d", "Boom")],
+            )
+        );
+    }
+
+    #[test]
+    fn test_no_violations() {
+        assert_eq!(
+            "",
+            report(
+                &[
+                    node("root//a:a", &["PUBLIC"], &["root//a:b"]),
+                    node("root//a:b", &[], &[]),
+                ],
+                &[],
+            )
+        );
+    }
+
+    #[test]
+    fn test_dep_not_found() {
+        let nodes: BTreeMap<TargetLabel, TargetNode> = [node("root//a:a", &[], &["root//b:b"])]
+            .into_iter()
+            .map(|n| (n.label().dupe(), n))
+            .collect();
+        assert!(check_edges(&nodes, &HashMap::new(), &mut BTreeMap::new()).is_err());
+    }
+}
@@ -23,6 +23,7 @@ use buck2_common::liveliness_observer::NoopLivelinessObserver;
 use buck2_core::execution_types::executor_config::CommandExecutorConfig;
 use buck2_core::fs::artifact_path_resolver::ArtifactFs;
 use buck2_core::fs::buck_out_path::BuckOutPath;
+use buck2_core::fs::project_rel_path::ProjectRelativePathBuf;
 use buck2_events::dispatch::EventDispatcher;
 use buck2_execute::artifact::fs::ExecutorFs;
 use buck2_execute::artifact_value::ArtifactValue;
@@ -39,15 +40,18 @@ use buck2_execute::execute::clean_output_paths::CleanOutputPaths;
 use buck2_execute::execute::command_executor::ActionExecutionTimingData;
 use buck2_execute::execute::command_executor::CommandExecutor;
 use buck2_execute::execute::dep_file_digest::DepFileDigest;
+use buck2_execute::execute::input_manifest::action_input_manifest;
 use buck2_execute::execute::kind::CommandExecutionKind;
 use buck2_execute::execute::manager::CommandExecutionManager;
 use buck2_execute::execute::prepared::PreparedAction;
 use buck2_execute::execute::prepared::PreparedCommand;
 use buck2_execute::execute::request::CommandExecutionRequest;
+use buck2_execute::execute::request::ExecutorPreference;
 use buck2_execute::execute::request::OutputType;
 use buck2_execute::execute::result::CommandExecutionReport;
 use buck2_execute::execute::result::CommandExecutionResult;
 use buck2_execute::execute::result::CommandExecutionStatus;
+use buck2_execute::execute::target::CommandExecutionTarget;
 use buck2_execute::materialize::materializer::HasMaterializer;
 use buck2_execute::materialize::materializer::Materializer;
 use buck2_execute::output_size::OutputCountAndBytes;
@@ -67,6 +71,12 @@ use itertools::Itertools;
 
 use crate::actions::artifact::get_artifact_fs::GetArtifactFs;
 use crate::actions::execute::action_execution_target::ActionExecutionTarget;
+use crate::actions::execute::determinism::diff_outputs;
+use crate::actions::execute::determinism::first_execution_path;
+use crate::actions::execute::determinism::DeterminismChecker;
+use crate::actions::execute::determinism::HasDeterminismChecker;
+use crate::actions::execute::determinism::MoveFirstExecutionOutputs;
+use crate::actions::execute::determinism::NondeterministicAction;
 use crate::actions::execute::dice_data::CommandExecutorResponse;
 use crate::actions::execute::dice_data::DiceHasCommandExecutor;
 use crate::actions::execute::dice_data::GetReClient;
@@ -255,6 +265,7 @@ impl HasActionExecutor for DiceComputations<'_> {
         let io_provider = self.global_data().get_io_provider();
         let http_client = self.per_transaction_data().get_http_client();
         let mergebase = self.per_transaction_data().get_mergebase();
+        let determinism_checker = self.per_transaction_data().get_determinism_checker();
 
         Ok(Arc::new(BuckActionExecutor::new(
             CommandExecutor::new(
@@ -274,6 +285,7 @@ impl HasActionExecutor for DiceComputations<'_> {
             io_provider,
             http_client,
             mergebase,
+            determinism_checker,
         )))
     }
 }
@@ -289,6 +301,7 @@ pub struct BuckActionExecutor {
     io_provider: Arc<dyn IoProvider>,
     http_client: HttpClient,
     mergebase: Mergebase,
+    determinism_checker: Option<Arc<DeterminismChecker>>,
 }
 
 impl BuckActionExecutor {
@@ -303,6 +316,7 @@ impl BuckActionExecutor {
         io_provider: Arc<dyn IoProvider>,
         http_client: HttpClient,
         mergebase: Mergebase,
+        determinism_checker: Option<Arc<DeterminismChecker>>,
     ) -> Self {
         Self {
             command_executor,
@@ -315,6 +329,7 @@ impl BuckActionExecutor {
             io_provider,
             http_client,
             mergebase,
+            determinism_checker,
         }
     }
 }
@@ -390,9 +405,19 @@ impl ActionExecutionCtx for BuckActionExecutionContext<'_> {
         &mut self,
         request: &CommandExecutionRequest,
     ) -> anyhow::Result<PreparedAction> {
-        self.executor
+        let prepared_action = self
+            .executor
             .command_executor
-            .prepare_action(request, self.digest_config())
+            .prepare_action(request, self.digest_config())?;
+        if self.run_action_knobs().log_action_input_manifests {
+            self.executor.events.instant_event(action_input_manifest(
+                self.fs(),
+                &self.target(),
+                request,
+                &prepared_action,
+            ));
+        }
+        Ok(prepared_action)
     }
 
     async fn action_cache(
@@ -401,6 +426,10 @@ impl ActionExecutionCtx for BuckActionExecutionContext<'_> {
         request: &CommandExecutionRequest,
         prepared_action: &PreparedAction,
     ) -> ControlFlow<CommandExecutionResult, CommandExecutionManager> {
+        // Checking determinism requires actually running the command.
+        if self.determinism_checker().is_some() {
+            return ControlFlow::Continue(manager);
+        }
         let action = self.target();
         self.executor
             .command_executor
@@ -477,20 +506,13 @@ impl ActionExecutionCtx for BuckActionExecutionContext<'_> {
         request: &CommandExecutionRequest,
         prepared_action: &PreparedAction,
     ) -> CommandExecutionResult {
-        let action = self.target();
-        self.executor
-            .command_executor
-            .exec_cmd(
-                manager,
-                &PreparedCommand {
-                    target: &action as _,
-                    request,
-                    prepared_action,
-                    digest_config: self.digest_config(),
-                },
-                self.cancellations,
-            )
-            .await
+        match self.determinism_checker() {
+            Some(checker) => {
+                self.exec_cmd_checking_determinism(&checker, manager, request, prepared_action)
+                    .await
+            }
+            None => self.exec_cmd_once(manager, request, prepared_action).await,
+        }
     }
 
     async fn cache_upload(
@@ -564,6 +586,184 @@ impl ActionExecutionCtx for BuckActionExecutionContext<'_> {
     }
 }
 
+impl BuckActionExecutionContext<'_> {
+    /// The determinism checker, if this action should be checked.
+    fn determinism_checker(&self) -> Option<Arc<DeterminismChecker>> {
+        self.executor
+            .determinism_checker
+            .as_ref()
+            .filter(|checker| checker.should_check(self.action.category().as_str()))
+            .map(|checker| checker.dupe())
+    }
+
+    /// Move the outputs of the first execution of a command to their `first_execution_path`,
+    /// returning the pairs of output paths and where they were moved.
+    async fn move_first_execution_outputs(
+        &self,
+    ) -> anyhow::Result<Vec<(ProjectRelativePathBuf, ProjectRelativePathBuf)>> {
+        let buck_out = self.fs().buck_out_path_resolver().root();
+        let paths: Vec<_> = self
+            .outputs
+            .iter()
+            .map(|o| {
+                let path = self.fs().resolve_build(o.get_path());
+                let first = first_execution_path(buck_out, &path);
+                (path, first)
+            })
+            .collect();
+        // The materializer knows about the outputs of the first execution, which are gone now.
+        self.executor
+            .materializer
+            .invalidate_many(paths.iter().map(|(path, _)| path.clone()).collect())
+            .await
+            .context("Failed to invalidate output directory")?;
+        self.executor
+            .blocking_executor
+            .execute_io(
+                Box::new(MoveFirstExecutionOutputs {
+                    paths: paths.clone(),
+                }),
+                self.cancellations,
+            )
+            .await
+            .context("Failed to move the outputs of the first execution")?;
+        Ok(paths)
+    }
+
+    async fn exec_cmd_once(
+        &mut self,
+        manager: CommandExecutionManager,
+        request: &CommandExecutionRequest,
+        prepared_action: &PreparedAction,
+    ) -> CommandExecutionResult {
+        let action = self.target();
+        self.executor
+            .command_executor
+            .exec_cmd(
+                manager,
+                &PreparedCommand {
+                    target: &action as _,
+                    request,
+                    prepared_action,
+                    digest_config: self.digest_config(),
+                },
+                self.cancellations,
+            )
+            .await
+    }
+
+    /// Execute the command twice locally and record the action if the outputs differ. The outputs
+    /// of the first execution are moved to scratch paths, so that the second execution starts from
+    /// clean output paths; they are kept only if the outputs differ. The result of the second
+    /// execution is returned, since its outputs are the ones left at the output paths.
+    async fn exec_cmd_checking_determinism(
+        &mut self,
+        checker: &DeterminismChecker,
+        manager: CommandExecutionManager,
+        request: &CommandExecutionRequest,
+        prepared_action: &PreparedAction,
+    ) -> CommandExecutionResult {
+        let local_request = match request
+            .executor_preference()
+            .and(ExecutorPreference::LocalRequired)
+        {
+            Ok(preference)
+                if self
+                    .executor
+                    .command_executor
+                    .is_local_execution_possible(preference) =>
+            {
+                request.clone().with_executor_preference(preference)
+            }
+            // This command can't run locally, so there is nothing to compare against.
+            _ => {
+                let action = self.target().re_action_key();
+                self.executor.events.console_warning(format!(
+                    "Action `{}` was not checked for determinism: it cannot run locally",
+                    action
+                ));
+                checker.record_unchecked(action);
+                return self.exec_cmd_once(manager, request, prepared_action).await;
+            }
+        };
+
+        let first = self
+            .exec_cmd_once(manager, &local_request, prepared_action)
+            .await;
+        if !first.was_success() {
+            return first;
+        }
+        let moved = match self.move_first_execution_outputs().await {
+            Ok(moved) => moved,
+            Err(e) => {
+                let action = self.target().re_action_key();
+                self.executor.events.console_warning(format!(
+                    "Action `{}` was not checked for determinism: {:#}",
+                    action, e
+                ));
+                checker.record_unchecked(action);
+                return first;
+            }
+        };
+        let second = self
+            .exec_cmd_once(
+                self.command_execution_manager(),
+                &local_request,
+                prepared_action,
+            )
+            .await;
+        if !second.was_success() {
+            return second;
+        }
+
+        let outputs = diff_outputs(self.fs(), &first.outputs, &second.outputs);
+        // Keep the report of the first execution so it shows up in the event log too.
+        self.command_reports.extend(first.rejected_execution);
+        self.command_reports.push(first.report);
+
+        if !outputs.is_empty() {
+            let target = self.target();
+            let action = target.re_action_key();
+            self.executor
+                .events
+                .instant_event(buck2_data::ActionNondeterminism {
+                    key: Some(target.as_proto_action_key()),
+                    name: Some(target.as_proto_action_name()),
+                    outputs: outputs.iter().map(|o| o.to_proto()).collect(),
+                });
+            self.executor.events.console_warning(format!(
+                "Action `{}` is not deterministic: {} output file(s) differ between two executions, \
+                the outputs of the first execution are in `{}`",
+                action,
+                outputs.len(),
+                moved
+                    .iter()
+                    .map(|(_, first)| first.to_string())
+                    .collect::<Vec<_>>()
+                    .join("`, `")
+            ));
+            checker.record(NondeterministicAction { action, outputs });
+        } else if let Err(e) = self
+            .executor
+            .blocking_executor
+            .execute_io(
+                Box::new(CleanOutputPaths {
+                    paths: moved.into_iter().map(|(_, first)| first).collect(),
+                }),
+                self.cancellations,
+            )
+            .await
+        {
+            tracing::warn!(
+                "Failed to clean up the outputs of the first execution: {:#}",
+                e
+            );
+        }
+
+        second
+    }
+}
+
 #[async_trait]
 impl ActionExecutor for BuckActionExecutor {
     async fn execute(
@@ -732,6 +932,7 @@ mod tests {
     use crate::actions::execute::action_executor::ActionExecutor;
     use crate::actions::execute::action_executor::ActionOutputs;
     use crate::actions::execute::action_executor::BuckActionExecutor;
+    use crate::actions::execute::determinism::DeterminismChecker;
     use crate::actions::key::ActionKeyExt;
     use crate::actions::Action;
     use crate::actions::ActionExecutable;
@@ -742,8 +943,9 @@ mod tests {
     use crate::artifact_groups::ArtifactGroup;
     use crate::artifact_groups::ArtifactGroupValues;
 
-    #[tokio::test]
-    async fn can_execute_some_action() {
+    /// Execute an action running a single command with the dry run executor,
+    /// returning how many times the command was run.
+    async fn execute_some_action(determinism_checker: Option<Arc<DeterminismChecker>>) -> usize {
         let cells = CellResolver::testing_with_name_and_path(
             CellName::testing_new("cell"),
             CellRootPathBuf::new(ProjectRelativePathBuf::unchecked_new("cell_path".into())),
@@ -764,7 +966,7 @@ mod tests {
 
         let executor = BuckActionExecutor::new(
             CommandExecutor::new(
-                Arc::new(DryRunExecutor::new(tracker, artifact_fs.clone())),
+                Arc::new(DryRunExecutor::new(tracker.dupe(), artifact_fs.clone())),
                 Arc::new(NoOpCommandOptionalExecutor {}),
                 Arc::new(NoOpCacheUploader {}),
                 artifact_fs,
@@ -790,6 +992,7 @@ mod tests {
                 .unwrap()
                 .build(),
             Default::default(),
+            determinism_checker,
         );
 
         #[derive(Debug, Allocative)]
@@ -946,6 +1149,24 @@ mod tests {
             })
             .collect();
         assert_eq!(res.0, ActionOutputs::new(outputs));
+        let runs = tracker.lock().unwrap().len();
+        runs
+    }
+
+    #[tokio::test]
+    async fn can_execute_some_action() {
+        assert_eq!(1, execute_some_action(None).await);
+    }
+
+    #[tokio::test]
+    async fn can_check_determinism() {
+        let checker = Arc::new(DeterminismChecker::new(Vec::new()));
+        assert_eq!(2, execute_some_action(Some(checker.dupe())).await);
+        assert_eq!(Vec::<String>::new(), checker.unchecked_actions());
+        assert!(checker.nondeterministic_actions().is_empty());
+
+        let checker = Arc::new(DeterminismChecker::new(vec!["other".to_owned()]));
+        assert_eq!(1, execute_some_action(Some(checker)).await);
     }
 
     #[test]
@@ -0,0 +1,295 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Support for `buck2 build --check-determinism`: commands are executed twice locally and the
+//! digests of their outputs are compared. The outputs of the first execution are moved to
+//! `buck-out/v2/tmp/check_determinism/` before the second execution, so that the second
+//! execution starts from clean output paths and the differing files can be inspected.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use buck2_core::directory::unordered_entry_walk;
+use buck2_core::directory::DirectoryEntry;
+use buck2_core::fs::artifact_path_resolver::ArtifactFs;
+use buck2_core::fs::fs_util;
+use buck2_core::fs::paths::forward_rel_path::ForwardRelativePath;
+use buck2_core::fs::project::ProjectRoot;
+use buck2_core::fs::project_rel_path::ProjectRelativePath;
+use buck2_core::fs::project_rel_path::ProjectRelativePathBuf;
+use buck2_execute::artifact_value::ArtifactValue;
+use buck2_execute::directory::ActionDirectoryMember;
+use buck2_execute::execute::blocking::IoRequest;
+use buck2_execute::execute::request::CommandExecutionOutput;
+use dice::UserComputationData;
+use dupe::Dupe;
+use indexmap::IndexMap;
+use serde::Serialize;
+
+/// An output file whose contents differed between two executions of the same command.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NondeterministicOutput {
+    /// Path relative to the project root.
+    pub path: String,
+    /// Digest (or symlink target) produced by the first execution, if it produced this file.
+    pub first: Option<String>,
+    /// Digest (or symlink target) produced by the second execution, if it produced this file.
+    pub second: Option<String>,
+}
+
+impl NondeterministicOutput {
+    pub fn to_proto(&self) -> buck2_data::NondeterministicOutput {
+        buck2_data::NondeterministicOutput {
+            path: self.path.clone(),
+            first: self.first.clone(),
+            second: self.second.clone(),
+        }
+    }
+}
+
+/// An action for which executing the same command twice produced different outputs.
+#[derive(Debug, Clone, Serialize)]
+pub struct NondeterministicAction {
+    pub action: String,
+    pub outputs: Vec<NondeterministicOutput>,
+}
+
+/// Collects the nondeterministic actions found during a command. Its presence in the per
+/// transaction data is what enables the determinism check.
+pub struct DeterminismChecker {
+    /// Categories of the actions to check, all actions are checked if empty.
+    categories: Vec<String>,
+    nondeterministic: Mutex<Vec<NondeterministicAction>>,
+    /// Actions which should have been checked, but could not be executed locally.
+    unchecked: Mutex<Vec<String>>,
+}
+
+impl DeterminismChecker {
+    pub fn new(categories: Vec<String>) -> Self {
+        Self {
+            categories,
+            nondeterministic: Mutex::new(Vec::new()),
+            unchecked: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Whether actions of this category are checked. Those which are not use the action cache
+    /// and are executed once.
+    pub fn should_check(&self, category: &str) -> bool {
+        self.categories.is_empty() || self.categories.iter().any(|c| c == category)
+    }
+
+    pub fn record(&self, action: NondeterministicAction) {
+        self.nondeterministic.lock().unwrap().push(action);
+    }
+
+    pub fn record_unchecked(&self, action: String) {
+        self.unchecked.lock().unwrap().push(action);
+    }
+
+    /// The actions which could not be checked so far, sorted.
+    pub fn unchecked_actions(&self) -> Vec<String> {
+        let mut actions = self.unchecked.lock().unwrap().clone();
+        actions.sort();
+        actions
+    }
+
+    /// The nondeterministic actions found so far, sorted by action.
+    pub fn nondeterministic_actions(&self) -> Vec<NondeterministicAction> {
+        let mut actions = self.nondeterministic.lock().unwrap().clone();
+        actions.sort_by(|a, b| a.action.cmp(&b.action));
+        actions
+    }
+}
+
+pub trait HasDeterminismChecker {
+    fn set_determinism_checker(&mut self, checker: Arc<DeterminismChecker>);
+
+    fn get_determinism_checker(&self) -> Option<Arc<DeterminismChecker>>;
+}
+
+impl HasDeterminismChecker for UserComputationData {
+    fn set_determinism_checker(&mut self, checker: Arc<DeterminismChecker>) {
+        self.data.set(checker);
+    }
+
+    fn get_determinism_checker(&self) -> Option<Arc<DeterminismChecker>> {
+        self.data
+            .get::<Arc<DeterminismChecker>>()
+            .ok()
+            .map(|c| c.dupe())
+    }
+}
+
+/// Where the output at `path` is kept after the first execution:
+/// `buck-out/v2/tmp/check_determinism/` followed by the path of the output within buck-out.
+pub fn first_execution_path(
+    buck_out: &ProjectRelativePath,
+    path: &ProjectRelativePath,
+) -> ProjectRelativePathBuf {
+    let scratch = buck_out.join(ForwardRelativePath::unchecked_new("tmp/check_determinism"));
+    match path.strip_prefix_opt(buck_out) {
+        Some(path) => scratch.join(path),
+        None => scratch.join(path.as_forward_relative_path()),
+    }
+}
+
+/// Moves the outputs of the first execution of a command out of the way of the second execution.
+pub struct MoveFirstExecutionOutputs {
+    /// Pairs of output paths and the paths to move them to.
+    pub paths: Vec<(ProjectRelativePathBuf, ProjectRelativePathBuf)>,
+}
+
+impl IoRequest for MoveFirstExecutionOutputs {
+    fn execute(self: Box<Self>, project_fs: &ProjectRoot) -> anyhow::Result<()> {
+        for (from, to) in &self.paths {
+            project_fs.remove_path_recursive(to)?;
+            let from = project_fs.resolve(from);
+            if fs_util::symlink_metadata_if_exists(&from)?.is_none() {
+                continue;
+            }
+            let to = project_fs.resolve(to);
+            if let Some(parent) = to.parent() {
+                fs_util::create_dir_all(parent)?;
+            }
+            fs_util::rename(&from, &to)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compare the outputs of two executions of the same command, file by file.
+pub fn diff_outputs(
+    fs: &ArtifactFs,
+    first: &IndexMap<CommandExecutionOutput, ArtifactValue>,
+    second: &IndexMap<CommandExecutionOutput, ArtifactValue>,
+) -> Vec<NondeterministicOutput> {
+    diff_leaves(&output_leaves(fs, first), &output_leaves(fs, second))
+}
+
+/// Map every file (or symlink) in the outputs to its digest (or target).
+fn output_leaves(
+    fs: &ArtifactFs,
+    outputs: &IndexMap<CommandExecutionOutput, ArtifactValue>,
+) -> BTreeMap<String, String> {
+    let mut leaves = BTreeMap::new();
+    for (output, value) in outputs {
+        let output_path = output.as_ref().resolve(fs).into_path();
+        let mut walk = unordered_entry_walk(value.entry().as_ref());
+        while let Some((path, entry)) = walk.next() {
+            let leaf = match entry {
+                DirectoryEntry::Dir(_) => continue,
+                DirectoryEntry::Leaf(ActionDirectoryMember::File(meta)) => meta.digest.to_string(),
+                DirectoryEntry::Leaf(ActionDirectoryMember::Symlink(symlink)) => {
+                    format!("-> {}", symlink.target())
+                }
+                DirectoryEntry::Leaf(ActionDirectoryMember::ExternalSymlink(symlink)) => {
+                    format!("-> {}", symlink)
+                }
+            };
+            let path = path.get();
+            let path = if path.is_empty() {
+                output_path.to_string()
+            } else {
+                output_path.join(&path).to_string()
+            };
+            leaves.insert(path, leaf);
+        }
+    }
+    leaves
+}
+
+fn diff_leaves(
+    first: &BTreeMap<String, String>,
+    second: &BTreeMap<String, String>,
+) -> Vec<NondeterministicOutput> {
+    let mut paths: Vec<&String> = first.keys().chain(second.keys()).collect();
+    paths.sort();
+    paths.dedup();
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let (first, second) = (first.get(path), second.get(path));
+            if first == second {
+                return None;
+            }
+            Some(NondeterministicOutput {
+                path: path.clone(),
+                first: first.cloned(),
+                second: second.cloned(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaves(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+        entries
+            .iter()
+            .map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
+            .collect()
+    }
+
+    #[test]
+    fn test_should_check() {
+        let checker = DeterminismChecker::new(Vec::new());
+        assert!(checker.should_check("cxx_compile"));
+        let checker = DeterminismChecker::new(vec!["cxx_compile".to_owned()]);
+        assert!(checker.should_check("cxx_compile"));
+        assert!(!checker.should_check("cxx_link"));
+    }
+
+    #[test]
+    fn test_first_execution_path() {
+        let buck_out = ProjectRelativePath::unchecked_new("buck-out/v2");
+        assert_eq!(
+            ProjectRelativePath::unchecked_new(
+                "buck-out/v2/tmp/check_determinism/gen/root/abc/__foo__/out.txt"
+            ),
+            first_execution_path(
+                buck_out,
+                ProjectRelativePath::unchecked_new("buck-out/v2/gen/root/abc/__foo__/out.txt")
+            )
+        );
+    }
+
+    #[test]
+    fn test_diff_leaves() {
+        let first = leaves(&[("out/a", "1:1"), ("out/b", "2:2"), ("out/c", "3:3")]);
+        let second = leaves(&[("out/a", "1:1"), ("out/b", "4:4"), ("out/d", "5:5")]);
+        assert_eq!(
+            vec![
+                NondeterministicOutput {
+                    path: "out/b".to_owned(),
+                    first: Some("2:2".to_owned()),
+                    second: Some("4:4".to_owned()),
+                },
+                NondeterministicOutput {
+                    path: "out/c".to_owned(),
+                    first: Some("3:3".to_owned()),
+                    second: None,
+                },
+                NondeterministicOutput {
+                    path: "out/d".to_owned(),
+                    first: None,
+                    second: Some("5:5".to_owned()),
+                },
+            ],
+            diff_leaves(&first, &second)
+        );
+        assert_eq!(
+            Vec::<NondeterministicOutput>::new(),
+            diff_leaves(&first, &first)
+        );
+    }
+}
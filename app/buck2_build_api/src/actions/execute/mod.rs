@@ -9,5 +9,6 @@
 
 pub mod action_execution_target;
 pub mod action_executor;
+pub mod determinism;
 pub mod dice_data;
 pub mod error;
@@ -24,6 +24,10 @@ pub struct RunActionKnobs {
     /// for network actions (download_file, cas_artifact). Used to support offline
     /// builds.
     pub use_network_action_output_cache: bool,
+
+    /// Emit an `ActionInputManifest` event (every input path and digest, the env and the argv)
+    /// for each command we prepare. This is verbose and only meant for debugging cache misses.
+    pub log_action_input_manifests: bool,
 }
 
 pub trait HasRunActionKnobs {
@@ -45,6 +45,8 @@ use itertools::Itertools;
 use serde::Serialize;
 use starlark_map::small_set::SmallSet;
 
+use crate::actions::execute::determinism::DeterminismChecker;
+use crate::actions::execute::determinism::NondeterministicAction;
 use crate::build::action_error::BuildReportActionError;
 use crate::build::BuildProviderType;
 use crate::build::ConfiguredBuildTargetResult;
@@ -75,6 +77,14 @@ pub struct BuildReport {
     project_root: AbsNormPathBuf,
     truncated: bool,
     strings: BTreeMap<String, String>,
+    /// Actions that produced different outputs when executed twice. Only filled with
+    /// `--check-determinism`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    nondeterministic_actions: Vec<NondeterministicAction>,
+    /// Actions that should have been checked with `--check-determinism`, but could not be
+    /// executed twice locally.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    determinism_unchecked_actions: Vec<String>,
 }
 
 /// The fields that stored in the unconfigured `BuildReportEntry` for buck1 backcompat.
@@ -246,6 +256,8 @@ impl<'a> BuildReportCollector<'a> {
             // Setting this to false since we don't currently truncate buck2's build report.
             truncated: false,
             strings: this.strings,
+            nondeterministic_actions: Vec::new(),
+            determinism_unchecked_actions: Vec::new(),
         }
     }
 
@@ -543,8 +555,9 @@ pub fn generate_build_report(
     trace_id: &TraceId,
     configured: &BTreeMap<ConfiguredProvidersLabel, Option<ConfiguredBuildTargetResult>>,
     other_errors: &BTreeMap<Option<ProvidersLabel>, Vec<buck2_error::Error>>,
+    determinism_checker: Option<&DeterminismChecker>,
 ) -> Result<Option<String>, buck2_error::Error> {
-    let build_report = BuildReportCollector::convert(
+    let mut build_report = BuildReportCollector::convert(
         trace_id,
         artifact_fs,
         cell_resolver,
@@ -556,6 +569,10 @@ pub fn generate_build_report(
         configured,
         other_errors,
     );
+    if let Some(checker) = determinism_checker {
+        build_report.nondeterministic_actions = checker.nondeterministic_actions();
+        build_report.determinism_unchecked_actions = checker.unchecked_actions();
+    }
 
     let mut serialized_build_report = None;
 
@@ -187,6 +187,10 @@ impl FrozenBxlFunction {
         self.implementation
     }
 
+    pub(crate) fn bxl_id(&self) -> &Arc<BxlFunctionLabel> {
+        &self.bxl_id
+    }
+
     pub(crate) fn to_clap<'v>(&'v self, mut clap: clap::Command<'v>) -> clap::Command<'v> {
         if let Some(docs) = self.docs.as_ref() {
             clap = clap.about(docs.as_str())
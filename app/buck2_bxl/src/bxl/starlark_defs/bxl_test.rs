@@ -0,0 +1,235 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! `bxl_test()`, which declares a test of a bxl function, run by `buck2 bxl --test`.
+
+use std::fmt;
+use std::fmt::Display;
+use std::sync::Arc;
+
+use allocative::Allocative;
+use buck2_build_api::bxl::types::BxlFunctionLabel;
+use buck2_interpreter::build_context::starlark_path_from_build_context;
+use dupe::Dupe;
+use starlark::any::ProvidesStaticType;
+use starlark::environment::GlobalsBuilder;
+use starlark::eval::Evaluator;
+use starlark::starlark_module;
+use starlark::starlark_simple_value;
+use starlark::values::list_or_tuple::UnpackListOrTuple;
+use starlark::values::none::NoneOr;
+use starlark::values::starlark_value;
+use starlark::values::AllocValue;
+use starlark::values::Freeze;
+use starlark::values::Freezer;
+use starlark::values::FrozenValue;
+use starlark::values::Heap;
+use starlark::values::NoSerialize;
+use starlark::values::StarlarkValue;
+use starlark::values::Trace;
+use starlark::values::Value;
+
+use crate::bxl::starlark_defs::bxl_function::BxlFunction;
+use crate::bxl::starlark_defs::bxl_function::FrozenBxlFunction;
+
+#[derive(Debug, buck2_error::Error)]
+pub(crate) enum BxlTestError {
+    #[error("`bxl_test` can only be declared in bxl files")]
+    NotInBxlFile,
+    #[error(
+        "`bxl` argument of `bxl_test` must be a bxl function declared with `bxl_main`, got `{0}`"
+    )]
+    NotABxlFunction(String),
+    #[error("Expected output:\n{expected}\nActual output:\n{actual}")]
+    OutputMismatch { expected: String, actual: String },
+    #[error("No ensured artifact path ends with `{expected}`. Ensured artifacts: [{actual}]")]
+    MissingArtifact { expected: String, actual: String },
+}
+
+/// What a `bxl_test` runs and what it expects.
+#[derive(Debug, Allocative)]
+pub(crate) struct BxlTestSpec {
+    /// The command line arguments passed to the bxl function, as they would be after `--`.
+    pub(crate) args: Vec<String>,
+    /// The exact expected contents of `ctx.output`, if checked.
+    expected_output: Option<String>,
+    /// Suffixes of project relative paths of artifacts that the function must ensure.
+    expected_artifacts: Vec<String>,
+}
+
+impl BxlTestSpec {
+    /// Check the output and the ensured artifacts of a run of the bxl function.
+    pub(crate) fn check(&self, output: &str, artifacts: &[String]) -> anyhow::Result<()> {
+        if let Some(expected) = &self.expected_output {
+            if expected != output {
+                return Err(BxlTestError::OutputMismatch {
+                    expected: expected.clone(),
+                    actual: output.to_owned(),
+                }
+                .into());
+            }
+        }
+        for expected in &self.expected_artifacts {
+            if !artifacts.iter().any(|a| a.ends_with(expected.as_str())) {
+                return Err(BxlTestError::MissingArtifact {
+                    expected: expected.clone(),
+                    actual: artifacts.join(", "),
+                }
+                .into());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The value created by `bxl_test()`.
+#[derive(Debug, ProvidesStaticType, Trace, NoSerialize, Allocative)]
+pub(crate) struct BxlTest<'v> {
+    /// The bxl function under test.
+    bxl: Value<'v>,
+    #[trace(unsafe_ignore)]
+    spec: Arc<BxlTestSpec>,
+}
+
+impl<'v> Display for BxlTest<'v> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "bxl_test({})", self.bxl)
+    }
+}
+
+impl<'v> AllocValue<'v> for BxlTest<'v> {
+    fn alloc_value(self, heap: &'v Heap) -> Value<'v> {
+        heap.alloc_complex(self)
+    }
+}
+
+#[starlark_value(type = "bxl_test")]
+impl<'v> StarlarkValue<'v> for BxlTest<'v> {}
+
+impl<'v> Freeze for BxlTest<'v> {
+    type Frozen = FrozenBxlTest;
+    fn freeze(self, freezer: &Freezer) -> anyhow::Result<Self::Frozen> {
+        Ok(FrozenBxlTest {
+            bxl: self.bxl.freeze(freezer)?,
+            spec: self.spec,
+        })
+    }
+}
+
+#[derive(Debug, ProvidesStaticType, NoSerialize, Allocative)]
+pub(crate) struct FrozenBxlTest {
+    bxl: FrozenValue,
+    spec: Arc<BxlTestSpec>,
+}
+starlark_simple_value!(FrozenBxlTest);
+
+impl Display for FrozenBxlTest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "bxl_test({})", self.bxl)
+    }
+}
+
+#[starlark_value(type = "bxl_test")]
+impl<'v> StarlarkValue<'v> for FrozenBxlTest {
+    type Canonical = BxlTest<'v>;
+}
+
+impl FrozenBxlTest {
+    /// The label of the bxl function under test.
+    pub(crate) fn bxl_label(&self) -> anyhow::Result<Arc<BxlFunctionLabel>> {
+        Ok(self
+            .bxl
+            .to_value()
+            .downcast_ref::<FrozenBxlFunction>()
+            .ok_or_else(|| BxlTestError::NotABxlFunction(self.bxl.to_string()))?
+            .bxl_id()
+            .dupe())
+    }
+
+    pub(crate) fn spec(&self) -> &BxlTestSpec {
+        &self.spec
+    }
+}
+
+#[starlark_module]
+pub(crate) fn register_bxl_test(builder: &mut GlobalsBuilder) {
+    /// Declares a test of a bxl function, which `buck2 bxl --test path/to/file.bxl` runs along
+    /// with every other test assigned to a global in that file.
+    ///
+    /// The test runs `bxl` with `args` as its command line arguments (as they would be passed after
+    /// `--`), typically pointing at targets in a fixture cell. It passes if the function succeeds,
+    /// its `ctx.output` is exactly `expected_output` (when given), and, for each entry of
+    /// `expected_artifacts`, an ensured artifact's project relative path ends with that entry.
+    ///
+    /// Sample usage:
+    /// ```text
+    /// main = bxl_main(impl = _impl, cli_args = {"target": cli_args.target_label()})
+    ///
+    /// test_main = bxl_test(
+    ///     bxl = main,
+    ///     args = ["--target", "fixtures//:lib"],
+    ///     expected_output = "fixtures//:lib\n",
+    /// )
+    /// ```
+    fn bxl_test<'v>(
+        #[starlark(require = named)] bxl: Value<'v>,
+        #[starlark(require = named, default = UnpackListOrTuple::default())]
+        args: UnpackListOrTuple<String>,
+        #[starlark(require = named, default = NoneOr::None)] expected_output: NoneOr<String>,
+        #[starlark(require = named, default = UnpackListOrTuple::default())]
+        expected_artifacts: UnpackListOrTuple<String>,
+        eval: &mut Evaluator<'v, '_, '_>,
+    ) -> anyhow::Result<BxlTest<'v>> {
+        if starlark_path_from_build_context(eval)?
+            .unpack_bxl_file()
+            .is_none()
+        {
+            return Err(BxlTestError::NotInBxlFile.into());
+        }
+        if bxl.downcast_ref::<BxlFunction>().is_none()
+            && bxl.downcast_ref::<FrozenBxlFunction>().is_none()
+        {
+            return Err(BxlTestError::NotABxlFunction(bxl.to_repr()).into());
+        }
+
+        Ok(BxlTest {
+            bxl,
+            spec: Arc::new(BxlTestSpec {
+                args: args.items,
+                expected_output: expected_output.into_option(),
+                expected_artifacts: expected_artifacts.items,
+            }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let spec = BxlTestSpec {
+            args: Vec::new(),
+            expected_output: Some("hello\n".to_owned()),
+            expected_artifacts: vec!["foo/out.txt".to_owned()],
+        };
+        let artifacts = vec!["buck-out/v2/gen/root/123/foo/out.txt".to_owned()];
+        assert!(spec.check("hello\n", &artifacts).is_ok());
+        assert!(spec.check("hello", &artifacts).is_err());
+        assert!(spec.check("hello\n", &[]).is_err());
+
+        let spec = BxlTestSpec {
+            args: Vec::new(),
+            expected_output: None,
+            expected_artifacts: Vec::new(),
+        };
+        assert!(spec.check("anything", &[]).is_ok());
+    }
+}
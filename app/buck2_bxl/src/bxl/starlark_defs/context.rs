@@ -31,7 +31,6 @@ use buck2_build_api::dynamic::deferred::dynamic_lambda_ctx_data;
 use buck2_build_api::dynamic::deferred::DynamicLambda;
 use buck2_build_api::interpreter::rule_defs::context::AnalysisActions;
 use buck2_build_api::interpreter::rule_defs::plugins::AnalysisPlugins;
-use buck2_cli_proto::build_request::Materializations;
 use buck2_common::dice::cells::HasCellResolver;
 use buck2_common::dice::data::HasIoProvider;
 use buck2_common::events::HasEvents;
@@ -119,6 +118,7 @@ use crate::bxl::starlark_defs::context::starlark_async::BxlDiceComputations;
 use crate::bxl::starlark_defs::context::starlark_async::BxlSafeDiceComputations;
 use crate::bxl::starlark_defs::cquery::StarlarkCQueryCtx;
 use crate::bxl::starlark_defs::event::StarlarkUserEventParser;
+use crate::bxl::starlark_defs::lazy_ctx::StarlarkLazyCtx;
 use crate::bxl::starlark_defs::nodes::configured::StarlarkConfiguredTargetNode;
 use crate::bxl::starlark_defs::nodes::unconfigured::StarlarkTargetNode;
 use crate::bxl::starlark_defs::providers_expr::ConfiguredProvidersExprArg;
@@ -454,6 +454,20 @@ impl<'v> BxlContext<'v> {
             .expect("nothing to have stolen state yet"))
     }
 
+    /// The artifacts requested to be materialized by `build()`, only available in the root
+    /// context.
+    pub(crate) fn materializations_map(
+        &self,
+        function: &str,
+    ) -> anyhow::Result<&Arc<DashMap<BuildArtifact, ()>>> {
+        Ok(&self
+            .data
+            .context_type
+            .unpack_root()
+            .context(BxlContextDynamicError::Unsupported(function.to_owned()))?
+            .materializations)
+    }
+
     pub(crate) fn add_build_result(
         &self,
         result: BxlBuildResult,
@@ -1260,26 +1274,12 @@ fn context_methods(builder: &mut MethodsBuilder) {
             ValueTyped<'v, StarlarkBxlBuildResult>,
         >,
     > {
-        let materialization_setting = materializations;
-        let materializations = &this
-            .data
-            .context_type
-            .unpack_root()
-            .context(BxlContextDynamicError::Unsupported("build".to_owned()))?
-            .materializations;
         build::build(
             this,
-            materializations,
+            this.materializations_map("build")?,
             labels,
             target_platform,
-            Materializations::from_str_name(&materialization_setting.to_uppercase()).ok_or_else(
-                || {
-                    anyhow::anyhow!(
-                        "Unknown materialization setting `{}`",
-                        materialization_setting
-                    )
-                },
-            )?,
+            build::parse_materializations(materializations)?,
             eval,
         )
     }
@@ -1310,6 +1310,13 @@ fn context_methods(builder: &mut MethodsBuilder) {
         Ok(BxlFilesystem::new(this))
     }
 
+    /// Returns the `bxl_lazy_ctx` for creating lazy operations, which can be evaluated
+    /// concurrently with `ctx.lazy.batch_resolve()`.
+    #[starlark(attribute)]
+    fn lazy<'v>(this: &'v BxlContext<'v>) -> anyhow::Result<StarlarkLazyCtx<'v>> {
+        Ok(StarlarkLazyCtx::new(this))
+    }
+
     /// Checks if a target label exists. Target label must be a string literal, and an exact target.
     fn target_exists<'v>(this: &'v BxlContext<'v>, label: &'v str) -> anyhow::Result<bool> {
         this.via_dice(|ctx, this_no_dice: &BxlContextNoDice<'_>| {
@@ -20,6 +20,7 @@ use buck2_build_api::build::BuildEvent;
 use buck2_build_api::build::BuildTargetResult;
 use buck2_build_api::build::ConfiguredBuildEvent;
 use buck2_build_api::build::ConvertMaterializationContext;
+use buck2_build_api::build::MaterializationContext;
 use buck2_build_api::build::ProvidersToBuild;
 use buck2_build_api::bxl::build_result::BxlBuildResult;
 use buck2_build_api::interpreter::rule_defs::artifact::StarlarkArtifact;
@@ -29,6 +30,7 @@ use buck2_core::provider::label::ConfiguredProvidersLabel;
 use buck2_interpreter::types::configured_providers_label::StarlarkConfiguredProvidersLabel;
 use dashmap::DashMap;
 use derive_more::Display;
+use dice::DiceComputations;
 use dupe::Dupe;
 use futures::FutureExt;
 use futures::StreamExt;
@@ -216,62 +218,17 @@ pub(crate) fn build<'v>(
                 let materializations = &materializations;
                 let per_spec_results: Vec<Vec<ConfiguredBuildEvent>> = dice
                     .compute_join(build_spec.labels().unique(), |ctx, target| {
-                        async move {
-                            let target = target.clone();
-
-                            ctx.with_linear_recompute(|ctx| async move {
-                                build_configured_label(
-                                    &ctx,
-                                    materializations,
-                                    target,
-                                    &ProvidersToBuild {
-                                        default: true,
-                                        default_other: true,
-                                        run: true,
-                                        tests: true,
-                                    }, // TODO support skipping/configuring?
-                                    BuildConfiguredLabelOptions {
-                                        skippable: false,
-                                        want_configured_graph_size: false,
-                                    },
-                                )
-                                .await
-                                .collect::<Vec<_>>()
-                                .await
-                            })
-                            .await
-                        }
-                        .boxed()
+                        async move { build_label(ctx, materializations, target.clone()).await }
+                            .boxed()
                     })
                     .await;
 
-                // TODO (torozco): support --fail-fast in BXL.
-                BuildTargetResult::collect_stream(
-                    futures::stream::iter(
-                        per_spec_results
-                            .into_iter()
-                            .flatten()
-                            .map(BuildEvent::Configured),
-                    ),
-                    false,
-                )
-                .await
+                collect_build_events(per_spec_results.into_iter().flatten()).await
             }
             .boxed_local()
         })
     })?;
 
-    if let Some(err) = build_result
-        .configured
-        .values()
-        .flatten()
-        .flat_map(|r| &r.errors)
-        .chain(build_result.other_errors.values().flatten())
-        .next()
-    {
-        return Err(err.dupe().into());
-    }
-
     build_result
         .configured
         .into_iter()
@@ -289,3 +246,83 @@ pub(crate) fn build<'v>(
         })
         .try_collect()
 }
+
+/// Parses the `materializations` argument of `ctx.build()` and `ctx.lazy.build()`.
+pub(crate) fn parse_materializations(setting: &str) -> anyhow::Result<Materializations> {
+    Materializations::from_str_name(&setting.to_uppercase())
+        .ok_or_else(|| anyhow::anyhow!("Unknown materialization setting `{}`", setting))
+}
+
+/// Builds the default, other, run and test outputs of a single label.
+pub(crate) async fn build_label(
+    dice: &mut DiceComputations<'_>,
+    materializations: &MaterializationContext,
+    target: ConfiguredProvidersLabel,
+) -> Vec<ConfiguredBuildEvent> {
+    dice.with_linear_recompute(|ctx| async move {
+        build_configured_label(
+            &ctx,
+            materializations,
+            target,
+            &ProvidersToBuild {
+                default: true,
+                default_other: true,
+                run: true,
+                tests: true,
+            }, // TODO support skipping/configuring?
+            BuildConfiguredLabelOptions {
+                skippable: false,
+                want_configured_graph_size: false,
+            },
+        )
+        .await
+        .collect::<Vec<_>>()
+        .await
+    })
+    .await
+}
+
+/// Collects the events of `build_label`, failing with the first build error.
+pub(crate) async fn collect_build_events(
+    events: impl IntoIterator<Item = ConfiguredBuildEvent>,
+) -> anyhow::Result<BuildTargetResult> {
+    // TODO (torozco): support --fail-fast in BXL.
+    let build_result = BuildTargetResult::collect_stream(
+        futures::stream::iter(events.into_iter().map(BuildEvent::Configured)),
+        false,
+    )
+    .await?;
+
+    if let Some(err) = build_result
+        .configured
+        .values()
+        .flatten()
+        .flat_map(|r| &r.errors)
+        .chain(build_result.other_errors.values().flatten())
+        .next()
+    {
+        return Err(err.dupe().into());
+    }
+
+    Ok(build_result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_materializations() -> anyhow::Result<()> {
+        assert_eq!(
+            Materializations::Default,
+            parse_materializations("default")?
+        );
+        assert_eq!(Materializations::Skip, parse_materializations("skip")?);
+        assert_eq!(
+            Materializations::Materialize,
+            parse_materializations("Materialize")?
+        );
+        assert!(parse_materializations("always").is_err());
+        Ok(())
+    }
+}
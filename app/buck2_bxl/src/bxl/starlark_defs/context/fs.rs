@@ -19,13 +19,18 @@ use buck2_core::buck_path::path::BuckPath;
 use buck2_core::cells::cell_path::CellPath;
 use buck2_core::cells::cell_path::CellPathRef;
 use buck2_core::cells::instance::CellInstance;
+use buck2_core::cells::paths::CellRelativePath;
 use buck2_core::fs::artifact_path_resolver::ArtifactFs;
 use buck2_core::fs::paths::abs_norm_path::AbsNormPathBuf;
+use buck2_core::fs::paths::forward_rel_path::ForwardRelativePathBuf;
 use buck2_core::fs::project::ProjectRoot;
 use buck2_core::fs::project_rel_path::ProjectRelativePathBuf;
 use buck2_core::package::package_relative_path::PackageRelativePath;
 use buck2_core::package::PackageLabel;
+use buck2_interpreter_for_build::interpreter::globspec::glob_matching_paths;
 use buck2_node::nodes::unconfigured::TargetNode;
+use buck2_query::query::syntax::simple::eval::file_set::FileNode;
+use buck2_query::query::syntax::simple::eval::file_set::FileSet;
 use derivative::Derivative;
 use derive_more::Display;
 use dice::DiceComputations;
@@ -36,6 +41,7 @@ use starlark::environment::MethodsBuilder;
 use starlark::environment::MethodsStatic;
 use starlark::eval::Evaluator;
 use starlark::starlark_module;
+use starlark::values::list_or_tuple::UnpackListOrTuple;
 use starlark::values::none::NoneOr;
 use starlark::values::starlark_value;
 use starlark::values::AllocValue;
@@ -51,6 +57,7 @@ use starlark::StarlarkDocs;
 
 use super::BxlContext;
 use crate::bxl::starlark_defs::file_expr::FileExpr;
+use crate::bxl::starlark_defs::file_set::StarlarkFileSet;
 use crate::bxl::starlark_defs::file_set::StarlarkReadDirSet;
 use crate::bxl::starlark_defs::target_list_expr::TargetListExpr;
 use crate::bxl::starlark_defs::target_list_expr::TargetListExprArg;
@@ -112,6 +119,8 @@ pub(crate) enum BxlFilesystemError {
     PackageMismatch(PackageLabel, CellPath),
     #[error("Expected a single target hint, not an iterable: `{0}`")]
     MultipleTargetHintsNotSupported(String),
+    #[error("File `{0}` is not valid JSON")]
+    InvalidJson(CellPath),
 }
 
 impl<'v> BxlFilesystem<'v> {
@@ -149,6 +158,67 @@ async fn try_exists(
     }
 }
 
+/// Glob patterns for `ctx.fs.glob`. The patterns are matched by the same code as `glob()` in
+/// build files, but relative to an arbitrary directory.
+struct BxlGlob<'a> {
+    include: &'a [&'a str],
+    exclude: &'a [&'a str],
+    /// Whether any include pattern contains `**`, in which case we have to walk the whole tree.
+    recursive: bool,
+    /// The largest number of path components in an include pattern.
+    max_depth: usize,
+}
+
+impl<'a> BxlGlob<'a> {
+    fn new(include: &'a [&'a str], exclude: &'a [&'a str]) -> Self {
+        Self {
+            include,
+            exclude,
+            recursive: include.iter().any(|p| p.contains("**")),
+            max_depth: include
+                .iter()
+                .map(|p| p.split('/').count())
+                .max()
+                .unwrap_or(0),
+        }
+    }
+
+    /// Whether a directory at `depth` (the number of its path components) can contain matches.
+    fn descend(&self, depth: usize) -> bool {
+        self.recursive || depth < self.max_depth
+    }
+
+    /// Walk the directory `root` and return all the files whose path relative to `root` matches.
+    /// All the directory listings go through DICE.
+    async fn files(
+        &self,
+        ctx: &mut DiceComputations<'_>,
+        root: CellPath,
+    ) -> anyhow::Result<Vec<CellPath>> {
+        let mut candidates = Vec::new();
+        if try_exists(ctx, root.as_ref()).await? {
+            let mut stack = vec![(ForwardRelativePathBuf::empty(), 0)];
+            while let Some((rel, depth)) = stack.pop() {
+                let listing = DiceFileComputations::read_dir(ctx, root.join(&rel).as_ref()).await?;
+                for entry in listing.included.iter() {
+                    let child_rel = rel.join(&entry.file_name);
+                    if !entry.file_type.is_dir() {
+                        candidates.push(child_rel);
+                    } else if self.descend(depth + 1) {
+                        stack.push((child_rel, depth + 1));
+                    }
+                }
+            }
+        }
+        let mut files: Vec<CellPath> =
+            glob_matching_paths(self.include, self.exclude, candidates.iter().map(|p| &**p))?
+                .map(|rel| root.join(rel))
+                .collect();
+        files.sort();
+        Ok(files)
+    }
+}
+
 /// Provides some basic tracked filesystem access for bxl functions so that they can meaningfully
 /// detect simple properties of artifacts, and source directories.
 #[starlark_module]
@@ -237,6 +307,94 @@ fn fs_operations(builder: &mut MethodsBuilder) {
         Ok(std::path::Path::is_file(this.resolve(expr)?.as_ref()))
     }
 
+    /// Returns the contents of a file as a string, reading it through Buck's cached filesystem
+    /// so that the result is invalidated when the file changes.
+    /// The input is a either a literal, a source artifact (via `artifact`), or a `file_node`.
+    ///
+    /// Sample usage:
+    /// ```text
+    /// def _impl_read(ctx):
+    ///     ctx.output.print(ctx.fs.read("bin/TARGETS"))
+    /// ```
+    fn read<'v>(
+        this: &'v BxlFilesystem<'v>,
+        expr: FileExpr<'v>,
+        heap: &'v Heap,
+    ) -> anyhow::Result<StringValue<'v>> {
+        let contents = this.ctx.async_ctx.borrow_mut().via(|dice| {
+            async {
+                let path = expr.get(dice, this.cell()?).await?;
+                DiceFileComputations::read_file(dice, path.as_ref()).await
+            }
+            .boxed_local()
+        })?;
+        Ok(heap.alloc_str(&contents))
+    }
+
+    /// Reads a file like `read`, and decodes its contents as JSON.
+    /// The input is a either a literal, a source artifact (via `artifact`), or a `file_node`.
+    ///
+    /// Sample usage:
+    /// ```text
+    /// def _impl_read_json(ctx):
+    ///     config = ctx.fs.read_json("tools/config.json")
+    ///     ctx.output.print(config["version"])
+    /// ```
+    fn read_json<'v>(
+        this: &'v BxlFilesystem<'v>,
+        expr: FileExpr<'v>,
+        heap: &'v Heap,
+    ) -> anyhow::Result<Value<'v>> {
+        let json = this.ctx.async_ctx.borrow_mut().via(|dice| {
+            async {
+                let path = expr.get(dice, this.cell()?).await?;
+                let contents = DiceFileComputations::read_file(dice, path.as_ref()).await?;
+                serde_json::from_str::<serde_json::Value>(&contents).map_err(|e| {
+                    anyhow::Error::new(e).context(BxlFilesystemError::InvalidJson(path))
+                })
+            }
+            .boxed_local()
+        })?;
+        Ok(heap.alloc(json))
+    }
+
+    /// Returns the files matching the given glob patterns, as a `file_set`. Patterns use the same
+    /// syntax as `glob()` in build files and are relative to `root`, which defaults to the root of
+    /// the cell the bxl script is in. Files matching any of the `exclude` patterns are omitted.
+    /// Directory listings go through Buck's cached filesystem, so results are invalidated when
+    /// files are added or removed.
+    ///
+    /// Sample usage:
+    /// ```text
+    /// def _impl_glob(ctx):
+    ///     for file in ctx.fs.glob(["**/*.bzl"], exclude = ["**/test_*.bzl"], root = "prelude"):
+    ///         ctx.output.print(file)
+    /// ```
+    fn glob<'v>(
+        this: &'v BxlFilesystem<'v>,
+        include: UnpackListOrTuple<&str>,
+        #[starlark(require = named, default = UnpackListOrTuple::default())]
+        exclude: UnpackListOrTuple<&str>,
+        #[starlark(require = named, default = NoneOr::None)] root: NoneOr<FileExpr<'v>>,
+    ) -> anyhow::Result<StarlarkFileSet> {
+        let glob = BxlGlob::new(&include.items, &exclude.items);
+        let files = this.ctx.async_ctx.borrow_mut().via(|dice| {
+            async {
+                let root = match root {
+                    NoneOr::None => {
+                        CellPath::new(this.ctx.cell_name(), CellRelativePath::empty().to_buf())
+                    }
+                    NoneOr::Other(root) => root.get(dice, this.cell()?).await?,
+                };
+                glob.files(dice, root).await
+            }
+            .boxed_local()
+        })?;
+        Ok(StarlarkFileSet::from(
+            files.into_iter().map(FileNode).collect::<FileSet>(),
+        ))
+    }
+
     /// Returns the relative path to the project root, given the file expression.
     ///
     /// Sample usage:
@@ -339,3 +497,18 @@ fn fs_operations(builder: &mut MethodsBuilder) {
             .alloc_typed(StarlarkArtifact::new(SourceArtifact::new(buck_path).into())))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bxl_glob_descend() {
+        let glob = BxlGlob::new(&["*.bzl", "src/**/*.rs"], &[]);
+        assert!(glob.descend(5));
+
+        let glob = BxlGlob::new(&["a/*/c.txt"], &[]);
+        assert!(glob.descend(2));
+        assert!(!glob.descend(3));
+    }
+}
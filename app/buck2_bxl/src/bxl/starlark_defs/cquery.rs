@@ -511,7 +511,7 @@ fn cquery_methods(builder: &mut MethodsBuilder) {
                                 target_universe.into_option().as_ref().map(|v| &v.items[..]),
                             )
                             .await?,
-                        eval,
+                        eval.heap(),
                     )
                 }
                 .boxed_local()
@@ -13,6 +13,7 @@ use buck2_interpreter::bxl::BXL_SPECIFIC_GLOBALS;
 use starlark::environment::GlobalsBuilder;
 
 use crate::bxl::starlark_defs::bxl_function::register_bxl_function;
+use crate::bxl::starlark_defs::bxl_test::register_bxl_test;
 use crate::bxl::starlark_defs::cli_args;
 use crate::bxl::starlark_defs::functions::register_artifact_function;
 use crate::bxl::starlark_defs::functions::register_error_handling_function;
@@ -39,6 +40,7 @@ pub(crate) fn init_bxl_specific_globals() {
         // TODO(nga): move these into `bxl` namespace.
         g.struct_("cli_args", cli_args::register_cli_args_module);
         register_bxl_function(g);
+        register_bxl_test(g);
         register_artifact_function(g);
         register_target_function(g);
         register_file_set_function(g);
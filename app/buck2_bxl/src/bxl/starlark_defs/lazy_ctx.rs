@@ -0,0 +1,278 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use allocative::Allocative;
+use buck2_build_api::build::ConvertMaterializationContext;
+use buck2_common::global_cfg_options::GlobalCfgOptions;
+use buck2_core::cells::cell_path::CellPathRef;
+use buck2_core::cells::paths::CellRelativePath;
+use buck2_core::pattern::pattern_type::TargetPatternExtra;
+use buck2_core::pattern::ParsedPattern;
+use buck2_core::provider::label::ConfiguredProvidersLabel;
+use buck2_core::provider::label::ProvidersLabel;
+use buck2_core::provider::label::ProvidersName;
+use derivative::Derivative;
+use derive_more::Display;
+use dupe::Dupe;
+use starlark::any::ProvidesStaticType;
+use starlark::environment::Methods;
+use starlark::environment::MethodsBuilder;
+use starlark::environment::MethodsStatic;
+use starlark::starlark_module;
+use starlark::values::list_or_tuple::UnpackListOrTuple;
+use starlark::values::starlark_value;
+use starlark::values::AllocValue;
+use starlark::values::Heap;
+use starlark::values::NoSerialize;
+use starlark::values::StarlarkValue;
+use starlark::values::Trace;
+use starlark::values::Value;
+use starlark::StarlarkDocs;
+
+use crate::bxl::starlark_defs::context::build::parse_materializations;
+use crate::bxl::starlark_defs::context::BxlContext;
+use crate::bxl::starlark_defs::lazy_ctx::cquery::StarlarkLazyCqueryCtx;
+use crate::bxl::starlark_defs::lazy_ctx::lazy::resolve_lazies;
+use crate::bxl::starlark_defs::lazy_ctx::lazy::StarlarkLazy;
+use crate::bxl::starlark_defs::lazy_ctx::operation::LazyLabel;
+use crate::bxl::starlark_defs::lazy_ctx::operation::LazyOperation;
+use crate::bxl::starlark_defs::providers_expr::ConfiguredProvidersLabelArg;
+use crate::bxl::starlark_defs::providers_expr::ProvidersExpr;
+use crate::bxl::starlark_defs::target_list_expr::ConfiguredTargetNodeArg;
+use crate::bxl::value_as_starlark_target_label::ValueAsStarlarkTargetLabel;
+
+pub(crate) mod cquery;
+pub(crate) mod lazy;
+pub(crate) mod operation;
+
+#[derive(
+    ProvidesStaticType,
+    Derivative,
+    Display,
+    Trace,
+    NoSerialize,
+    StarlarkDocs,
+    Allocative
+)]
+#[starlark_docs(directory = "bxl")]
+#[derivative(Debug)]
+#[display(fmt = "{:?}", self)]
+#[allocative(skip)]
+pub(crate) struct StarlarkLazyCtx<'v> {
+    #[trace(unsafe_ignore)]
+    #[derivative(Debug = "ignore")]
+    ctx: &'v BxlContext<'v>,
+}
+
+impl<'v> StarlarkLazyCtx<'v> {
+    pub(crate) fn new(ctx: &'v BxlContext<'v>) -> Self {
+        Self { ctx }
+    }
+
+    fn global_cfg_options(
+        &self,
+        target_platform: ValueAsStarlarkTargetLabel<'v>,
+    ) -> anyhow::Result<GlobalCfgOptions> {
+        Ok(GlobalCfgOptions {
+            target_platform: target_platform.parse_target_platforms(
+                self.ctx.target_alias_resolver(),
+                self.ctx.cell_resolver(),
+                self.ctx.cell_name(),
+                &self.ctx.global_cfg_options().target_platform,
+            )?,
+            cli_modifiers: vec![].into(),
+        })
+    }
+
+    fn providers_label(
+        &self,
+        label: ConfiguredProvidersLabelArg<'v>,
+        target_platform: ValueAsStarlarkTargetLabel<'v>,
+    ) -> anyhow::Result<LazyLabel<ConfiguredProvidersLabel, ProvidersLabel>> {
+        Ok(match label {
+            ConfiguredProvidersLabelArg::ConfiguredTargetNode(node) => LazyLabel::Configured(
+                ConfiguredProvidersLabel::new(node.0.label().dupe(), ProvidersName::Default),
+            ),
+            ConfiguredProvidersLabelArg::ConfiguredTargetLabel(label) => LazyLabel::Configured(
+                ConfiguredProvidersLabel::new(label.label().dupe(), ProvidersName::Default),
+            ),
+            ConfiguredProvidersLabelArg::ConfiguredProvidersLabel(label) => {
+                LazyLabel::Configured(label.label().clone())
+            }
+            ConfiguredProvidersLabelArg::Unconfigured(label) => LazyLabel::Unconfigured(
+                ProvidersExpr::<ProvidersLabel>::unpack_providers_label(label, self.ctx)?,
+                self.global_cfg_options(target_platform)?,
+            ),
+        })
+    }
+}
+
+#[starlark_value(type = "bxl_lazy_ctx", StarlarkTypeRepr, UnpackValue)]
+impl<'v> StarlarkValue<'v> for StarlarkLazyCtx<'v> {
+    fn get_methods() -> Option<&'static Methods> {
+        static RES: MethodsStatic = MethodsStatic::new();
+        RES.methods(lazy_ctx_methods)
+    }
+}
+
+impl<'v> AllocValue<'v> for StarlarkLazyCtx<'v> {
+    fn alloc_value(self, heap: &'v Heap) -> Value<'v> {
+        heap.alloc_complex_no_freeze(self)
+    }
+}
+
+/// The context for creating lazy operations in bxl. The functions on this ctx record a request
+/// without evaluating it. The returned `bxl_lazy` values can be evaluated together with
+/// `batch_resolve()`, in which case DICE evaluates them concurrently, which is much faster than
+/// calling the eager functions of `bxl_ctx` one target at a time.
+#[starlark_module]
+fn lazy_ctx_methods(builder: &mut MethodsBuilder) {
+    /// Returns a lazy value that runs analysis on a single target or sub target. It accepts an
+    /// optional `target_platform` used to configure unconfigured labels, like `ctx.analysis()`.
+    /// Resolving it returns an `analysis_result`, and fails if the target is incompatible with the
+    /// target platform.
+    ///
+    /// Sample usage:
+    /// ```text
+    /// def _impl_lazy_analysis(ctx):
+    ///     lazy = ctx.lazy.analysis("root//:foo")
+    ///     ctx.output.print(lazy.resolve().providers())
+    /// ```
+    fn analysis<'v>(
+        this: &StarlarkLazyCtx<'v>,
+        #[starlark(require = pos)] label: ConfiguredProvidersLabelArg<'v>,
+        #[starlark(require = named, default = ValueAsStarlarkTargetLabel::NONE)]
+        target_platform: ValueAsStarlarkTargetLabel<'v>,
+    ) -> anyhow::Result<StarlarkLazy<'v>> {
+        let label = this.providers_label(label, target_platform)?;
+        Ok(StarlarkLazy::new(this.ctx, LazyOperation::Analysis(label)))
+    }
+
+    /// Returns a lazy value that gets the configured target node of a single target. It accepts
+    /// an optional `target_platform` used to configure unconfigured labels, like
+    /// `ctx.configured_targets()`. Resolving it returns a `target_node`, and fails if the target
+    /// is incompatible with the target platform.
+    ///
+    /// Sample usage:
+    /// ```text
+    /// def _impl_lazy_configured_target_node(ctx):
+    ///     node = ctx.lazy.configured_target_node("root//:foo").resolve()
+    ///     ctx.output.print(node.label)
+    /// ```
+    fn configured_target_node<'v>(
+        this: &StarlarkLazyCtx<'v>,
+        #[starlark(require = pos)] label: ConfiguredTargetNodeArg<'v>,
+        #[starlark(require = named, default = ValueAsStarlarkTargetLabel::NONE)]
+        target_platform: ValueAsStarlarkTargetLabel<'v>,
+    ) -> anyhow::Result<StarlarkLazy<'v>> {
+        let label = match label {
+            ConfiguredTargetNodeArg::ConfiguredTargetNode(node) => {
+                LazyLabel::Configured(node.0.label().dupe())
+            }
+            ConfiguredTargetNodeArg::ConfiguredTargetLabel(label) => {
+                LazyLabel::Configured(label.label().dupe())
+            }
+            ConfiguredTargetNodeArg::Str(s) => LazyLabel::Unconfigured(
+                ParsedPattern::<TargetPatternExtra>::parse_relaxed(
+                    this.ctx.target_alias_resolver(),
+                    // TODO(nga): Parse relaxed relative to cell root is incorrect.
+                    CellPathRef::new(this.ctx.cell_name(), CellRelativePath::empty()),
+                    s,
+                    this.ctx.cell_resolver(),
+                )?
+                .as_target_label(s)?,
+                this.global_cfg_options(target_platform)?,
+            ),
+            ConfiguredTargetNodeArg::Unconfigured(label) => LazyLabel::Unconfigured(
+                label.label().dupe(),
+                this.global_cfg_options(target_platform)?,
+            ),
+        };
+        Ok(StarlarkLazy::new(
+            this.ctx,
+            LazyOperation::ConfiguredTargetNode(label),
+        ))
+    }
+
+    /// Returns a lazy value that builds a single target or sub target, like `ctx.build()`. It
+    /// accepts an optional `target_platform` used to configure unconfigured labels, and the same
+    /// `materializations` setting as `ctx.build()`. Resolving it returns a `bxl_build_result`.
+    ///
+    /// Sample usage:
+    /// ```text
+    /// def _impl_lazy_build(ctx):
+    ///     lazies = [ctx.lazy.build(t) for t in ctx.configured_targets("root//...")]
+    ///     for result in ctx.lazy.batch_resolve(lazies):
+    ///         ctx.output.ensure_multiple(result.artifacts())
+    /// ```
+    fn build<'v>(
+        this: &StarlarkLazyCtx<'v>,
+        #[starlark(require = pos)] label: ConfiguredProvidersLabelArg<'v>,
+        #[starlark(require = named, default = ValueAsStarlarkTargetLabel::NONE)]
+        target_platform: ValueAsStarlarkTargetLabel<'v>,
+        #[starlark(require = named, default = "default")] materializations: &str,
+    ) -> anyhow::Result<StarlarkLazy<'v>> {
+        let materializations = ConvertMaterializationContext::with_existing_map(
+            parse_materializations(materializations)?,
+            this.ctx.materializations_map("lazy.build")?,
+        );
+        let label = this.providers_label(label, target_platform)?;
+        Ok(StarlarkLazy::new(
+            this.ctx,
+            LazyOperation::Build(label, materializations),
+        ))
+    }
+
+    /// Returns the `bxl_lazy_cquery_ctx`, whose functions return lazy values that evaluate
+    /// cquery. It accepts an optional `target_platform` used to configure the unconfigured nodes,
+    /// like `ctx.cquery()`.
+    ///
+    /// Sample usage:
+    /// ```text
+    /// def _impl_lazy_cquery(ctx):
+    ///     lazies = [ctx.lazy.cquery().eval("deps({})".format(t)) for t in ["root//:a", "root//:b"]]
+    ///     for deps in ctx.lazy.batch_resolve(lazies):
+    ///         ctx.output.print(deps)
+    /// ```
+    fn cquery<'v>(
+        this: &StarlarkLazyCtx<'v>,
+        #[starlark(require = named, default = ValueAsStarlarkTargetLabel::NONE)]
+        target_platform: ValueAsStarlarkTargetLabel<'v>,
+    ) -> anyhow::Result<StarlarkLazyCqueryCtx<'v>> {
+        Ok(StarlarkLazyCqueryCtx::new(
+            this.ctx,
+            this.global_cfg_options(target_platform)?,
+        ))
+    }
+
+    /// Evaluates many lazy values concurrently and returns their results in the same order.
+    ///
+    /// All the values are evaluated even if some of them fail. If a value that was not created
+    /// with `catch()` fails, the first such failure is raised; values created with `catch()`
+    /// return a `bxl_result` instead, so that one failing target does not abort the whole batch.
+    ///
+    /// Sample usage:
+    /// ```text
+    /// def _impl_batch_resolve(ctx):
+    ///     targets = ctx.configured_targets("root//...")
+    ///     lazies = [ctx.lazy.analysis(t).catch() for t in targets]
+    ///     for target, result in zip(targets, ctx.lazy.batch_resolve(lazies)):
+    ///         if result.is_ok():
+    ///             ctx.output.print(result.unwrap().providers())
+    ///         else:
+    ///             ctx.output.print("{} failed: {}".format(target.label, result.unwrap_err()))
+    /// ```
+    fn batch_resolve<'v>(
+        this: &StarlarkLazyCtx<'v>,
+        #[starlark(require = pos)] lazies: UnpackListOrTuple<&'v StarlarkLazy<'v>>,
+        heap: &'v Heap,
+    ) -> anyhow::Result<Vec<Value<'v>>> {
+        resolve_lazies(this.ctx, &lazies.items, heap)
+    }
+}
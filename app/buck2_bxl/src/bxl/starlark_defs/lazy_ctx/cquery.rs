@@ -0,0 +1,121 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use allocative::Allocative;
+use buck2_common::global_cfg_options::GlobalCfgOptions;
+use derivative::Derivative;
+use derive_more::Display;
+use dupe::Dupe;
+use starlark::any::ProvidesStaticType;
+use starlark::environment::Methods;
+use starlark::environment::MethodsBuilder;
+use starlark::environment::MethodsStatic;
+use starlark::starlark_module;
+use starlark::values::list_or_tuple::UnpackListOrTuple;
+use starlark::values::none::NoneOr;
+use starlark::values::starlark_value;
+use starlark::values::AllocValue;
+use starlark::values::Heap;
+use starlark::values::NoSerialize;
+use starlark::values::StarlarkValue;
+use starlark::values::Trace;
+use starlark::values::Value;
+use starlark::StarlarkDocs;
+
+use crate::bxl::starlark_defs::context::BxlContext;
+use crate::bxl::starlark_defs::lazy_ctx::lazy::StarlarkLazy;
+use crate::bxl::starlark_defs::lazy_ctx::operation::LazyCquery;
+use crate::bxl::starlark_defs::lazy_ctx::operation::LazyOperation;
+use crate::bxl::starlark_defs::uquery::UnpackUnconfiguredQueryArgs;
+
+#[derive(
+    ProvidesStaticType,
+    Derivative,
+    Display,
+    Trace,
+    NoSerialize,
+    StarlarkDocs,
+    Allocative
+)]
+#[starlark_docs(directory = "bxl")]
+#[derivative(Debug)]
+#[display(fmt = "{:?}", self)]
+#[allocative(skip)]
+pub(crate) struct StarlarkLazyCqueryCtx<'v> {
+    #[trace(unsafe_ignore)]
+    #[derivative(Debug = "ignore")]
+    ctx: &'v BxlContext<'v>,
+    #[trace(unsafe_ignore)]
+    global_cfg_options: GlobalCfgOptions,
+}
+
+impl<'v> StarlarkLazyCqueryCtx<'v> {
+    pub(crate) fn new(ctx: &'v BxlContext<'v>, global_cfg_options: GlobalCfgOptions) -> Self {
+        Self {
+            ctx,
+            global_cfg_options,
+        }
+    }
+}
+
+#[starlark_value(type = "bxl_lazy_cquery_ctx", StarlarkTypeRepr, UnpackValue)]
+impl<'v> StarlarkValue<'v> for StarlarkLazyCqueryCtx<'v> {
+    fn get_methods() -> Option<&'static Methods> {
+        static RES: MethodsStatic = MethodsStatic::new();
+        RES.methods(lazy_cquery_ctx_methods)
+    }
+}
+
+impl<'v> AllocValue<'v> for StarlarkLazyCqueryCtx<'v> {
+    fn alloc_value(self, heap: &'v Heap) -> Value<'v> {
+        heap.alloc_complex_no_freeze(self)
+    }
+}
+
+/// The context for creating lazy cquery operations, returned by `ctx.lazy.cquery()`. The queries
+/// are evaluated when the returned `bxl_lazy` values are resolved, so that many of them can be
+/// evaluated concurrently with `ctx.lazy.batch_resolve()`.
+#[starlark_module]
+fn lazy_cquery_ctx_methods(builder: &mut MethodsBuilder) {
+    /// Returns a lazy value that evaluates a query string, like `ctx.cquery().eval()`. Resolving
+    /// it returns a `dict` of target labels mapped to their `target_set` results if `query_args`
+    /// was passed in, otherwise a single `target_set`.
+    ///
+    /// Sample usage:
+    /// ```text
+    /// def _impl_lazy_eval(ctx):
+    ///     lazy = ctx.lazy.cquery().eval("inputs(%s)", query_args = ["cell//path/to/file:target"])
+    ///     ctx.output.print(lazy.resolve())
+    /// ```
+    fn eval<'v>(
+        this: &StarlarkLazyCqueryCtx<'v>,
+        #[starlark(require = pos)] query: &'v str,
+        #[starlark(require = named, default = NoneOr::None)] query_args: NoneOr<
+            UnpackUnconfiguredQueryArgs<'v>,
+        >,
+        #[starlark(require = named, default = NoneOr::None)] target_universe: NoneOr<
+            UnpackListOrTuple<String>,
+        >,
+    ) -> anyhow::Result<StarlarkLazy<'v>> {
+        let query_args = match query_args {
+            NoneOr::None => Vec::new(),
+            NoneOr::Other(query_args) => query_args.into_strings(),
+        };
+        Ok(StarlarkLazy::new(
+            this.ctx,
+            LazyOperation::Cquery(LazyCquery {
+                query: query.to_owned(),
+                query_args,
+                target_universe: target_universe.into_option().map(|v| v.items),
+                global_cfg_options: this.global_cfg_options.dupe(),
+                working_dir: this.ctx.working_dir()?,
+            }),
+        ))
+    }
+}
@@ -0,0 +1,220 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::sync::Arc;
+
+use allocative::Allocative;
+use derivative::Derivative;
+use derive_more::Display;
+use dupe::Dupe;
+use futures::FutureExt;
+use starlark::any::ProvidesStaticType;
+use starlark::environment::Methods;
+use starlark::environment::MethodsBuilder;
+use starlark::environment::MethodsStatic;
+use starlark::starlark_module;
+use starlark::values::starlark_value;
+use starlark::values::AllocValue;
+use starlark::values::Heap;
+use starlark::values::NoSerialize;
+use starlark::values::StarlarkValue;
+use starlark::values::Trace;
+use starlark::values::Value;
+use starlark::StarlarkDocs;
+
+use crate::bxl::starlark_defs::context::BxlContext;
+use crate::bxl::starlark_defs::lazy_ctx::operation::LazyOperation;
+
+#[derive(Debug, buck2_error::Error)]
+enum LazyResultError {
+    #[error("Called `unwrap()` on an error result: {0}")]
+    UnwrapErr(String),
+    #[error("Called `unwrap_err()` on a successful result")]
+    UnwrapOk,
+}
+
+/// A BXL operation that has not been evaluated yet. It is created by one of the functions of
+/// `ctx.lazy`, and evaluated by `resolve()` or, together with other lazy values,
+/// `ctx.lazy.batch_resolve()`.
+#[derive(
+    ProvidesStaticType,
+    Derivative,
+    Display,
+    Trace,
+    NoSerialize,
+    StarlarkDocs,
+    Allocative
+)]
+#[starlark_docs(directory = "bxl")]
+#[derivative(Debug)]
+#[display(fmt = "{:?}", self)]
+#[allocative(skip)]
+pub(crate) struct StarlarkLazy<'v> {
+    #[trace(unsafe_ignore)]
+    #[derivative(Debug = "ignore")]
+    ctx: &'v BxlContext<'v>,
+    #[trace(unsafe_ignore)]
+    operation: Arc<LazyOperation>,
+    /// Whether failures are returned as a `bxl_result` rather than raised.
+    catch_errors: bool,
+}
+
+impl<'v> StarlarkLazy<'v> {
+    pub(crate) fn new(ctx: &'v BxlContext<'v>, operation: LazyOperation) -> Self {
+        Self {
+            ctx,
+            operation: Arc::new(operation),
+            catch_errors: false,
+        }
+    }
+}
+
+#[starlark_value(type = "bxl_lazy", StarlarkTypeRepr, UnpackValue)]
+impl<'v> StarlarkValue<'v> for StarlarkLazy<'v> {
+    fn get_methods() -> Option<&'static Methods> {
+        static RES: MethodsStatic = MethodsStatic::new();
+        RES.methods(lazy_methods)
+    }
+}
+
+impl<'v> AllocValue<'v> for StarlarkLazy<'v> {
+    fn alloc_value(self, heap: &'v Heap) -> Value<'v> {
+        heap.alloc_complex_no_freeze(self)
+    }
+}
+
+/// Evaluate the lazy values concurrently within a single DICE call. Failures of lazy values
+/// created with `catch()` are returned as `bxl_result`s; otherwise the first failure (in the
+/// order of `lazies`) is raised once all the values have been evaluated.
+pub(crate) fn resolve_lazies<'v>(
+    ctx: &'v BxlContext<'v>,
+    lazies: &[&StarlarkLazy<'v>],
+    heap: &'v Heap,
+) -> anyhow::Result<Vec<Value<'v>>> {
+    let operations: Vec<_> = lazies.iter().map(|l| l.operation.dupe()).collect();
+    let results = ctx.async_ctx.borrow_mut().via(|dice| {
+        async move { Ok(LazyOperation::resolve_all(dice, &operations).await) }.boxed_local()
+    })?;
+
+    lazies
+        .iter()
+        .zip(results)
+        .map(|(lazy, result)| {
+            let value = result.and_then(|v| v.alloc(ctx, heap));
+            if lazy.catch_errors {
+                Ok(heap.alloc(match value {
+                    Ok(value) => StarlarkLazyResult::Ok(value),
+                    Err(e) => StarlarkLazyResult::Err(format!("{:#}", e)),
+                }))
+            } else {
+                value
+            }
+        })
+        .collect()
+}
+
+/// Operations on a lazy value.
+#[starlark_module]
+fn lazy_methods(builder: &mut MethodsBuilder) {
+    /// Evaluate this lazy value. To evaluate many lazy values concurrently, use
+    /// `ctx.lazy.batch_resolve()` instead of calling `resolve()` on each of them.
+    ///
+    /// Sample usage:
+    /// ```text
+    /// def _impl_resolve(ctx):
+    ///     analysis = ctx.lazy.analysis("root//:foo").resolve()
+    ///     ctx.output.print(analysis.providers())
+    /// ```
+    fn resolve<'v>(this: &StarlarkLazy<'v>, heap: &'v Heap) -> anyhow::Result<Value<'v>> {
+        Ok(resolve_lazies(this.ctx, &[this], heap)?
+            .into_iter()
+            .next()
+            .unwrap())
+    }
+
+    /// Returns a lazy value that does not raise when it fails: resolving it returns a
+    /// `bxl_result` which holds either the value or the error.
+    ///
+    /// Sample usage:
+    /// ```text
+    /// def _impl_catch(ctx):
+    ///     result = ctx.lazy.analysis("root//:foo").catch().resolve()
+    ///     if result.is_ok():
+    ///         ctx.output.print(result.unwrap().providers())
+    ///     else:
+    ///         ctx.output.print("failed: " + result.unwrap_err())
+    /// ```
+    fn catch<'v>(this: &StarlarkLazy<'v>) -> anyhow::Result<StarlarkLazy<'v>> {
+        Ok(StarlarkLazy {
+            ctx: this.ctx,
+            operation: this.operation.dupe(),
+            catch_errors: true,
+        })
+    }
+}
+
+/// The outcome of resolving a lazy value created with `catch()`: either the value, or the
+/// error message.
+#[derive(
+    ProvidesStaticType,
+    Derivative,
+    Display,
+    Trace,
+    NoSerialize,
+    StarlarkDocs,
+    Allocative
+)]
+#[starlark_docs(directory = "bxl")]
+#[derivative(Debug)]
+#[allocative(skip)]
+pub(crate) enum StarlarkLazyResult<'v> {
+    #[display(fmt = "Ok({})", _0)]
+    Ok(Value<'v>),
+    #[display(fmt = "Err({})", _0)]
+    Err(String),
+}
+
+#[starlark_value(type = "bxl_result", StarlarkTypeRepr, UnpackValue)]
+impl<'v> StarlarkValue<'v> for StarlarkLazyResult<'v> {
+    fn get_methods() -> Option<&'static Methods> {
+        static RES: MethodsStatic = MethodsStatic::new();
+        RES.methods(lazy_result_methods)
+    }
+}
+
+impl<'v> AllocValue<'v> for StarlarkLazyResult<'v> {
+    fn alloc_value(self, heap: &'v Heap) -> Value<'v> {
+        heap.alloc_complex_no_freeze(self)
+    }
+}
+
+/// Operations on the result of a lazy value created with `catch()`.
+#[starlark_module]
+fn lazy_result_methods(builder: &mut MethodsBuilder) {
+    /// Returns whether the lazy value was evaluated successfully.
+    fn is_ok<'v>(this: &StarlarkLazyResult<'v>) -> anyhow::Result<bool> {
+        Ok(matches!(this, StarlarkLazyResult::Ok(_)))
+    }
+
+    /// Returns the value, or raises the error if the evaluation failed.
+    fn unwrap<'v>(this: &StarlarkLazyResult<'v>) -> anyhow::Result<Value<'v>> {
+        match this {
+            StarlarkLazyResult::Ok(value) => Ok(*value),
+            StarlarkLazyResult::Err(e) => Err(LazyResultError::UnwrapErr(e.clone()).into()),
+        }
+    }
+
+    /// Returns the error message, or raises if the evaluation succeeded.
+    fn unwrap_err<'v>(this: &StarlarkLazyResult<'v>) -> anyhow::Result<String> {
+        match this {
+            StarlarkLazyResult::Ok(_) => Err(LazyResultError::UnwrapOk.into()),
+            StarlarkLazyResult::Err(e) => Ok(e.clone()),
+        }
+    }
+}
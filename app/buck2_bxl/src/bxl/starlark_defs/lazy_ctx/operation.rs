@@ -0,0 +1,185 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::sync::Arc;
+
+use buck2_build_api::analysis::calculation::RuleAnalysisCalculation;
+use buck2_build_api::analysis::AnalysisResult;
+use buck2_build_api::build::MaterializationContext;
+use buck2_build_api::bxl::build_result::BxlBuildResult;
+use buck2_build_api::query::oneshot::CqueryOwnerBehavior;
+use buck2_build_api::query::oneshot::QUERY_FRONTEND;
+use buck2_common::global_cfg_options::GlobalCfgOptions;
+use buck2_core::fs::project_rel_path::ProjectRelativePathBuf;
+use buck2_core::provider::label::ConfiguredProvidersLabel;
+use buck2_core::provider::label::ProvidersLabel;
+use buck2_core::target::configured_target_label::ConfiguredTargetLabel;
+use buck2_core::target::label::TargetLabel;
+use buck2_node::nodes::configured::ConfiguredTargetNode;
+use buck2_node::nodes::configured_frontend::ConfiguredTargetNodeCalculation;
+use buck2_node::target_calculation::ConfiguredTargetCalculation;
+use buck2_query::query::syntax::simple::eval::values::QueryEvaluationResult;
+use derivative::Derivative;
+use dice::DiceComputations;
+use dupe::Dupe;
+use futures::FutureExt;
+use starlark::values::Heap;
+use starlark::values::Value;
+
+use crate::bxl::starlark_defs::analysis_result::StarlarkAnalysisResult;
+use crate::bxl::starlark_defs::build_result::StarlarkBxlBuildResult;
+use crate::bxl::starlark_defs::context::build::build_label;
+use crate::bxl::starlark_defs::context::build::collect_build_events;
+use crate::bxl::starlark_defs::context::BxlContext;
+use crate::bxl::starlark_defs::nodes::configured::StarlarkConfiguredTargetNode;
+use crate::bxl::starlark_defs::query_util::parse_query_evaluation_result;
+
+/// A label that is either already configured, or that will be configured with the given options
+/// when the operation is resolved.
+#[derive(Debug)]
+pub(crate) enum LazyLabel<C, U> {
+    Configured(C),
+    Unconfigured(U, GlobalCfgOptions),
+}
+
+/// A request recorded by `ctx.lazy` that has not been evaluated yet. Unlike Starlark values,
+/// operations are `Send`, so many of them can be evaluated concurrently by DICE.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub(crate) enum LazyOperation {
+    Analysis(LazyLabel<ConfiguredProvidersLabel, ProvidersLabel>),
+    ConfiguredTargetNode(LazyLabel<ConfiguredTargetLabel, TargetLabel>),
+    Build(
+        LazyLabel<ConfiguredProvidersLabel, ProvidersLabel>,
+        #[derivative(Debug = "ignore")] MaterializationContext,
+    ),
+    Cquery(LazyCquery),
+}
+
+/// The arguments of `ctx.lazy.cquery().eval()`.
+#[derive(Debug)]
+pub(crate) struct LazyCquery {
+    pub(crate) query: String,
+    pub(crate) query_args: Vec<String>,
+    pub(crate) target_universe: Option<Vec<String>>,
+    pub(crate) global_cfg_options: GlobalCfgOptions,
+    pub(crate) working_dir: ProjectRelativePathBuf,
+}
+
+/// The result of a resolved `LazyOperation`, before it is allocated on the Starlark heap.
+pub(crate) enum LazyValue {
+    Analysis(ConfiguredProvidersLabel, AnalysisResult),
+    ConfiguredTargetNode(ConfiguredTargetNode),
+    Build(BxlBuildResult),
+    Cquery(QueryEvaluationResult<ConfiguredTargetNode>),
+}
+
+impl LazyLabel<ConfiguredProvidersLabel, ProvidersLabel> {
+    async fn configure(
+        &self,
+        dice: &mut DiceComputations<'_>,
+    ) -> anyhow::Result<ConfiguredProvidersLabel> {
+        match self {
+            LazyLabel::Configured(label) => Ok(label.clone()),
+            LazyLabel::Unconfigured(label, global_cfg_options) => {
+                dice.get_configured_provider_label(label, global_cfg_options)
+                    .await
+            }
+        }
+    }
+}
+
+impl LazyOperation {
+    async fn resolve(&self, dice: &mut DiceComputations<'_>) -> anyhow::Result<LazyValue> {
+        match self {
+            LazyOperation::Analysis(label) => {
+                let label = label.configure(dice).await?;
+                let result = dice
+                    .get_analysis_result(label.target())
+                    .await?
+                    .require_compatible()?;
+                Ok(LazyValue::Analysis(label, result))
+            }
+            LazyOperation::ConfiguredTargetNode(label) => {
+                let label = match label {
+                    LazyLabel::Configured(label) => label.clone(),
+                    LazyLabel::Unconfigured(label, global_cfg_options) => {
+                        dice.get_configured_target(label, global_cfg_options)
+                            .await?
+                    }
+                };
+                let node = dice
+                    .get_configured_target_node(&label)
+                    .await?
+                    .require_compatible()?;
+                Ok(LazyValue::ConfiguredTargetNode(node))
+            }
+            LazyOperation::Build(label, materializations) => {
+                let label = label.configure(dice).await?;
+                let result =
+                    collect_build_events(build_label(dice, materializations, label.clone()).await)
+                        .await?
+                        .configured
+                        .remove(&label)
+                        .flatten();
+                Ok(LazyValue::Build(BxlBuildResult::new(label, result)))
+            }
+            LazyOperation::Cquery(cquery) => {
+                let result = QUERY_FRONTEND
+                    .get()?
+                    .eval_cquery(
+                        dice,
+                        &cquery.working_dir,
+                        CqueryOwnerBehavior::Correct,
+                        &cquery.query,
+                        &cquery.query_args,
+                        cquery.global_cfg_options.dupe(),
+                        cquery.target_universe.as_deref(),
+                    )
+                    .await?;
+                Ok(LazyValue::Cquery(result))
+            }
+        }
+    }
+
+    /// Evaluate all the operations concurrently. A failure of one operation does not prevent the
+    /// others from completing; the results are returned in the same order as the operations.
+    pub(crate) async fn resolve_all(
+        dice: &mut DiceComputations<'_>,
+        operations: &[Arc<LazyOperation>],
+    ) -> Vec<anyhow::Result<LazyValue>> {
+        dice.compute_join(operations, |dice, operation| {
+            async move { operation.resolve(dice).await }.boxed()
+        })
+        .await
+    }
+}
+
+impl LazyValue {
+    /// Allocate the value. Build results are also recorded on `ctx`, like those of `ctx.build()`.
+    pub(crate) fn alloc<'v>(
+        self,
+        ctx: &BxlContext<'v>,
+        heap: &'v Heap,
+    ) -> anyhow::Result<Value<'v>> {
+        match self {
+            LazyValue::Analysis(label, result) => {
+                Ok(heap.alloc(StarlarkAnalysisResult::new(result, label)?))
+            }
+            LazyValue::ConfiguredTargetNode(node) => {
+                Ok(heap.alloc(StarlarkConfiguredTargetNode(node)))
+            }
+            LazyValue::Build(result) => {
+                ctx.add_build_result(result.clone())?;
+                Ok(heap.alloc(StarlarkBxlBuildResult(result)))
+            }
+            LazyValue::Cquery(result) => parse_query_evaluation_result(result, heap),
+        }
+    }
+}
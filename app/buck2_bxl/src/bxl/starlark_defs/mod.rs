@@ -16,6 +16,7 @@ pub(crate) mod artifacts;
 pub(crate) mod audit;
 pub(crate) mod build_result;
 pub(crate) mod bxl_function;
+pub(crate) mod bxl_test;
 pub(crate) mod cli_args;
 pub(crate) mod context;
 pub(crate) mod cquery;
@@ -24,6 +25,7 @@ pub(crate) mod file_expr;
 pub(crate) mod file_set;
 pub(crate) mod functions;
 pub(crate) mod globals;
+pub(crate) mod lazy_ctx;
 pub(crate) mod nodes;
 pub(crate) mod providers_expr;
 mod query_util;
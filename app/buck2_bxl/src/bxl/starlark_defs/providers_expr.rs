@@ -221,7 +221,7 @@ impl<P: ProvidersLabelMaybeConfigured> ProvidersExpr<P> {
         }
     }
 
-    fn unpack_providers_label<'v>(
+    pub(crate) fn unpack_providers_label<'v>(
         arg: ProvidersLabelArg<'v>,
         ctx: &BxlContextNoDice<'_>,
     ) -> anyhow::Result<ProvidersLabel> {
@@ -10,8 +10,8 @@
 use buck2_interpreter::error::BuckStarlarkError;
 use buck2_query::query::syntax::simple::eval::values::QueryEvaluationResult;
 use buck2_query::query::syntax::simple::eval::values::QueryEvaluationValue;
-use starlark::eval::Evaluator;
 use starlark::values::dict::Dict;
+use starlark::values::Heap;
 use starlark::values::Value;
 
 use super::targetset::NodeLike;
@@ -20,31 +20,28 @@ use crate::bxl::starlark_defs::targetset::StarlarkTargetSet;
 
 pub(crate) fn parse_query_evaluation_result<'v, T: NodeLike>(
     result: QueryEvaluationResult<T>,
-    eval: &mut Evaluator<'v, '_, '_>,
+    heap: &'v Heap,
 ) -> anyhow::Result<Value<'v>> {
     Ok(match result {
         QueryEvaluationResult::Single(result) => match result {
             QueryEvaluationValue::TargetSet(targets) => {
-                eval.heap().alloc(StarlarkTargetSet::from(targets))
+                heap.alloc(StarlarkTargetSet::from(targets))
             }
-            QueryEvaluationValue::FileSet(files) => eval.heap().alloc(StarlarkFileSet::from(files)),
+            QueryEvaluationValue::FileSet(files) => heap.alloc(StarlarkFileSet::from(files)),
         },
-        QueryEvaluationResult::Multiple(multi) => eval.heap().alloc(Dict::new(
+        QueryEvaluationResult::Multiple(multi) => heap.alloc(Dict::new(
             multi
                 .0
                 .into_iter()
                 .map(|(q, res)| {
                     Ok((
-                        eval.heap()
-                            .alloc(q)
-                            .get_hashed()
-                            .map_err(BuckStarlarkError::new)?,
+                        heap.alloc(q).get_hashed().map_err(BuckStarlarkError::new)?,
                         match res? {
                             QueryEvaluationValue::TargetSet(targets) => {
-                                eval.heap().alloc(StarlarkTargetSet::from(targets))
+                                heap.alloc(StarlarkTargetSet::from(targets))
                             }
                             QueryEvaluationValue::FileSet(files) => {
-                                eval.heap().alloc(StarlarkFileSet::from(files))
+                                heap.alloc(StarlarkFileSet::from(files))
                             }
                         },
                     ))
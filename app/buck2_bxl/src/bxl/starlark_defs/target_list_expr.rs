@@ -135,7 +135,7 @@ pub(crate) enum TargetNodeOrTargetLabel<'v> {
 }
 
 impl<'v> TargetNodeOrTargetLabel<'v> {
-    fn label(&self) -> &'v TargetLabel {
+    pub(crate) fn label(&self) -> &'v TargetLabel {
         match self {
             TargetNodeOrTargetLabel::TargetNode(node) => node.0.label(),
             TargetNodeOrTargetLabel::TargetLabel(label) => label.label(),
@@ -476,7 +476,7 @@ fn uquery_methods(builder: &mut MethodsBuilder) {
                                 GlobalCfgOptions::default(),
                             )
                             .await?,
-                        eval,
+                        eval.heap(),
                     )
                 }
                 .boxed_local()
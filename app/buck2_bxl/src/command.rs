@@ -69,6 +69,7 @@ use crate::bxl::eval::resolve_cli_args;
 use crate::bxl::eval::BxlResolvedCliArgs;
 use crate::bxl::eval::CliResolutionCtx;
 use crate::bxl::key::BxlKey;
+use crate::test_command::bxl_test;
 
 pub(crate) async fn bxl_command(
     ctx: &dyn ServerCommandContextTrait,
@@ -132,6 +133,10 @@ async fn bxl(
     mut ctx: DiceTransaction,
     request: &BxlRequest,
 ) -> anyhow::Result<buck2_cli_proto::BxlResponse> {
+    if request.test {
+        return bxl_test(server_ctx, stdout, ctx, request).await;
+    }
+
     let cwd = server_ctx.working_dir();
     let cell_resolver = ctx.get_cell_resolver().await?;
     let bxl_label = parse_bxl_label_from_cli(cwd, &request.bxl_label, &cell_resolver)?;
@@ -238,6 +243,7 @@ async fn bxl(
                 .map(|(k, v)| (k.to_owned(), Some(v.to_owned())))
                 .collect::<BTreeMap<_, _>>(),
             &BTreeMap::default(),
+            None,
         )?
     } else {
         None
@@ -277,7 +283,7 @@ pub(crate) async fn get_bxl_cli_args(
     resolve_cli_args(bxl_label, &cli_ctx, bxl_args, &frozen_callable).await
 }
 
-async fn copy_output<W: Write>(
+pub(crate) async fn copy_output<W: Write>(
     mut output: W,
     dice: &mut DiceComputations<'_>,
     output_loc: &BuckOutPath,
@@ -303,7 +309,7 @@ async fn copy_output<W: Write>(
     Ok(())
 }
 
-async fn ensure_artifacts(
+pub(crate) async fn ensure_artifacts(
     ctx: &mut DiceComputations<'_>,
     materialization_ctx: &MaterializationContext,
     target_results: impl IntoIterator<Item = &ConfiguredBuildTargetResult>,
@@ -422,7 +428,7 @@ pub(crate) fn parse_bxl_label_from_cli(
     })
 }
 
-fn filter_bxl_build_results(
+pub(crate) fn filter_bxl_build_results(
     build_results: Option<&Vec<BxlBuildResult>>,
 ) -> BTreeMap<ConfiguredProvidersLabel, ConfiguredBuildTargetResult> {
     let mut btree = BTreeMap::new();
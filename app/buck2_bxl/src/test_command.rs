@@ -0,0 +1,226 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! `buck2 bxl --test`: runs the `bxl_test`s declared in a bxl file.
+
+use std::io::Write;
+use std::sync::Arc;
+
+use anyhow::Context;
+use buck2_build_api::actions::artifact::get_artifact_fs::GetArtifactFs;
+use buck2_build_api::artifact_groups::ArtifactGroup;
+use buck2_build_api::build::ConvertMaterializationContext;
+use buck2_build_api::bxl::calculation::BxlComputeResult;
+use buck2_cli_proto::build_request::Materializations;
+use buck2_cli_proto::BxlRequest;
+use buck2_cli_proto::BxlResponse;
+use buck2_cli_proto::HasClientContext;
+use buck2_common::dice::cells::HasCellResolver;
+use buck2_common::global_cfg_options::GlobalCfgOptions;
+use buck2_core::cells::CellResolver;
+use buck2_events::errors::create_error_report;
+use buck2_interpreter::load_module::InterpreterCalculation;
+use buck2_interpreter::paths::bxl::BxlFilePath;
+use buck2_interpreter::paths::module::StarlarkModulePath;
+use buck2_server_ctx::ctx::ServerCommandContextTrait;
+use buck2_server_ctx::pattern::global_cfg_options_from_client_context;
+use dice::DiceTransaction;
+use dupe::Dupe;
+
+use crate::bxl::calculation::eval_bxl;
+use crate::bxl::eval::BxlResolvedCliArgs;
+use crate::bxl::key::BxlKey;
+use crate::bxl::starlark_defs::bxl_test::FrozenBxlTest;
+use crate::command::copy_output;
+use crate::command::ensure_artifacts;
+use crate::command::filter_bxl_build_results;
+use crate::command::get_bxl_cli_args;
+use crate::command::parse_bxl_label_from_cli;
+
+#[derive(Debug, buck2_error::Error)]
+enum BxlTestCommandError {
+    #[error("No `bxl_test` found in `{0}`")]
+    NoTests(BxlFilePath),
+    #[error("`{0}` is not a `bxl_test` in `{1}`")]
+    NotATest(String, BxlFilePath),
+    #[error("Arguments of a `bxl_test` must not request `--help`")]
+    Help,
+    #[error("BXL test `{0}` failed")]
+    Failed(String),
+}
+
+pub(crate) async fn bxl_test(
+    server_ctx: &dyn ServerCommandContextTrait,
+    mut stdout: impl Write,
+    mut ctx: DiceTransaction,
+    request: &BxlRequest,
+) -> anyhow::Result<BxlResponse> {
+    let cwd = server_ctx.working_dir();
+    let cell_resolver = ctx.get_cell_resolver().await?;
+    // Either `cell//path/file.bxl` to run all the tests in the file, or
+    // `cell//path/file.bxl:test_name` to run one.
+    let (bxl_path, test_name) = if request.bxl_label.ends_with(".bxl") {
+        let label =
+            parse_bxl_label_from_cli(cwd, &format!("{}:", request.bxl_label), &cell_resolver)?;
+        (label.bxl_path, None)
+    } else {
+        let label = parse_bxl_label_from_cli(cwd, &request.bxl_label, &cell_resolver)?;
+        (label.bxl_path, Some(label.name))
+    };
+
+    let client_ctx = request.client_context()?;
+    let global_cfg_options =
+        global_cfg_options_from_client_context(client_ctx, server_ctx, &mut ctx).await?;
+
+    let module = ctx
+        .get_loaded_module(StarlarkModulePath::BxlFile(&bxl_path))
+        .await?;
+    let single_test = test_name.is_some();
+    let mut names: Vec<String> = match test_name {
+        Some(name) => vec![name],
+        None => module
+            .env()
+            .names()
+            .map(|n| n.as_str().to_owned())
+            .collect(),
+    };
+    names.sort();
+    let mut tests = Vec::new();
+    for name in names {
+        match module
+            .env()
+            .get_any_visibility(&name)?
+            .0
+            .downcast::<FrozenBxlTest>()
+        {
+            Ok(test) => tests.push((name, test)),
+            Err(_) if single_test => {
+                return Err(BxlTestCommandError::NotATest(name, bxl_path).into());
+            }
+            Err(_) => {}
+        }
+    }
+    if tests.is_empty() {
+        return Err(BxlTestCommandError::NoTests(bxl_path).into());
+    }
+
+    let mut passed = 0;
+    let mut errors = Vec::new();
+    for (name, test) in tests {
+        let result = run_bxl_test(
+            server_ctx,
+            &mut ctx,
+            &cell_resolver,
+            &global_cfg_options,
+            request,
+            &test,
+        )
+        .await;
+        match result {
+            Ok(()) => {
+                writeln!(stdout, "✓ Pass: {}", name)?;
+                passed += 1;
+            }
+            Err(e) => {
+                writeln!(stdout, "✗ Fail: {}", name)?;
+                let e: buck2_error::Error = e.context(BxlTestCommandError::Failed(name)).into();
+                errors.push(create_error_report(&e));
+            }
+        }
+    }
+    writeln!(
+        stdout,
+        "Tests finished: Pass {}. Fail {}.",
+        passed,
+        errors.len()
+    )?;
+
+    Ok(BxlResponse {
+        project_root: server_ctx.project_root().to_string(),
+        errors,
+        serialized_build_report: None,
+    })
+}
+
+/// Run the bxl function of a test, ensure its artifacts, and check the results.
+async fn run_bxl_test(
+    server_ctx: &dyn ServerCommandContextTrait,
+    ctx: &mut DiceTransaction,
+    cell_resolver: &CellResolver,
+    global_cfg_options: &GlobalCfgOptions,
+    request: &BxlRequest,
+    test: &FrozenBxlTest,
+) -> anyhow::Result<()> {
+    let bxl_label = (*test.bxl_label()?).clone();
+    let bxl_args = match get_bxl_cli_args(
+        server_ctx.working_dir(),
+        ctx,
+        &bxl_label,
+        &test.spec().args,
+        cell_resolver,
+    )
+    .await?
+    {
+        BxlResolvedCliArgs::Resolved(bxl_args) => Arc::new(bxl_args),
+        BxlResolvedCliArgs::Help => return Err(BxlTestCommandError::Help.into()),
+    };
+
+    let bxl_key = BxlKey::new(
+        bxl_label,
+        bxl_args,
+        request.print_stacktrace,
+        global_cfg_options.dupe(),
+    );
+    let BxlComputeResult {
+        bxl_result,
+        materializations,
+    } = eval_bxl(ctx, bxl_key).await?;
+
+    let final_artifact_materializations =
+        Materializations::from_i32(request.final_artifact_materializations)
+            .context("Invalid final_artifact_materializations")?;
+    let materialization_context = ConvertMaterializationContext::with_existing_map(
+        final_artifact_materializations,
+        &Arc::new((*materializations).clone()),
+    );
+    let build_results = filter_bxl_build_results(bxl_result.get_build_result_opt());
+    if let Err(errors) = ensure_artifacts(
+        ctx,
+        &materialization_context,
+        build_results.values(),
+        bxl_result.get_artifacts_opt(),
+    )
+    .await
+    {
+        if let Some(e) = errors.into_iter().next() {
+            return Err(e.into());
+        }
+    }
+
+    let artifact_fs = ctx.get_artifact_fs().await?;
+    let mut artifacts = Vec::new();
+    for group in bxl_result.get_artifacts_opt().into_iter().flatten() {
+        if let ArtifactGroup::Artifact(artifact) = group {
+            artifacts.push(artifact.get_path().resolve(&artifact_fs)?.to_string());
+        }
+    }
+    for result in build_results.values() {
+        for artifacts_result in result.outputs.iter().flatten() {
+            for (artifact, _value) in artifacts_result.values.iter() {
+                artifacts.push(artifact.get_path().resolve(&artifact_fs)?.to_string());
+            }
+        }
+    }
+
+    let mut output = Vec::new();
+    copy_output(&mut output, ctx, bxl_result.get_output_loc()).await?;
+
+    test.spec()
+        .check(&String::from_utf8_lossy(&output), &artifacts)
+}
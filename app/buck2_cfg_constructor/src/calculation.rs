@@ -17,6 +17,7 @@ use buck2_core::bzl::ImportPath;
 use buck2_core::cells::cell_path::CellPathRef;
 use buck2_core::cells::paths::CellRelativePath;
 use buck2_core::configuration::data::ConfigurationData;
+use buck2_core::target::label::TargetLabel;
 use buck2_interpreter::paths::package::PackageFilePath;
 use buck2_interpreter_for_build::interpreter::package_file_calculation::EvalPackageFile;
 use buck2_node::cfg_constructor::CfgConstructorCalculationImpl;
@@ -163,6 +164,14 @@ impl CfgConstructorCalculationImpl for CfgConstructorCalculationInstance {
         };
         Ok(ctx.compute(&key).await??)
     }
+
+    async fn validate_cli_modifiers(
+        &self,
+        ctx: &mut DiceComputations<'_>,
+        cli_modifiers: &[TargetLabel],
+    ) -> anyhow::Result<()> {
+        crate::cli_modifiers::validate_cli_modifiers(ctx, cli_modifiers).await
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
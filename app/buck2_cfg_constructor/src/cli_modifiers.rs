@@ -0,0 +1,202 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+
+use anyhow::Context;
+use buck2_build_api::analysis::calculation::RuleAnalysisCalculation;
+use buck2_build_api::interpreter::rule_defs::provider::builtin::configuration_info::FrozenConfigurationInfo;
+use buck2_core::configuration::constraints::ConstraintKey;
+use buck2_core::configuration::constraints::ConstraintValue;
+use buck2_core::target::label::TargetLabel;
+use buck2_events::dispatch::console_message;
+use buck2_node::nodes::frontend::TargetGraphCalculation;
+use buck2_node::nodes::unconfigured::RuleKind;
+use dice::DiceComputations;
+use dupe::Dupe;
+use futures::FutureExt;
+
+#[derive(Debug, buck2_error::Error)]
+#[buck2(user)]
+enum CliModifierError {
+    #[error("Unknown modifier `{0}`")]
+    Unknown(TargetLabel),
+    #[error(
+        "Modifier `{0}` must be a configuration target, like a `constraint_value` or a `config_setting`, but it is a `{1}`"
+    )]
+    NotConfigurationTarget(TargetLabel, String),
+    #[error("Modifier `{0}` has no `ConfigurationInfo` provider")]
+    MissingConfigurationInfo(TargetLabel),
+}
+
+/// Two modifiers setting a constraint setting to different values. The later one wins, which is
+/// allowed but is usually a mistake, so it is reported.
+#[derive(Debug, PartialEq, derive_more::Display)]
+#[display(
+    fmt = "Modifier `{}` overrides modifier `{}`: they set constraint setting `{}` to `{}` and `{}`",
+    second,
+    first,
+    setting,
+    first_value,
+    second_value
+)]
+struct CliModifierConflict {
+    first: TargetLabel,
+    second: TargetLabel,
+    setting: ConstraintKey,
+    first_value: ConstraintValue,
+    second_value: ConstraintValue,
+}
+
+/// Constraints set by a modifier, which is either a `constraint_value` (one constraint) or a
+/// `config_setting` (any number of constraints).
+async fn modifier_constraints(
+    ctx: &mut DiceComputations<'_>,
+    modifier: &TargetLabel,
+) -> anyhow::Result<BTreeMap<ConstraintKey, ConstraintValue>> {
+    let node = ctx
+        .get_target_node(modifier)
+        .await
+        .with_context(|| CliModifierError::Unknown(modifier.dupe()))?;
+    if node.rule_kind() != RuleKind::Configuration {
+        return Err(CliModifierError::NotConfigurationTarget(
+            modifier.dupe(),
+            node.rule_type().name().to_owned(),
+        )
+        .into());
+    }
+    let result = ctx.get_configuration_analysis_result(modifier).await?;
+    let configuration_info = result
+        .providers()
+        .provider_collection()
+        .builtin_provider::<FrozenConfigurationInfo>()
+        .ok_or_else(|| CliModifierError::MissingConfigurationInfo(modifier.dupe()))?;
+    Ok(configuration_info.to_config_setting_data().constraints)
+}
+
+/// Check that every modifier passed on the command line is a configuration target, and warn when
+/// two of them set the same constraint setting to different values.
+pub(crate) async fn validate_cli_modifiers(
+    ctx: &mut DiceComputations<'_>,
+    cli_modifiers: &[TargetLabel],
+) -> anyhow::Result<()> {
+    let constraints = ctx
+        .try_compute_join(cli_modifiers, |ctx: &mut _, modifier| {
+            async move { anyhow::Ok((modifier, modifier_constraints(ctx, modifier).await?)) }
+                .boxed()
+        })
+        .await?;
+
+    for conflict in conflicts(constraints) {
+        console_message(conflict.to_string());
+    }
+    Ok(())
+}
+
+/// Every pair of consecutive modifiers (in command line order) setting a constraint setting to
+/// different values.
+fn conflicts<'a>(
+    constraints: impl IntoIterator<Item = (&'a TargetLabel, BTreeMap<ConstraintKey, ConstraintValue>)>,
+) -> Vec<CliModifierConflict> {
+    let mut settings: BTreeMap<ConstraintKey, (&TargetLabel, ConstraintValue)> = BTreeMap::new();
+    let mut conflicts = Vec::new();
+    for (modifier, constraints) in constraints {
+        for (setting, value) in constraints {
+            match settings.entry(setting) {
+                Entry::Vacant(e) => {
+                    e.insert((modifier, value));
+                }
+                Entry::Occupied(mut e) => {
+                    let (first, first_value) = e.get();
+                    if *first_value != value {
+                        conflicts.push(CliModifierConflict {
+                            first: (*first).dupe(),
+                            second: modifier.dupe(),
+                            setting: e.key().dupe(),
+                            first_value: first_value.dupe(),
+                            second_value: value.dupe(),
+                        });
+                    }
+                    e.insert((modifier, value));
+                }
+            }
+        }
+    }
+    conflicts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn constraints(pairs: &[(&str, &str)]) -> BTreeMap<ConstraintKey, ConstraintValue> {
+        pairs
+            .iter()
+            .map(|(setting, value)| {
+                (
+                    ConstraintKey(TargetLabel::testing_parse(setting)),
+                    ConstraintValue(TargetLabel::testing_parse(value)),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_no_conflicts() {
+        let dev = TargetLabel::testing_parse("root//mode:dev");
+        let linux = TargetLabel::testing_parse("root//os:linux");
+        let dev_linux = TargetLabel::testing_parse("root//mode:dev_linux");
+        assert_eq!(
+            Vec::<CliModifierConflict>::new(),
+            conflicts([
+                (&dev, constraints(&[("root//mode:mode", "root//mode:dev")])),
+                (&linux, constraints(&[("root//os:os", "root//os:linux")])),
+                (
+                    &dev_linux,
+                    constraints(&[
+                        ("root//mode:mode", "root//mode:dev"),
+                        ("root//os:os", "root//os:linux")
+                    ])
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let dev = TargetLabel::testing_parse("root//mode:dev");
+        let release = TargetLabel::testing_parse("root//mode:release");
+        let dev_linux = TargetLabel::testing_parse("root//mode:dev_linux");
+        let conflicts = conflicts([
+            (&dev, constraints(&[("root//mode:mode", "root//mode:dev")])),
+            (
+                &release,
+                constraints(&[("root//mode:mode", "root//mode:release")]),
+            ),
+            (
+                &dev_linux,
+                constraints(&[
+                    ("root//mode:mode", "root//mode:dev"),
+                    ("root//os:os", "root//os:linux"),
+                ]),
+            ),
+        ]);
+        assert_eq!(
+            vec![
+                "Modifier `root//mode:release` overrides modifier `root//mode:dev`: they set \
+                 constraint setting `root//mode:mode` to `root//mode:dev` and `root//mode:release`",
+                "Modifier `root//mode:dev_linux` overrides modifier `root//mode:release`: they \
+                 set constraint setting `root//mode:mode` to `root//mode:release` and \
+                 `root//mode:dev`",
+            ],
+            conflicts.iter().map(|c| c.to_string()).collect::<Vec<_>>()
+        );
+    }
+}
@@ -11,6 +11,7 @@
 #![feature(async_closure)]
 
 pub(crate) mod calculation;
+pub(crate) mod cli_modifiers;
 pub(crate) mod registration;
 
 use std::borrow::Borrow;
@@ -21,6 +21,7 @@ use buck2_client_ctx::common::CommonDaemonCommandOptions;
 use buck2_client_ctx::daemon::client::BuckdClientConnector;
 use buck2_client_ctx::daemon::client::StdoutPartialResultHandler;
 use buck2_client_ctx::exit_result::ExitResult;
+use buck2_client_ctx::immediate_config::ImmediateConfigContext;
 use buck2_client_ctx::path_arg::PathArg;
 use buck2_client_ctx::streaming::StreamingCommand;
 
@@ -34,6 +35,12 @@ pub struct BxlCommand {
     #[clap(flatten)]
     bxl_opts: BxlCommandOptions,
 
+    /// Run the `bxl_test`s declared in a bxl file and report which pass, instead of running a bxl
+    /// function. The label is either `<cell>//path/file.bxl` to run all the tests in the file, or
+    /// `<cell>//path/file.bxl:<test>` to run one.
+    #[clap(long)]
+    test: bool,
+
     #[clap(flatten)]
     common_ops: CommonCommandOptions,
 }
@@ -96,6 +103,7 @@ impl StreamingCommand for BxlCommand {
                     final_artifact_materializations: self.bxl_opts.materializations.to_proto()
                         as i32,
                     print_stacktrace: ctx.verbosity.print_success_stderr(),
+                    test: self.test,
                 },
                 ctx.stdin()
                     .console_interaction_stream(&self.common_ops.console_opts),
@@ -149,3 +157,124 @@ impl StreamingCommand for BxlCommand {
         &self.bxl_opts.user_event_log
     }
 }
+
+/// Rewrite `buck2 <alias> <args>...`, where `<alias>` is declared in the `[bxl_aliases]` section of
+/// the root buckconfig, into `buck2 bxl <function> -- <args>...`, so that teams can expose bxl
+/// scripts as custom subcommands. Options before the alias are kept as they are.
+///
+/// This only reads the buckconfig, so `buck2 <alias> --help` is answered from the `cli_args` of the
+/// bxl function without running any analysis.
+pub fn expand_bxl_alias(
+    command: &clap::Command<'_>,
+    args: Vec<String>,
+    immediate_config: &ImmediateConfigContext,
+) -> Vec<String> {
+    let position = match subcommand_position(command, &args) {
+        Some(position) => position,
+        None => return args,
+    };
+    let name = args[position].as_str();
+    if name == "help" || command.find_subcommand(name).is_some() {
+        return args;
+    }
+    // Outside of a project there is no buckconfig, so there are no aliases: let clap report the
+    // unknown subcommand.
+    let label = match immediate_config.bxl_alias(name) {
+        Ok(Some(label)) => label.to_owned(),
+        Ok(None) | Err(_) => return args,
+    };
+
+    let mut expanded = args[..position].to_vec();
+    expanded.extend(["bxl".to_owned(), label, "--".to_owned()]);
+    expanded.extend(args[position + 1..].iter().cloned());
+    expanded
+}
+
+/// The index of the subcommand in `args` (which starts with the binary name), skipping the options
+/// of `command` that come before it and their values.
+fn subcommand_position(command: &clap::Command<'_>, args: &[String]) -> Option<usize> {
+    let takes_value = |matches: &dyn Fn(&clap::Arg) -> bool| {
+        command
+            .get_arguments()
+            .any(|arg| matches(arg) && arg.is_takes_value_set())
+    };
+
+    let mut i = 1;
+    while i < args.len() {
+        let arg = args[i].as_str();
+        if arg == "--" {
+            return None;
+        } else if let Some(long) = arg.strip_prefix("--") {
+            if !long.contains('=') && takes_value(&|a| a.get_long() == Some(long)) {
+                i += 1;
+            }
+        } else if let Some(short) = arg.strip_prefix('-') {
+            // `-v2` and `-v=2` carry their value, only a lone `-v` takes the next argument.
+            let mut chars = short.chars();
+            if let (Some(c), None) = (chars.next(), chars.next()) {
+                if takes_value(&|a| a.get_short() == Some(c)) {
+                    i += 1;
+                }
+            }
+        } else {
+            return Some(i);
+        }
+        i += 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| (*a).to_owned()).collect()
+    }
+
+    #[test]
+    fn test_subcommand_position() {
+        let command = clap::Command::new("buck2")
+            .arg(clap::Arg::new("verbose").short('v').takes_value(true))
+            .arg(
+                clap::Arg::new("isolation-dir")
+                    .long("isolation-dir")
+                    .takes_value(true),
+            )
+            .arg(clap::Arg::new("no-buckd").long("no-buckd"));
+
+        assert_eq!(
+            Some(1),
+            subcommand_position(&command, &args(&["buck2", "ide-gen", "--foo"]))
+        );
+        assert_eq!(
+            Some(4),
+            subcommand_position(
+                &command,
+                &args(&["buck2", "-v", "2", "--no-buckd", "ide-gen"])
+            )
+        );
+        assert_eq!(
+            Some(3),
+            subcommand_position(
+                &command,
+                &args(&["buck2", "--isolation-dir=foo", "-v3", "ide-gen"])
+            )
+        );
+        assert_eq!(
+            Some(3),
+            subcommand_position(
+                &command,
+                &args(&["buck2", "--isolation-dir", "foo", "build"])
+            )
+        );
+        assert_eq!(
+            None,
+            subcommand_position(&command, &args(&["buck2", "--no-buckd"]))
+        );
+        assert_eq!(
+            None,
+            subcommand_position(&command, &args(&["buck2", "--", "ide-gen"]))
+        );
+    }
+}
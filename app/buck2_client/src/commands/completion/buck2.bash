@@ -0,0 +1,35 @@
+# Bash completion for buck2, printed by `buck2 completion bash`.
+# Candidates are computed by `buck2 complete`, which never starts a daemon.
+
+_buck2() {
+    local line="${COMP_LINE:0:COMP_POINT}"
+    local -a words
+    read -r -a words <<< "$line"
+    # The word being completed is empty after a trailing space.
+    if [[ "$line" =~ [[:space:]]$ ]]; then
+        words+=("")
+    fi
+    local cur="${words[${#words[@]}-1]}"
+
+    local IFS=$'\n'
+    local -a candidates
+    candidates=($("${words[0]}" complete -- "${words[@]:1}" 2>/dev/null))
+
+    # Bash splits words at `:`, so only the part after the last colon is replaced.
+    local colon_prefix=""
+    if [[ "$cur" == *:* && "$COMP_WORDBREAKS" == *:* ]]; then
+        colon_prefix="${cur%"${cur##*:}"}"
+    fi
+
+    COMPREPLY=()
+    local candidate
+    for candidate in "${candidates[@]}"; do
+        COMPREPLY+=("${candidate#"$colon_prefix"}")
+        # Packages and directories are completed further, so do not end the word.
+        if [[ "$candidate" == */ || "$candidate" == *: ]]; then
+            compopt -o nospace 2>/dev/null
+        fi
+    done
+}
+
+complete -F _buck2 buck2
@@ -0,0 +1,11 @@
+# Fish completion for buck2, printed by `buck2 completion fish`.
+# Candidates are computed by `buck2 complete`, which never starts a daemon.
+
+function __buck2_complete
+    set -l words (commandline -opc)
+    set -l buck2 $words[1]
+    set -e words[1]
+    $buck2 complete -- $words (commandline -ct | string collect --allow-empty) 2>/dev/null
+end
+
+complete -c buck2 -f -a '(__buck2_complete)'
@@ -0,0 +1,297 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+mod modifier;
+mod target;
+
+use buck2_client_ctx::client_ctx::ClientCommandContext;
+use buck2_client_ctx::exit_result::ExitResult;
+
+use crate::commands::completion::modifier::complete_modifier;
+use crate::commands::completion::target::complete_target;
+
+#[derive(Debug, Clone, Copy, clap::ArgEnum)]
+enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+#[derive(Debug, clap::Parser)]
+#[clap(
+    about = "Print a shell completion script",
+    long_about = "Print a shell completion script.\n\n\
+    Completion of subcommands, options, target patterns and `--modifier` values is computed by \
+    `buck2` itself when TAB is pressed. Cells and packages are completed from the filesystem, \
+    target names and modifiers are asked to the daemon if one is running, and a daemon is never \
+    started.\n\n\
+    For example, add `source <(buck2 completion bash)` to `~/.bashrc`, \
+    `source <(buck2 completion zsh)` to `~/.zshrc`, or \
+    `buck2 completion fish | source` to `~/.config/fish/config.fish`."
+)]
+pub struct CompletionCommand {
+    #[clap(arg_enum)]
+    shell: Shell,
+}
+
+impl CompletionCommand {
+    pub fn exec(
+        self,
+        _matches: &clap::ArgMatches,
+        _ctx: ClientCommandContext<'_>,
+    ) -> anyhow::Result<()> {
+        let script = match self.shell {
+            Shell::Bash => include_str!("buck2.bash"),
+            Shell::Zsh => include_str!("buck2.zsh"),
+            Shell::Fish => include_str!("buck2.fish"),
+        };
+        buck2_client_ctx::print!("{}", script)?;
+        Ok(())
+    }
+}
+
+/// Print the completions of the last word of a command line, one per line. This is what the
+/// scripts printed by `buck2 completion` call.
+#[derive(Debug, clap::Parser)]
+pub struct CompleteCommand {
+    /// Words of the command line after `buck2`, the last one being the word to complete (which
+    /// may be empty).
+    #[clap(allow_hyphen_values = true, multiple_values = true)]
+    words: Vec<String>,
+}
+
+/// What the word under the cursor is.
+#[derive(Debug, PartialEq)]
+enum Completing {
+    Subcommand,
+    Option,
+    Target,
+    Modifier,
+    Nothing,
+}
+
+/// Positional arguments with these names take target patterns.
+const TARGET_ARG_NAMES: &[&str] = &["TARGET_PATTERNS", "TARGET"];
+
+fn is_target_arg(arg: &clap::Arg<'_>) -> bool {
+    TARGET_ARG_NAMES.contains(&arg.get_id())
+        || arg
+            .get_value_names()
+            .unwrap_or_default()
+            .iter()
+            .any(|name| TARGET_ARG_NAMES.contains(name))
+}
+
+/// Finds the (sub)command the words are for, and what the last word is.
+fn completing<'c, 'h>(
+    command: &'c clap::Command<'h>,
+    words: &[String],
+) -> (Vec<&'c clap::Command<'h>>, Completing) {
+    let mut commands = vec![command];
+    let find_arg = |commands: &[&'c clap::Command<'h>], matches: &dyn Fn(&clap::Arg) -> bool| {
+        // Options of parent commands, like `--verbose`, are accepted by subcommands as well.
+        commands
+            .iter()
+            .rev()
+            .find_map(|c| c.get_arguments().find(|a| matches(a)))
+    };
+
+    let previous = match words.split_last() {
+        Some((_, previous)) => previous,
+        None => &[],
+    };
+    let mut takes_value = None;
+    let mut after_double_dash = false;
+    for word in previous {
+        if takes_value.take().is_some() || after_double_dash {
+            continue;
+        }
+        if word == "--" {
+            after_double_dash = true;
+        } else if let Some(long) = word.strip_prefix("--") {
+            if !long.contains('=') {
+                takes_value = find_arg(&commands, &|a| a.get_long() == Some(long))
+                    .filter(|a| a.is_takes_value_set());
+            }
+        } else if let Some(short) = word.strip_prefix('-') {
+            // Only a lone `-m` takes the next word as its value.
+            let mut chars = short.chars();
+            if let (Some(c), None) = (chars.next(), chars.next()) {
+                takes_value = find_arg(&commands, &|a| a.get_short() == Some(c))
+                    .filter(|a| a.is_takes_value_set());
+            }
+        } else if let Some(sub) = commands.last().and_then(|c| c.find_subcommand(word)) {
+            commands.push(sub);
+        }
+    }
+
+    let current = words.last().map(|w| w.as_str()).unwrap_or_default();
+    let command = commands.last().expect("starts with the root command");
+    let completing = if let Some(arg) = takes_value {
+        if arg.get_long() == Some("modifier") {
+            Completing::Modifier
+        } else {
+            Completing::Nothing
+        }
+    } else if after_double_dash {
+        Completing::Nothing
+    } else if current.starts_with('-') {
+        Completing::Option
+    } else if command.has_subcommands() && command.get_positionals().next().is_none() {
+        Completing::Subcommand
+    } else if command.get_positionals().any(is_target_arg) {
+        Completing::Target
+    } else {
+        Completing::Nothing
+    };
+    (commands, completing)
+}
+
+fn complete_subcommand(command: &clap::Command<'_>, partial: &str) -> Vec<String> {
+    command
+        .get_subcommands()
+        .filter(|c| !c.is_hide_set())
+        .map(|c| c.get_name().to_owned())
+        .filter(|name| name.starts_with(partial))
+        .collect()
+}
+
+fn complete_option(commands: &[&clap::Command<'_>], partial: &str) -> Vec<String> {
+    let mut options: Vec<String> = commands
+        .iter()
+        .flat_map(|c| c.get_arguments())
+        .filter(|a| !a.is_hide_set())
+        .filter_map(|a| a.get_long())
+        .map(|long| format!("--{}", long))
+        .filter(|option| option.starts_with(partial))
+        .collect();
+    options.sort();
+    options.dedup();
+    options
+}
+
+impl CompleteCommand {
+    pub fn exec(
+        self,
+        _matches: &clap::ArgMatches,
+        ctx: ClientCommandContext<'_>,
+        command: &clap::Command<'_>,
+    ) -> ExitResult {
+        let (commands, completing) = completing(command, &self.words);
+        let partial = self.words.last().map(|w| w.as_str()).unwrap_or_default();
+        let completions = match completing {
+            Completing::Subcommand => {
+                complete_subcommand(commands.last().expect("not empty"), partial)
+            }
+            Completing::Option => complete_option(&commands, partial),
+            Completing::Target => {
+                let partial = partial.to_owned();
+                ctx.with_runtime(async move |ctx| complete_target(&ctx, &partial).await)?
+            }
+            Completing::Modifier => {
+                let partial = partial.to_owned();
+                ctx.with_runtime(async move |ctx| complete_modifier(&ctx, &partial).await)?
+            }
+            Completing::Nothing => Vec::new(),
+        };
+        for completion in completions {
+            buck2_client_ctx::println!("{}", completion)?;
+        }
+        ExitResult::success()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::completing;
+    use super::Completing;
+
+    #[derive(Debug, clap::Parser)]
+    #[clap(name = "buck2")]
+    struct Opt {
+        #[clap(short, long, global = true)]
+        verbose: Option<u32>,
+        #[clap(subcommand)]
+        cmd: Cmd,
+    }
+
+    #[derive(Debug, clap::Subcommand)]
+    enum Cmd {
+        Build {
+            #[clap(long, short = 'm')]
+            modifier: Vec<String>,
+            #[clap(name = "TARGET_PATTERNS")]
+            patterns: Vec<String>,
+        },
+        #[clap(subcommand)]
+        Audit(Audit),
+    }
+
+    #[derive(Debug, clap::Subcommand)]
+    enum Audit {
+        Cell { aliases: Vec<String> },
+    }
+
+    fn check(words: &[&str]) -> (Vec<String>, Completing) {
+        let command = <Opt as clap::CommandFactory>::command();
+        let words: Vec<String> = words.iter().map(|w| (*w).to_owned()).collect();
+        let (commands, completing) = completing(&command, &words);
+        (
+            commands.iter().map(|c| c.get_name().to_owned()).collect(),
+            completing,
+        )
+    }
+
+    #[test]
+    fn test_completing() {
+        assert_eq!(
+            (vec!["buck2".to_owned()], Completing::Subcommand),
+            check(&[])
+        );
+        assert_eq!(
+            (vec!["buck2".to_owned()], Completing::Subcommand),
+            check(&["-v", "2", "bu"])
+        );
+        assert_eq!(
+            (
+                vec!["buck2".to_owned(), "build".to_owned()],
+                Completing::Target
+            ),
+            check(&["build", "//foo:"])
+        );
+        assert_eq!(
+            (
+                vec!["buck2".to_owned(), "build".to_owned()],
+                Completing::Modifier
+            ),
+            check(&["build", "-m", ""])
+        );
+        assert_eq!(
+            (
+                vec!["buck2".to_owned(), "build".to_owned()],
+                Completing::Target
+            ),
+            check(&["build", "--modifier", "linux", ""])
+        );
+        assert_eq!(
+            (
+                vec!["buck2".to_owned(), "build".to_owned()],
+                Completing::Option
+            ),
+            check(&["build", "--mod"])
+        );
+        assert_eq!(
+            (
+                vec!["buck2".to_owned(), "audit".to_owned(), "cell".to_owned()],
+                Completing::Nothing
+            ),
+            check(&["audit", "cell", ""])
+        );
+    }
+}
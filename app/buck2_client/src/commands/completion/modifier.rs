@@ -0,0 +1,56 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use buck2_audit::modifiers::AuditModifiersCommand;
+use buck2_audit::AuditCommand;
+use buck2_client_ctx::client_ctx::ClientCommandContext;
+use buck2_client_ctx::command_outcome::CommandOutcome;
+use buck2_client_ctx::daemon::client::connect::BuckdConnectOptions;
+use buck2_client_ctx::daemon::client::CaptureStdout;
+
+/// Completions of a partially typed `--modifier` value, from `buck2 audit modifiers --values`
+/// run by a daemon which is already running. There are no completions without a daemon.
+pub(crate) async fn complete_modifier(
+    ctx: &ClientCommandContext<'_>,
+    partial: &str,
+) -> anyhow::Result<Vec<String>> {
+    let mut buckd = ctx
+        .connect_buckd(BuckdConnectOptions::existing_only_no_console())
+        .await?;
+    let mut capture = CaptureStdout::new();
+    let outcome = buckd
+        .with_flushing()
+        .audit(
+            buck2_cli_proto::GenericRequest {
+                context: Some(buck2_cli_proto::ClientContext {
+                    reuse_current_config: true,
+                    ..ctx.empty_client_context("complete")?
+                }),
+                serialized_opts: serde_json::to_string(&AuditCommand::Modifiers(
+                    AuditModifiersCommand {
+                        common_opts: Default::default(),
+                        patterns: Vec::new(),
+                        values: true,
+                    },
+                ))?,
+            },
+            None,
+            &mut capture,
+        )
+        .await?;
+    match outcome {
+        CommandOutcome::Success(..) => {}
+        CommandOutcome::Failure(..) => return Err(anyhow::anyhow!("Failed to list modifiers")),
+    }
+    Ok(String::from_utf8(capture.buf)?
+        .lines()
+        .filter(|value| value.starts_with(partial))
+        .map(|value| value.to_owned())
+        .collect())
+}
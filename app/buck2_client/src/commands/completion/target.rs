@@ -0,0 +1,275 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Completion of target patterns.
+//!
+//! Cell names and package paths are completed from the filesystem only. Target names are asked to
+//! a daemon if one is already running (it never starts one), and otherwise scraped from the build
+//! file.
+
+use buck2_cli_proto::targets_request;
+use buck2_cli_proto::targets_request::OutputFormat;
+use buck2_cli_proto::TargetsRequest;
+use buck2_client_ctx::client_ctx::ClientCommandContext;
+use buck2_client_ctx::command_outcome::CommandOutcome;
+use buck2_client_ctx::daemon::client::connect::BuckdConnectOptions;
+use buck2_client_ctx::daemon::client::NoPartialResultHandler;
+use buck2_common::file_ops::FileType;
+use buck2_common::file_ops::SimpleDirEntry;
+use buck2_common::find_buildfile::find_buildfile;
+use buck2_core::fs::paths::abs_norm_path::AbsNormPath;
+use buck2_core::fs::paths::abs_norm_path::AbsNormPathBuf;
+use buck2_core::fs::paths::file_name::FileNameBuf;
+
+/// Completions of a partially typed target pattern.
+pub(crate) async fn complete_target(
+    ctx: &ClientCommandContext<'_>,
+    partial: &str,
+) -> anyhow::Result<Vec<String>> {
+    match partial.rsplit_once(':') {
+        Some((package, name)) => complete_target_name(ctx, package, name).await,
+        None => complete_package(ctx, partial),
+    }
+}
+
+/// The directory of a package as typed by the user: `cell//path` or a path relative to the
+/// working directory.
+fn package_dir(ctx: &ClientCommandContext<'_>, package: &str) -> anyhow::Result<AbsNormPathBuf> {
+    match package.split_once("//") {
+        Some((cell_alias, path)) => ctx.immediate_config.resolve_cell_path(cell_alias, path),
+        None => ctx.working_dir.path().join_normalized(package),
+    }
+}
+
+fn list_dir(dir: &AbsNormPath) -> anyhow::Result<Vec<SimpleDirEntry>> {
+    let mut entries = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        // Names which are not valid file names can not be in a target pattern either.
+        let Some(file_name) = entry
+            .file_name()
+            .into_string()
+            .ok()
+            .and_then(|name| FileNameBuf::try_from(name).ok())
+        else {
+            continue;
+        };
+        entries.push(SimpleDirEntry {
+            file_name,
+            file_type: FileType::from(entry.file_type()?),
+        });
+    }
+    entries.sort();
+    Ok(entries)
+}
+
+/// Whether the directory is a package, i.e. contains a build file of its cell.
+fn is_package(
+    ctx: &ClientCommandContext<'_>,
+    dir: &AbsNormPath,
+    entries: &[SimpleDirEntry],
+) -> bool {
+    let buildfiles = (|| {
+        let project_root = ctx.immediate_config.project_root()?;
+        let cell_resolver = ctx.immediate_config.cell_resolver()?;
+        let cell = cell_resolver.find(&*project_root.relativize(dir)?)?;
+        anyhow::Ok(cell_resolver.get(cell)?.buildfiles().to_vec())
+    })();
+    match buildfiles {
+        Ok(buildfiles) => find_buildfile(&buildfiles, entries).is_some(),
+        Err(_) => false,
+    }
+}
+
+fn complete_package(ctx: &ClientCommandContext<'_>, partial: &str) -> anyhow::Result<Vec<String>> {
+    let mut completions = Vec::new();
+
+    let (typed_dir, prefix) = match partial.rsplit_once('/') {
+        Some((dir, prefix)) => (format!("{}/", dir), prefix),
+        None => (String::new(), partial),
+    };
+    if !partial.contains("//") && !partial.contains('/') {
+        // A bare word may be the start of a cell alias.
+        let cell_resolver = ctx.immediate_config.cell_resolver()?;
+        let project_root = ctx.immediate_config.project_root()?;
+        let cwd_cell = cell_resolver.find(&*project_root.relativize(ctx.working_dir.path())?)?;
+        let mut aliases: Vec<&str> = cell_resolver
+            .get(cwd_cell)?
+            .cell_alias_resolver()
+            .mappings()
+            .map(|(alias, _)| alias.as_str())
+            .filter(|alias| alias.starts_with(prefix))
+            .collect();
+        aliases.sort();
+        completions.extend(aliases.into_iter().map(|alias| format!("{}//", alias)));
+    }
+
+    // Drop the trailing slash of `foo/`, but not one of the two of `cell//`.
+    let dir = match typed_dir.strip_suffix('/') {
+        Some(dir) if !dir.ends_with('/') => dir,
+        _ => &typed_dir,
+    };
+    let dir = package_dir(ctx, dir)?;
+    let Ok(entries) = list_dir(&dir) else {
+        return Ok(completions);
+    };
+    if prefix.is_empty() && partial.ends_with("//") && is_package(ctx, &dir, &entries) {
+        completions.push(format!("{}:", partial));
+    }
+    for entry in entries {
+        let name = entry.file_name.as_str();
+        if entry.file_type != FileType::Directory
+            || !name.starts_with(prefix)
+            || (name.starts_with('.') && !prefix.starts_with('.'))
+            || name == "buck-out"
+        {
+            continue;
+        }
+        let sub_dir = dir.join(&entry.file_name);
+        let sub_entries = list_dir(&sub_dir).unwrap_or_default();
+        if is_package(ctx, &sub_dir, &sub_entries) {
+            completions.push(format!("{}{}:", typed_dir, name));
+        }
+        if sub_entries
+            .iter()
+            .any(|e| e.file_type == FileType::Directory)
+        {
+            completions.push(format!("{}{}/", typed_dir, name));
+        }
+    }
+    Ok(completions)
+}
+
+async fn complete_target_name(
+    ctx: &ClientCommandContext<'_>,
+    package: &str,
+    prefix: &str,
+) -> anyhow::Result<Vec<String>> {
+    let names = match target_names_from_daemon(ctx, package).await {
+        Ok(names) => names,
+        Err(e) => {
+            tracing::debug!("Completing target names without a daemon: {:#}", e);
+            target_names_from_buildfile(ctx, package)?
+        }
+    };
+    Ok(names
+        .into_iter()
+        .filter(|name| name.starts_with(prefix))
+        .map(|name| format!("{}:{}", package, name))
+        .collect())
+}
+
+async fn target_names_from_daemon(
+    ctx: &ClientCommandContext<'_>,
+    package: &str,
+) -> anyhow::Result<Vec<String>> {
+    let mut buckd = ctx
+        .connect_buckd(BuckdConnectOptions::existing_only_no_console())
+        .await?;
+    let request = TargetsRequest {
+        context: Some(buck2_cli_proto::ClientContext {
+            // Completion must not invalidate the state of the daemon.
+            reuse_current_config: true,
+            ..ctx.empty_client_context("complete")?
+        }),
+        target_patterns: vec![buck2_data::TargetPattern {
+            value: format!("{}:", package),
+        }],
+        output_format: OutputFormat::Text as i32,
+        targets: Some(targets_request::Targets::Other(targets_request::Other {
+            cached: true,
+            ..Default::default()
+        })),
+        output: None,
+        concurrency: None,
+    };
+    let response = match buckd
+        .with_flushing()
+        .targets(request, None, &mut NoPartialResultHandler)
+        .await?
+    {
+        CommandOutcome::Success(response) => response,
+        CommandOutcome::Failure(_) => {
+            return Err(anyhow::anyhow!("Failed to list targets of `{}`", package));
+        }
+    };
+    Ok(response
+        .serialized_targets_output
+        .lines()
+        .filter_map(|label| label.rsplit_once(':').map(|(_, name)| name.to_owned()))
+        .collect())
+}
+
+fn target_names_from_buildfile(
+    ctx: &ClientCommandContext<'_>,
+    package: &str,
+) -> anyhow::Result<Vec<String>> {
+    let dir = package_dir(ctx, package)?;
+    let entries = list_dir(&dir)?;
+    let project_root = ctx.immediate_config.project_root()?;
+    let cell_resolver = ctx.immediate_config.cell_resolver()?;
+    let cell = cell_resolver.find(&*project_root.relativize(&dir)?)?;
+    let Some(buildfile) = find_buildfile(cell_resolver.get(cell)?.buildfiles(), &entries) else {
+        return Ok(Vec::new());
+    };
+    let contents = std::fs::read_to_string(dir.join(buildfile).as_path())?;
+    Ok(names_in_buildfile(&contents))
+}
+
+/// Values of `name = "..."` arguments in a build file. This does not evaluate the file, so it
+/// misses targets whose names are computed by macros, and includes names of things which are not
+/// targets, but it is good enough when there is no daemon to ask.
+fn names_in_buildfile(contents: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut rest = contents;
+    while let Some(i) = rest.find("name") {
+        let before = &contents[..contents.len() - rest.len() + i];
+        rest = &rest[i + "name".len()..];
+        if before.ends_with(|c: char| c.is_alphanumeric() || c == '_') {
+            continue;
+        }
+        let Some(value) = rest.trim_start().strip_prefix('=') else {
+            continue;
+        };
+        let value = value.trim_start();
+        let Some(quote) = value.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            continue;
+        };
+        if let Some((name, _)) = value[1..].split_once(quote) {
+            if !name.contains('\n') && !names.iter().any(|n| n == name) {
+                names.push(name.to_owned());
+            }
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::names_in_buildfile;
+
+    #[test]
+    fn test_names_in_buildfile() {
+        let contents = r#"
+load("//rules:defs.bzl", "my_rule")
+
+rust_library(
+    name = "lib",
+    srcs = glob(["src/**/*.rs"]),
+)
+
+my_rule(name='other', out_name = "not_a_target")
+
+rust_binary(
+    name = "lib",
+)
+"#;
+        assert_eq!(vec!["lib", "other"], names_in_buildfile(contents));
+    }
+}
@@ -0,0 +1,203 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+use buck2_client_ctx::client_ctx::ClientCommandContext;
+use buck2_client_ctx::exit_result::ExitResult;
+use buck2_event_log::stream_value::StreamValue;
+use buck2_event_observer::display;
+use buck2_event_observer::display::TargetDisplayOptions;
+use tokio_stream::StreamExt;
+
+use crate::commands::log::options::EventLogOptions;
+use crate::commands::log::transform_format;
+use crate::commands::log::LogCommandOutputFormat;
+use crate::commands::log::LogCommandOutputFormatWithWriter;
+
+#[derive(Debug, thiserror::Error)]
+enum ActionInputsError {
+    #[error(
+        "No input manifest found for an action matching `{0}`. Input manifests are only \
+        recorded when `buck2.log_action_input_manifests = true` is set in buckconfig."
+    )]
+    NotFound(String),
+}
+
+/// Show the inputs of an action: every input path with its digest, the environment and the
+/// command line, followed by the paths of its outputs.
+///
+/// This requires the invocation to have run with `buck2.log_action_input_manifests = true`.
+/// The action is selected by its identity as displayed by other `buck2 log` commands (e.g.
+/// `root//foo:bar (cxx_compile foo.cpp)`); if no action matches exactly, every action whose
+/// identity contains the given string is shown.
+#[derive(Debug, clap::Parser)]
+pub struct ActionInputsCommand {
+    /// The action to show inputs for.
+    #[clap(value_name = "ACTION")]
+    action: String,
+
+    #[clap(flatten)]
+    event_log: EventLogOptions,
+
+    #[clap(
+        long = "format",
+        help = "Which output format to use for this command",
+        default_value = "tabulated",
+        ignore_case = true,
+        arg_enum
+    )]
+    output: LogCommandOutputFormat,
+}
+
+#[derive(serde::Serialize)]
+struct Record<'a> {
+    action: &'a str,
+    /// One of `digest`, `argv`, `env`, `input` or `output`.
+    kind: &'static str,
+    key: String,
+    value: String,
+}
+
+impl Display for Record<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}\t{}\t{}", self.kind, self.key, self.value)
+    }
+}
+
+fn manifest_records<'a>(
+    action: &'a str,
+    manifest: &buck2_data::ActionInputManifest,
+) -> Vec<Record<'a>> {
+    let mut records = vec![Record {
+        action,
+        kind: "digest",
+        key: String::new(),
+        value: manifest.action_digest.clone(),
+    }];
+    records.extend(manifest.argv.iter().enumerate().map(|(i, arg)| Record {
+        action,
+        kind: "argv",
+        key: i.to_string(),
+        value: arg.clone(),
+    }));
+    records.extend(manifest.env.iter().map(|e| Record {
+        action,
+        kind: "env",
+        key: e.key.clone(),
+        value: e.value.clone(),
+    }));
+    records.extend(manifest.inputs.iter().map(|input| {
+        use buck2_data::action_input_manifest_entry::Entry;
+        let value = match &input.entry {
+            Some(Entry::FileDigest(digest)) if input.is_executable => {
+                format!("{} (executable)", digest)
+            }
+            Some(Entry::FileDigest(digest)) => digest.clone(),
+            Some(Entry::SymlinkTarget(target)) => format!("-> {}", target),
+            None => String::new(),
+        };
+        Record {
+            action,
+            kind: "input",
+            key: input.path.clone(),
+            value,
+        }
+    }));
+    records.extend(manifest.outputs.iter().map(|output| Record {
+        action,
+        kind: "output",
+        key: output.clone(),
+        value: String::new(),
+    }));
+    records
+}
+
+fn print_record(
+    output: &mut LogCommandOutputFormatWithWriter,
+    record: &Record,
+) -> anyhow::Result<()> {
+    match output {
+        LogCommandOutputFormatWithWriter::Tabulated(w) => {
+            Ok(w.write_all(format!("{}\n", record).as_bytes())?)
+        }
+        LogCommandOutputFormatWithWriter::Csv(writer) => Ok(writer.serialize(record)?),
+        LogCommandOutputFormatWithWriter::Json(w) => {
+            serde_json::to_writer(&mut *w, &record)?;
+            Ok(w.write_all(b"\n")?)
+        }
+    }
+}
+
+impl ActionInputsCommand {
+    pub fn exec(self, _matches: &clap::ArgMatches, ctx: ClientCommandContext<'_>) -> ExitResult {
+        let Self {
+            action,
+            event_log,
+            output,
+        } = self;
+
+        buck2_client_ctx::stdio::print_with_writer::<anyhow::Error, _>(|w| {
+            let mut output = transform_format(output, w);
+            ctx.with_runtime(async move |ctx| {
+                let log_path = event_log.get(&ctx).await?;
+
+                let (invocation, mut events) = log_path.unpack_stream().await?;
+                buck2_client_ctx::eprintln!(
+                    "Showing action inputs from: {}",
+                    invocation.display_command_line()
+                )?;
+
+                // An action may run more than one command, so keep every manifest.
+                let mut manifests = Vec::new();
+                while let Some(event) = events.try_next().await? {
+                    let event = match event {
+                        StreamValue::Event(event) => event,
+                        StreamValue::Result(..) | StreamValue::PartialResult(..) => continue,
+                    };
+                    if let Some(buck2_data::buck_event::Data::Instant(instant)) = event.data {
+                        if let Some(buck2_data::instant_event::Data::ActionInputManifest(
+                            manifest,
+                        )) = instant.data
+                        {
+                            let identity = display::display_action_identity(
+                                manifest.key.as_ref(),
+                                manifest.name.as_ref(),
+                                TargetDisplayOptions::for_log(),
+                            )?;
+                            if identity.contains(&action) {
+                                manifests.push((identity, manifest));
+                            }
+                        }
+                    }
+                }
+
+                if manifests.iter().any(|(identity, _)| *identity == action) {
+                    manifests.retain(|(identity, _)| *identity == action);
+                }
+                if manifests.is_empty() {
+                    return Err(ActionInputsError::NotFound(action).into());
+                }
+
+                for (identity, manifest) in &manifests {
+                    if let LogCommandOutputFormatWithWriter::Tabulated(w) = &mut output {
+                        w.write_all(format!("# {}\n", identity).as_bytes())?;
+                    }
+                    for record in manifest_records(identity, manifest) {
+                        print_record(&mut output, &record)?;
+                    }
+                }
+
+                anyhow::Ok(())
+            })
+        })?;
+        ExitResult::success()
+    }
+}
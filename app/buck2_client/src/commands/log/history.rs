@@ -0,0 +1,541 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! A local SQLite database of build history, populated from event logs by `buck2 log ingest` and
+//! queried by `buck2 log sql`.
+
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use anyhow::Context;
+use buck2_client_ctx::client_ctx::ClientCommandContext;
+use buck2_client_ctx::path_arg::PathArg;
+use buck2_core::fs::fs_util;
+use buck2_core::fs::paths::abs_path::AbsPathBuf;
+use buck2_event_log::read::EventLogPathBuf;
+use buck2_event_log::stream_value::StreamValue;
+use buck2_event_observer::display;
+use buck2_event_observer::display::TargetDisplayOptions;
+use gazebo::variants::VariantName;
+use rusqlite::Connection;
+use rusqlite::OpenFlags;
+use tokio_stream::StreamExt;
+
+use crate::commands::log::critical_path::critical_path_entry_names;
+use crate::commands::log::critical_path::CriticalPathEntryNames;
+
+/// Bump this when the schema changes. Databases with a different version are recreated, since they
+/// can always be rebuilt from event logs.
+const SCHEMA_VERSION: i64 = 1;
+
+const SCHEMA: &str = "
+CREATE TABLE commands (
+    trace_id TEXT PRIMARY KEY NOT NULL,
+    command TEXT,
+    command_line TEXT NOT NULL,
+    working_dir TEXT NOT NULL,
+    start_time_ms INTEGER,
+    duration_ms INTEGER,
+    success INTEGER,
+    log_path TEXT NOT NULL
+);
+
+CREATE TABLE actions (
+    trace_id TEXT NOT NULL,
+    owner TEXT NOT NULL,
+    category TEXT NOT NULL,
+    identifier TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    execution_kind TEXT NOT NULL,
+    cache_hit INTEGER NOT NULL,
+    failed INTEGER NOT NULL,
+    start_time_ms INTEGER,
+    duration_ms INTEGER
+);
+CREATE INDEX actions_trace_id ON actions (trace_id);
+CREATE INDEX actions_owner ON actions (owner);
+
+CREATE TABLE test_results (
+    trace_id TEXT NOT NULL,
+    target TEXT NOT NULL,
+    name TEXT NOT NULL,
+    status TEXT NOT NULL,
+    duration_ms INTEGER
+);
+CREATE INDEX test_results_trace_id ON test_results (trace_id);
+
+CREATE TABLE critical_path (
+    trace_id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    name TEXT NOT NULL,
+    category TEXT NOT NULL,
+    identifier TEXT NOT NULL,
+    total_duration_us INTEGER,
+    user_duration_us INTEGER,
+    potential_improvement_us INTEGER
+);
+CREATE INDEX critical_path_trace_id ON critical_path (trace_id);
+
+CREATE TABLE errors (
+    trace_id TEXT NOT NULL,
+    category TEXT,
+    type TEXT,
+    message TEXT NOT NULL
+);
+CREATE INDEX errors_trace_id ON errors (trace_id);
+";
+
+const TABLES: &[&str] = &[
+    "commands",
+    "actions",
+    "test_results",
+    "critical_path",
+    "errors",
+];
+
+/// Where the build history database lives.
+#[derive(Debug, clap::Parser)]
+pub(crate) struct BuildHistoryDbOptions {
+    /// Path to the build history database. Defaults to `build_history.sqlite` in buck-out.
+    #[clap(long, value_name = "PATH")]
+    db: Option<PathArg>,
+}
+
+impl BuildHistoryDbOptions {
+    pub(crate) fn path(&self, ctx: &ClientCommandContext<'_>) -> anyhow::Result<AbsPathBuf> {
+        match &self.db {
+            Some(db) => Ok(db.resolve(&ctx.working_dir)),
+            None => Ok(ctx
+                .paths()
+                .context("Error identifying buck-out")?
+                .build_history_db_path()
+                .into_abs_path_buf()),
+        }
+    }
+}
+
+/// Opens the database, creating or recreating the schema if needed.
+pub(crate) fn open_build_history_db(path: &AbsPathBuf) -> anyhow::Result<Connection> {
+    if let Some(parent) = path.parent() {
+        fs_util::create_dir_all(parent)?;
+    }
+    let connection = Connection::open(path)
+        .with_context(|| format!("Error opening build history database `{}`", path.display()))?;
+
+    let version: i64 = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version != SCHEMA_VERSION {
+        for table in TABLES {
+            connection.execute(&format!("DROP TABLE IF EXISTS {}", table), [])?;
+        }
+        connection.execute_batch(SCHEMA)?;
+        connection.execute(&format!("PRAGMA user_version = {}", SCHEMA_VERSION), [])?;
+    }
+
+    Ok(connection)
+}
+
+/// Opens an existing database for querying. The database is opened read-only, so queries
+/// can't modify it, and a database with an older schema is not recreated.
+pub(crate) fn open_build_history_db_for_query(path: &AbsPathBuf) -> anyhow::Result<Connection> {
+    if !fs_util::try_exists(path)? {
+        return Err(anyhow::anyhow!(
+            "Build history database `{}` does not exist, run `buck2 log ingest` first",
+            path.display()
+        ));
+    }
+    let connection = Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .with_context(|| format!("Error opening build history database `{}`", path.display()))?;
+
+    let version: i64 = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version != SCHEMA_VERSION {
+        return Err(anyhow::anyhow!(
+            "Build history database `{}` has schema version {}, expected {}, run `buck2 log ingest` to recreate it",
+            path.display(),
+            version,
+            SCHEMA_VERSION
+        ));
+    }
+
+    Ok(connection)
+}
+
+pub(crate) enum IngestOutcome {
+    Ingested,
+    AlreadyIngested,
+}
+
+#[derive(Default)]
+struct CommandRow {
+    command: Option<String>,
+    start_time_ms: Option<i64>,
+    duration_ms: Option<i64>,
+    success: Option<bool>,
+}
+
+struct ActionRow {
+    owner: String,
+    category: String,
+    identifier: String,
+    kind: String,
+    execution_kind: String,
+    cache_hit: bool,
+    failed: bool,
+    start_time_ms: Option<i64>,
+    duration_ms: Option<i64>,
+}
+
+struct TestResultRow {
+    target: String,
+    name: String,
+    status: String,
+    duration_ms: Option<i64>,
+}
+
+struct CriticalPathRow {
+    kind: &'static str,
+    name: String,
+    category: String,
+    identifier: String,
+    total_duration_us: Option<i64>,
+    user_duration_us: Option<i64>,
+    potential_improvement_us: Option<i64>,
+}
+
+struct ErrorRow {
+    category: Option<String>,
+    typ: Option<String>,
+    message: String,
+}
+
+fn unix_millis(time: SystemTime) -> Option<i64> {
+    time.duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_millis() as i64)
+}
+
+fn event_time(event: &buck2_data::BuckEvent) -> Option<SystemTime> {
+    SystemTime::try_from(event.timestamp.clone()?).ok()
+}
+
+fn duration(d: &Option<prost_types::Duration>) -> Option<Duration> {
+    Duration::try_from(d.clone()?).ok()
+}
+
+fn millis(d: Option<Duration>) -> Option<i64> {
+    d.map(|d| d.as_millis() as i64)
+}
+
+fn micros(d: &Option<prost_types::Duration>) -> Option<i64> {
+    duration(d).map(|d| d.as_micros() as i64)
+}
+
+fn execution_kind_name(kind: buck2_data::ActionExecutionKind) -> String {
+    kind.as_str_name()
+        .trim_start_matches("ACTION_EXECUTION_KIND_")
+        .to_lowercase()
+}
+
+fn is_cache_hit(kind: buck2_data::ActionExecutionKind) -> bool {
+    match kind {
+        buck2_data::ActionExecutionKind::ActionCache
+        | buck2_data::ActionExecutionKind::RemoteDepFileCache
+        | buck2_data::ActionExecutionKind::LocalDepFile => true,
+        _ => false,
+    }
+}
+
+/// Reads an event log and stores it in the database. Logs are identified by their trace id, and a
+/// log that was ingested before is skipped unless `replace` is set.
+pub(crate) async fn ingest_event_log(
+    connection: &mut Connection,
+    log_path: &EventLogPathBuf,
+    replace: bool,
+) -> anyhow::Result<IngestOutcome> {
+    let (invocation, mut events) = log_path.unpack_stream().await?;
+    let trace_id = invocation.trace_id.to_string();
+
+    let exists: bool = connection.query_row(
+        "SELECT EXISTS(SELECT 1 FROM commands WHERE trace_id = ?1)",
+        [&trace_id],
+        |row| row.get(0),
+    )?;
+    if exists && !replace {
+        return Ok(IngestOutcome::AlreadyIngested);
+    }
+
+    let target_display_options = TargetDisplayOptions::for_log();
+
+    let mut command = CommandRow::default();
+    let mut actions = Vec::new();
+    let mut test_results = Vec::new();
+    let mut critical_path = Vec::new();
+    let mut errors = Vec::new();
+
+    while let Some(event) = events.try_next().await? {
+        let event = match event {
+            StreamValue::Event(event) => event,
+            StreamValue::Result(..) | StreamValue::PartialResult(..) => continue,
+        };
+        let time = event_time(&event);
+        match &event.data {
+            Some(buck2_data::buck_event::Data::SpanStart(start)) => match &start.data {
+                Some(buck2_data::span_start_event::Data::Command(start)) => {
+                    command.command = start.data.as_ref().map(|d| d.variant_name().to_owned());
+                    command.start_time_ms = time.and_then(unix_millis);
+                }
+                _ => {}
+            },
+            Some(buck2_data::buck_event::Data::SpanEnd(end)) => {
+                let span_duration = duration(&end.duration);
+                match &end.data {
+                    Some(buck2_data::span_end_event::Data::Command(end)) => {
+                        command.duration_ms = millis(span_duration);
+                        command.success = Some(end.is_success);
+                        for error in &end.errors {
+                            errors.push(ErrorRow {
+                                category: error
+                                    .category
+                                    .and_then(buck2_data::error::ErrorCategory::from_i32)
+                                    .map(|c| c.as_str_name().to_lowercase()),
+                                typ: error
+                                    .typ
+                                    .and_then(buck2_data::error::ErrorType::from_i32)
+                                    .map(|t| t.as_str_name().to_lowercase()),
+                                message: error.message.clone(),
+                            });
+                        }
+                    }
+                    Some(buck2_data::span_end_event::Data::ActionExecution(action)) => {
+                        let owner = match action.key.as_ref().and_then(|k| k.owner.as_ref()) {
+                            Some(owner) => {
+                                display::display_action_owner(owner, target_display_options)?
+                            }
+                            None => continue,
+                        };
+                        let execution_kind =
+                            buck2_data::ActionExecutionKind::from_i32(action.execution_kind)
+                                .unwrap_or(buck2_data::ActionExecutionKind::NotSet);
+                        let start_time =
+                            time.and_then(|t| t.checked_sub(span_duration.unwrap_or_default()));
+                        actions.push(ActionRow {
+                            owner,
+                            category: action
+                                .name
+                                .as_ref()
+                                .map_or_else(String::new, |n| n.category.clone()),
+                            identifier: action
+                                .name
+                                .as_ref()
+                                .map_or_else(String::new, |n| n.identifier.clone()),
+                            kind: buck2_data::ActionKind::from_i32(action.kind)
+                                .map_or_else(String::new, |k| k.as_str_name().to_lowercase()),
+                            execution_kind: execution_kind_name(execution_kind),
+                            cache_hit: is_cache_hit(execution_kind),
+                            failed: action.failed,
+                            start_time_ms: start_time.and_then(unix_millis),
+                            duration_ms: millis(span_duration),
+                        });
+                    }
+                    _ => {}
+                }
+            }
+            Some(buck2_data::buck_event::Data::Instant(instant)) => match &instant.data {
+                Some(buck2_data::instant_event::Data::TestResult(result)) => {
+                    test_results.push(TestResultRow {
+                        target: match &result.target_label {
+                            Some(label) => display::display_configured_target_label(
+                                label,
+                                target_display_options,
+                            )?,
+                            None => String::new(),
+                        },
+                        name: result.name.clone(),
+                        status: buck2_data::TestStatus::from_i32(result.status)
+                            .map_or_else(String::new, |s| s.as_str_name().to_lowercase()),
+                        duration_ms: millis(duration(&result.duration)),
+                    });
+                }
+                Some(buck2_data::instant_event::Data::BuildGraphInfo(info)) => {
+                    for entry in &info.critical_path2 {
+                        let CriticalPathEntryNames {
+                            kind,
+                            name,
+                            category,
+                            identifier,
+                        } = match critical_path_entry_names(entry, target_display_options)? {
+                            Some(names) => names,
+                            None => continue,
+                        };
+                        critical_path.push(CriticalPathRow {
+                            kind,
+                            name,
+                            category: category.to_owned(),
+                            identifier: identifier.to_owned(),
+                            total_duration_us: micros(&entry.total_duration),
+                            user_duration_us: micros(&entry.user_duration),
+                            potential_improvement_us: micros(&entry.potential_improvement_duration),
+                        });
+                    }
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    let tx = connection.transaction()?;
+    for table in TABLES {
+        tx.execute(
+            &format!("DELETE FROM {} WHERE trace_id = ?1", table),
+            [&trace_id],
+        )?;
+    }
+    tx.execute(
+        "INSERT INTO commands VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        rusqlite::params![
+            trace_id,
+            command.command,
+            invocation.display_command_line(),
+            invocation.working_dir,
+            command.start_time_ms,
+            command.duration_ms,
+            command.success,
+            log_path.path().to_string_lossy(),
+        ],
+    )?;
+    {
+        let mut stmt =
+            tx.prepare("INSERT INTO actions VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)")?;
+        for a in actions {
+            stmt.execute(rusqlite::params![
+                trace_id,
+                a.owner,
+                a.category,
+                a.identifier,
+                a.kind,
+                a.execution_kind,
+                a.cache_hit,
+                a.failed,
+                a.start_time_ms,
+                a.duration_ms,
+            ])?;
+        }
+        let mut stmt = tx.prepare("INSERT INTO test_results VALUES (?1, ?2, ?3, ?4, ?5)")?;
+        for t in test_results {
+            stmt.execute(rusqlite::params![
+                trace_id,
+                t.target,
+                t.name,
+                t.status,
+                t.duration_ms
+            ])?;
+        }
+        let mut stmt =
+            tx.prepare("INSERT INTO critical_path VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)")?;
+        for (position, c) in critical_path.into_iter().enumerate() {
+            stmt.execute(rusqlite::params![
+                trace_id,
+                position as i64,
+                c.kind,
+                c.name,
+                c.category,
+                c.identifier,
+                c.total_duration_us,
+                c.user_duration_us,
+                c.potential_improvement_us,
+            ])?;
+        }
+        let mut stmt = tx.prepare("INSERT INTO errors VALUES (?1, ?2, ?3, ?4)")?;
+        for e in errors {
+            stmt.execute(rusqlite::params![trace_id, e.category, e.typ, e.message])?;
+        }
+    }
+    tx.commit()?;
+
+    Ok(IngestOutcome::Ingested)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_execution_kind_name() {
+        assert_eq!(
+            "action_cache",
+            execution_kind_name(buck2_data::ActionExecutionKind::ActionCache)
+        );
+        assert!(is_cache_hit(
+            buck2_data::ActionExecutionKind::RemoteDepFileCache
+        ));
+        assert!(!is_cache_hit(buck2_data::ActionExecutionKind::Remote));
+    }
+
+    #[test]
+    fn test_schema_is_created_once() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = AbsPathBuf::try_from(dir.path().join("history.sqlite"))?;
+        let connection = open_build_history_db(&path)?;
+        connection.execute(
+            "INSERT INTO errors VALUES ('trace', NULL, NULL, 'message')",
+            [],
+        )?;
+        drop(connection);
+
+        // Reopening must not wipe existing data.
+        let connection = open_build_history_db(&path)?;
+        let count: i64 =
+            connection.query_row("SELECT COUNT(*) FROM errors", [], |row| row.get(0))?;
+        assert_eq!(1, count);
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_does_not_recreate_schema() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = AbsPathBuf::try_from(dir.path().join("history.sqlite"))?;
+        let connection = open_build_history_db(&path)?;
+        connection.execute(
+            "INSERT INTO errors VALUES ('trace', NULL, NULL, 'message')",
+            [],
+        )?;
+        connection.execute(&format!("PRAGMA user_version = {}", SCHEMA_VERSION - 1), [])?;
+        drop(connection);
+
+        let err = open_build_history_db_for_query(&path).unwrap_err();
+        assert!(
+            format!("{:#}", err).contains("run `buck2 log ingest`"),
+            "{:#}",
+            err
+        );
+
+        // The data of the older database is still there.
+        let connection = Connection::open(&path)?;
+        let count: i64 =
+            connection.query_row("SELECT COUNT(*) FROM errors", [], |row| row.get(0))?;
+        assert_eq!(1, count);
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_is_read_only() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = AbsPathBuf::try_from(dir.path().join("history.sqlite"))?;
+        drop(open_build_history_db(&path)?);
+
+        let connection = open_build_history_db_for_query(&path)?;
+        assert!(connection.execute("DELETE FROM errors", []).is_err());
+        Ok(())
+    }
+}
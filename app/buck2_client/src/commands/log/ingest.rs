@@ -0,0 +1,95 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use anyhow::Context;
+use buck2_client_ctx::client_ctx::ClientCommandContext;
+use buck2_client_ctx::exit_result::ExitResult;
+use buck2_event_log::file_names::retrieve_all_logs;
+
+use crate::commands::log::history::ingest_event_log;
+use crate::commands::log::history::open_build_history_db;
+use crate::commands::log::history::BuildHistoryDbOptions;
+use crate::commands::log::history::IngestOutcome;
+use crate::commands::log::options::EventLogOptions;
+
+/// Store the selected event logs in a local SQLite build history database.
+///
+/// The database has tables `commands`, `actions`, `test_results`, `critical_path` and `errors`,
+/// all keyed by `trace_id`. Query it with `buck2 log sql`.
+#[derive(Debug, clap::Parser)]
+pub struct IngestCommand {
+    #[clap(flatten)]
+    event_log: EventLogOptions,
+
+    /// Ingest all the logs in the log directory.
+    #[clap(long, group = "event_log")]
+    all: bool,
+
+    /// Re-ingest logs that are already in the database.
+    #[clap(long)]
+    replace: bool,
+
+    #[clap(flatten)]
+    db: BuildHistoryDbOptions,
+}
+
+impl IngestCommand {
+    pub fn exec(self, _matches: &clap::ArgMatches, ctx: ClientCommandContext<'_>) -> ExitResult {
+        let Self {
+            event_log,
+            all,
+            replace,
+            db,
+        } = self;
+
+        ctx.with_runtime(async move |ctx| {
+            let logs = if all {
+                retrieve_all_logs(ctx.paths().context("Error identifying log dir")?)?
+            } else {
+                vec![event_log.get(&ctx).await?]
+            };
+
+            let db_path = db.path(&ctx)?;
+            let mut connection = open_build_history_db(&db_path)?;
+
+            let mut ingested = 0;
+            let mut skipped = 0;
+            let mut failed = 0;
+            for log in logs {
+                let outcome = ingest_event_log(&mut connection, &log, replace)
+                    .await
+                    .with_context(|| format!("Error ingesting `{}`", log.path().display()));
+                match outcome {
+                    Ok(IngestOutcome::Ingested) => ingested += 1,
+                    Ok(IngestOutcome::AlreadyIngested) => skipped += 1,
+                    // With `--all`, one corrupt or truncated log shouldn't prevent
+                    // ingesting the others.
+                    Err(e) if all => {
+                        buck2_client_ctx::eprintln!("Warning: {:#}", e)?;
+                        failed += 1;
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+
+            buck2_client_ctx::eprintln!(
+                "Ingested {} log(s), skipped {} already ingested log(s) into `{}`",
+                ingested,
+                skipped,
+                db_path.display()
+            )?;
+            if failed > 0 {
+                buck2_client_ctx::eprintln!("Failed to ingest {} log(s)", failed)?;
+            }
+            anyhow::Ok(())
+        })?;
+
+        ExitResult::success()
+    }
+}
@@ -7,15 +7,20 @@
  * of this source tree.
  */
 
+mod action_inputs;
 mod critical_path;
 pub(crate) mod debug_replay;
 pub(crate) mod debug_what_ran;
+mod history;
+mod ingest;
 pub(crate) mod options;
 pub(crate) mod path_log;
 mod replay;
 mod show_log;
 mod show_user_log;
+mod sql;
 mod summary;
+mod what_changed;
 mod what_cmd;
 mod what_failed;
 mod what_materialized;
@@ -87,10 +92,15 @@ pub enum LogCommand {
     WhatUp(what_up::WhatUpCommand),
     WhatMaterialized(what_materialized::WhatMaterializedCommand),
     WhatUploaded(what_uploaded::WhatUploadedCommand),
+    #[clap(alias = "whatchanged")]
+    WhatChanged(what_changed::WhatChangedCommand),
+    ActionInputs(action_inputs::ActionInputsCommand),
     CriticalPath(critical_path::CriticalPathCommand),
     Replay(replay::ReplayCommand),
     ShowUser(show_user_log::ShowUserLogCommand),
     Summary(summary::SummaryCommand),
+    Ingest(ingest::IngestCommand),
+    Sql(sql::SqlCommand),
 }
 
 impl LogCommand {
@@ -104,10 +114,14 @@ impl LogCommand {
             Self::WhatUp(cmd) => cmd.exec(matches, ctx),
             Self::WhatMaterialized(cmd) => cmd.exec(matches, ctx),
             Self::WhatUploaded(cmd) => cmd.exec(matches, ctx),
+            Self::WhatChanged(cmd) => cmd.exec(matches, ctx),
+            Self::ActionInputs(cmd) => cmd.exec(matches, ctx),
             Self::CriticalPath(cmd) => cmd.exec(matches, ctx),
             Self::Replay(cmd) => cmd.exec(matches, ctx),
             Self::ShowUser(cmd) => cmd.exec(matches, ctx),
             Self::Summary(cmd) => cmd.exec(matches, ctx),
+            Self::Ingest(cmd) => cmd.exec(matches, ctx),
+            Self::Sql(cmd) => cmd.exec(matches, ctx),
         }
     }
 
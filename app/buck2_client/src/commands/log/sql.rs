@@ -0,0 +1,116 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::io::Write;
+
+use buck2_client_ctx::client_ctx::ClientCommandContext;
+use buck2_client_ctx::exit_result::ExitResult;
+use rusqlite::types::ValueRef;
+
+use crate::commands::log::history::open_build_history_db_for_query;
+use crate::commands::log::history::BuildHistoryDbOptions;
+use crate::commands::log::transform_format;
+use crate::commands::log::LogCommandOutputFormat;
+use crate::commands::log::LogCommandOutputFormatWithWriter;
+
+/// Run a SQL query against the build history database populated by `buck2 log ingest`.
+///
+/// For example, to find how often a target missed the cache:
+///
+/// buck2 log sql "SELECT owner, SUM(NOT cache_hit) FROM actions GROUP BY owner"
+#[derive(Debug, clap::Parser)]
+pub struct SqlCommand {
+    /// The SQL query to run.
+    #[clap(value_name = "QUERY")]
+    query: String,
+
+    #[clap(flatten)]
+    db: BuildHistoryDbOptions,
+
+    #[clap(
+        long = "format",
+        help = "Which output format to use for this command",
+        default_value = "tabulated",
+        ignore_case = true,
+        arg_enum
+    )]
+    output: LogCommandOutputFormat,
+}
+
+fn to_json(value: ValueRef<'_>) -> serde_json::Value {
+    match value {
+        ValueRef::Null => serde_json::Value::Null,
+        ValueRef::Integer(i) => i.into(),
+        ValueRef::Real(f) => f.into(),
+        ValueRef::Text(t) => String::from_utf8_lossy(t).into_owned().into(),
+        ValueRef::Blob(b) => format!("<{} bytes>", b.len()).into(),
+    }
+}
+
+fn to_text(value: ValueRef<'_>) -> String {
+    match value {
+        ValueRef::Null => String::new(),
+        ValueRef::Integer(i) => i.to_string(),
+        ValueRef::Real(f) => f.to_string(),
+        ValueRef::Text(t) => String::from_utf8_lossy(t).into_owned(),
+        ValueRef::Blob(b) => format!("<{} bytes>", b.len()),
+    }
+}
+
+impl SqlCommand {
+    pub fn exec(self, _matches: &clap::ArgMatches, ctx: ClientCommandContext<'_>) -> ExitResult {
+        let Self { query, db, output } = self;
+
+        let connection = open_build_history_db_for_query(&db.path(&ctx)?)?;
+        let mut stmt = connection.prepare(&query)?;
+        let columns: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+
+        buck2_client_ctx::stdio::print_with_writer::<anyhow::Error, _>(|w| {
+            let mut output = transform_format(output, w);
+            match &mut output {
+                LogCommandOutputFormatWithWriter::Tabulated(w) => {
+                    writeln!(w, "{}", columns.join("\t"))?;
+                }
+                LogCommandOutputFormatWithWriter::Csv(writer) => {
+                    writer.write_record(&columns)?;
+                }
+                LogCommandOutputFormatWithWriter::Json(_) => {}
+            }
+
+            let mut rows = stmt.query([])?;
+            while let Some(row) = rows.next()? {
+                match &mut output {
+                    LogCommandOutputFormatWithWriter::Tabulated(w) => {
+                        let values = (0..columns.len())
+                            .map(|i| Ok(to_text(row.get_ref(i)?)))
+                            .collect::<rusqlite::Result<Vec<_>>>()?;
+                        writeln!(w, "{}", values.join("\t"))?;
+                    }
+                    LogCommandOutputFormatWithWriter::Csv(writer) => {
+                        let values = (0..columns.len())
+                            .map(|i| Ok(to_text(row.get_ref(i)?)))
+                            .collect::<rusqlite::Result<Vec<_>>>()?;
+                        writer.write_record(&values)?;
+                    }
+                    LogCommandOutputFormatWithWriter::Json(w) => {
+                        let mut object = serde_json::Map::new();
+                        for (i, column) in columns.iter().enumerate() {
+                            object.insert(column.clone(), to_json(row.get_ref(i)?));
+                        }
+                        serde_json::to_writer(&mut *w, &object)?;
+                        writeln!(w)?;
+                    }
+                }
+            }
+            anyhow::Ok(())
+        })?;
+
+        ExitResult::success()
+    }
+}
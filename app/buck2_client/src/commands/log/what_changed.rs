@@ -0,0 +1,786 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::io::Write;
+
+use anyhow::Context;
+use buck2_client_ctx::client_ctx::ClientCommandContext;
+use buck2_client_ctx::exit_result::ExitResult;
+use buck2_client_ctx::path_arg::PathArg;
+use buck2_event_log::file_names::retrieve_nth_recent_log;
+use buck2_event_log::read::EventLogPathBuf;
+use buck2_event_observer::display;
+use buck2_event_observer::display::TargetDisplayOptions;
+use buck2_event_observer::what_ran::CommandReproducer;
+use buck2_event_observer::what_ran::WhatRanOptions;
+use buck2_event_observer::what_ran::WhatRanRelevantAction;
+use indexmap::IndexMap;
+
+use crate::commands::log::transform_format;
+use crate::commands::log::what_ran::WhatRanCommandState;
+use crate::commands::log::what_ran::WhatRanEntry;
+use crate::commands::log::what_ran::WhatRanEntryHandler;
+use crate::commands::log::LogCommandOutputFormat;
+use crate::commands::log::LogCommandOutputFormatWithWriter;
+
+/// Explain why actions ran in one invocation by comparing them to the same actions in another.
+///
+/// Actions are matched by their identity (owning target, category and identifier). For every
+/// action that was executed (rather than served from a cache) in the new invocation, this prints
+/// what differs from the old invocation, one line per difference: the first command line argument
+/// that changed, every environment variable that changed, and, when input manifests were
+/// recorded, every input path whose digest changed and every output path that was added or
+/// removed. If none of those are available or they are identical, the action digest is compared.
+///
+/// Command lines and environments are only recorded for local executions; for remote executions
+/// only the action digest is compared. If both invocations ran with
+/// `buck2.log_action_input_manifests = true`, commands, input files and output paths are compared
+/// for all actions.
+///
+/// By default, compares the most recent invocation to the one before it.
+#[derive(Debug, clap::Parser)]
+pub struct WhatChangedCommand {
+    /// The event log of the invocation to compare against. Defaults to the second most recent log.
+    #[clap(value_name = "OLD")]
+    old: Option<PathArg>,
+
+    /// The event log of the invocation to explain. Defaults to the most recent log.
+    #[clap(value_name = "NEW", requires = "old")]
+    new: Option<PathArg>,
+
+    /// Only show actions whose identity contains this string.
+    #[clap(long, value_name = "STRING")]
+    filter: Option<String>,
+
+    /// Also show actions that were served from a cache in the new invocation.
+    #[clap(long)]
+    include_cached: bool,
+
+    #[clap(
+        long = "format",
+        help = "Which output format to use for this command",
+        default_value = "tabulated",
+        ignore_case = true,
+        arg_enum
+    )]
+    output: LogCommandOutputFormat,
+}
+
+/// What we know about one execution of an action.
+#[derive(Default, Debug, Clone, PartialEq)]
+struct ActionInputs {
+    action_digest: Option<String>,
+    argv: Option<Vec<String>>,
+    env: Option<BTreeMap<String, String>>,
+    /// Input path to digest (or symlink target), from the action input manifest.
+    inputs: Option<BTreeMap<String, String>>,
+    /// Paths of the declared outputs, from the action input manifest.
+    output_paths: Option<BTreeSet<String>>,
+    /// Digests of the outputs produced.
+    outputs: Vec<String>,
+    cached: bool,
+}
+
+impl ActionInputs {
+    fn set_local_command(&mut self, argv: &[String], env: &[buck2_data::EnvironmentEntry]) {
+        self.argv = Some(argv.to_vec());
+        self.env = Some(
+            env.iter()
+                .map(|e| (e.key.clone(), e.value.clone()))
+                .collect(),
+        );
+    }
+
+    fn set_manifest(&mut self, manifest: &buck2_data::ActionInputManifest) {
+        use buck2_data::action_input_manifest_entry::Entry;
+
+        self.action_digest = Some(manifest.action_digest.clone());
+        self.set_local_command(&manifest.argv, &manifest.env);
+        self.inputs = Some(
+            manifest
+                .inputs
+                .iter()
+                .map(|input| {
+                    let value = match &input.entry {
+                        Some(Entry::FileDigest(digest)) => digest.clone(),
+                        Some(Entry::SymlinkTarget(target)) => format!("-> {}", target),
+                        None => String::new(),
+                    };
+                    (input.path.clone(), value)
+                })
+                .collect(),
+        );
+        self.output_paths = Some(manifest.outputs.iter().cloned().collect());
+    }
+
+    fn set_reproducer(&mut self, repro: CommandReproducer<'_>) {
+        match repro {
+            CommandReproducer::CacheQuery(query) => {
+                self.action_digest = Some(query.action_digest.clone());
+            }
+            CommandReproducer::CacheHit(hit) => {
+                self.action_digest = Some(hit.action_digest.clone());
+            }
+            CommandReproducer::ReExecute(execute) => {
+                self.action_digest = Some(execute.action_digest.clone());
+            }
+            CommandReproducer::LocalExecute(buck2_data::LocalExecute {
+                command: Some(command),
+            }) => {
+                self.action_digest = Some(command.action_digest.clone());
+                self.set_local_command(&command.argv, &command.env);
+            }
+            CommandReproducer::WorkerExecute(buck2_data::WorkerExecute {
+                command: Some(command),
+            }) => {
+                self.action_digest = Some(command.action_digest.clone());
+                self.set_local_command(&command.argv, &command.env);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The first difference found between two executions of an action.
+#[derive(Debug, PartialEq, serde::Serialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+enum ActionChange {
+    /// The action did not exist in the old invocation.
+    New,
+    /// A command line argument changed.
+    Argument {
+        index: usize,
+        old: Option<String>,
+        new: Option<String>,
+    },
+    /// An environment variable changed.
+    Env {
+        key: String,
+        old: Option<String>,
+        new: Option<String>,
+    },
+    /// An input file changed.
+    Input {
+        path: String,
+        old: Option<String>,
+        new: Option<String>,
+    },
+    /// An output path was added or removed.
+    OutputPath { path: String, added: bool },
+    /// The command line, environment, inputs and outputs are identical (or unknown), but the
+    /// action digest changed.
+    Digest { old: String, new: String },
+    /// The action digest is identical but the action produced different outputs, so the action
+    /// is not deterministic.
+    Outputs,
+    /// Nothing we can observe changed, yet the action was executed again.
+    None,
+}
+
+impl fmt::Display for ActionChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn opt(s: &Option<String>) -> &str {
+            s.as_deref().unwrap_or("<missing>")
+        }
+
+        match self {
+            ActionChange::New => write!(f, "new\taction did not run in the old invocation"),
+            ActionChange::Argument { index, old, new } => write!(
+                f,
+                "argument\targv[{}] changed from `{}` to `{}`",
+                index,
+                opt(old),
+                opt(new)
+            ),
+            ActionChange::Env { key, old, new } => write!(
+                f,
+                "env\t`{}` changed from `{}` to `{}`",
+                key,
+                opt(old),
+                opt(new)
+            ),
+            ActionChange::Input { path, old, new } => write!(
+                f,
+                "input\t`{}` changed from `{}` to `{}`",
+                path,
+                opt(old),
+                opt(new)
+            ),
+            ActionChange::OutputPath { path, added } => write!(
+                f,
+                "output_path\t`{}` {}",
+                path,
+                if *added { "added" } else { "removed" }
+            ),
+            ActionChange::Digest { old, new } => write!(
+                f,
+                "digest\tcommand is identical but action digest changed from `{}` to `{}` (inputs or outputs changed)",
+                old, new
+            ),
+            ActionChange::Outputs => write!(
+                f,
+                "outputs\taction digest is identical but outputs differ (the action is not deterministic)"
+            ),
+            ActionChange::None => write!(
+                f,
+                "none\tno observable change, the previous result was not cached"
+            ),
+        }
+    }
+}
+
+/// The keys whose values differ between two maps, in order.
+fn changed_keys<'a>(
+    old: &'a BTreeMap<String, String>,
+    new: &'a BTreeMap<String, String>,
+) -> impl Iterator<Item = (&'a String, Option<&'a String>, Option<&'a String>)> + 'a {
+    old.keys()
+        .chain(new.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter_map(|key| {
+            let (o, n) = (old.get(key), new.get(key));
+            (o != n).then_some((key, o, n))
+        })
+}
+
+/// Everything that differs between two executions of an action: the first command line argument,
+/// then every environment variable, input path and output path. If none of those differ (or are
+/// unknown), a single change explaining why the action ran anyway.
+fn differences(old: &ActionInputs, new: &ActionInputs) -> Vec<ActionChange> {
+    let mut changes = Vec::new();
+
+    if let (Some(old_argv), Some(new_argv)) = (&old.argv, &new.argv) {
+        if let Some(index) =
+            (0..old_argv.len().max(new_argv.len())).find(|i| old_argv.get(*i) != new_argv.get(*i))
+        {
+            changes.push(ActionChange::Argument {
+                index,
+                old: old_argv.get(index).cloned(),
+                new: new_argv.get(index).cloned(),
+            });
+        }
+    }
+
+    if let (Some(old_env), Some(new_env)) = (&old.env, &new.env) {
+        changes.extend(
+            changed_keys(old_env, new_env).map(|(key, o, n)| ActionChange::Env {
+                key: key.clone(),
+                old: o.cloned(),
+                new: n.cloned(),
+            }),
+        );
+    }
+
+    if let (Some(old_inputs), Some(new_inputs)) = (&old.inputs, &new.inputs) {
+        changes.extend(changed_keys(old_inputs, new_inputs).map(|(path, o, n)| {
+            ActionChange::Input {
+                path: path.clone(),
+                old: o.cloned(),
+                new: n.cloned(),
+            }
+        }));
+    }
+
+    if let (Some(old_paths), Some(new_paths)) = (&old.output_paths, &new.output_paths) {
+        changes.extend(old_paths.symmetric_difference(new_paths).map(|path| {
+            ActionChange::OutputPath {
+                path: path.clone(),
+                added: new_paths.contains(path),
+            }
+        }));
+    }
+
+    if changes.is_empty() {
+        changes.push(match (&old.action_digest, &new.action_digest) {
+            (Some(o), Some(n)) if o != n => ActionChange::Digest {
+                old: o.clone(),
+                new: n.clone(),
+            },
+            _ if !old.outputs.is_empty()
+                && !new.outputs.is_empty()
+                && old.outputs != new.outputs =>
+            {
+                ActionChange::Outputs
+            }
+            _ => ActionChange::None,
+        });
+    }
+
+    changes
+}
+
+/// Collects the inputs of every finished action in an event log, keyed by action identity.
+#[derive(Default)]
+struct ActionCollector {
+    actions: IndexMap<String, ActionInputs>,
+}
+
+impl ActionCollector {
+    /// Cache queries are where remote executions record their action digest.
+    fn reproducer_options() -> WhatRanOptions {
+        WhatRanOptions {
+            emit_cache_queries: true,
+            ..Default::default()
+        }
+    }
+
+    async fn collect(log: &EventLogPathBuf) -> anyhow::Result<IndexMap<String, ActionInputs>> {
+        let (_invocation, events) = log.unpack_stream().await?;
+        let mut collector = ActionCollector::default();
+        WhatRanCommandState::execute(events, &Self::reproducer_options(), &mut collector).await?;
+        Ok(collector.actions)
+    }
+}
+
+impl WhatRanEntryHandler for ActionCollector {
+    fn finished(
+        &mut self,
+        entry: WhatRanEntry,
+        data: &Option<buck2_data::span_end_event::Data>,
+    ) -> anyhow::Result<()> {
+        let (start, end) = match (
+            entry
+                .event
+                .data
+                .as_ref()
+                .and_then(WhatRanRelevantAction::from_buck_data),
+            data,
+        ) {
+            (
+                Some(WhatRanRelevantAction::ActionExecution(start)),
+                Some(buck2_data::span_end_event::Data::ActionExecution(end)),
+            ) => (start, end),
+            _ => return Ok(()),
+        };
+        let identity = display::display_action_identity(
+            start.key.as_ref(),
+            start.name.as_ref(),
+            TargetDisplayOptions::for_log(),
+        )?;
+
+        let options = Self::reproducer_options();
+        let mut inputs = ActionInputs::default();
+        for repro in &entry.reproducers {
+            if let Some(repro) = repro
+                .data
+                .as_ref()
+                .and_then(|data| CommandReproducer::from_buck_data(data, &options))
+            {
+                inputs.set_reproducer(repro);
+            }
+        }
+        for instant in &entry.instants {
+            if let Some(buck2_data::buck_event::Data::Instant(buck2_data::InstantEvent {
+                data: Some(buck2_data::instant_event::Data::ActionInputManifest(manifest)),
+            })) = &instant.data
+            {
+                inputs.set_manifest(manifest);
+            }
+        }
+        inputs.outputs = end.outputs.iter().map(|o| o.tiny_digest.clone()).collect();
+        inputs.cached = match buck2_data::ActionExecutionKind::from_i32(end.execution_kind) {
+            Some(
+                buck2_data::ActionExecutionKind::Local
+                | buck2_data::ActionExecutionKind::Remote
+                | buck2_data::ActionExecutionKind::LocalWorker,
+            ) => false,
+            _ => true,
+        };
+        self.actions.insert(identity, inputs);
+        Ok(())
+    }
+
+    fn unfinished(&mut self, _entry: &WhatRanEntry) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(serde::Serialize)]
+struct JsonRecord<'a> {
+    action: &'a str,
+    cached: bool,
+    #[serde(flatten)]
+    change: &'a ActionChange,
+}
+
+impl WhatChangedCommand {
+    pub fn exec(self, _matches: &clap::ArgMatches, ctx: ClientCommandContext<'_>) -> ExitResult {
+        let Self {
+            old,
+            new,
+            filter,
+            include_cached,
+            output,
+        } = self;
+
+        buck2_client_ctx::stdio::print_with_writer::<anyhow::Error, _>(|w| {
+            let mut output = transform_format(output, w);
+            ctx.with_runtime(async move |ctx| {
+                let resolve = |path: Option<PathArg>, recent: usize| match path {
+                    Some(path) => EventLogPathBuf::infer(path.resolve(&ctx.working_dir)),
+                    None => retrieve_nth_recent_log(
+                        ctx.paths().context("Error identifying log dir")?,
+                        recent,
+                    ),
+                };
+                // With a single path, compare it against the most recent log.
+                let (old_log, new_log) = match (old, new) {
+                    (Some(old), None) => (resolve(Some(old), 0)?, resolve(None, 0)?),
+                    (old, new) => (resolve(old, 1)?, resolve(new, 0)?),
+                };
+
+                buck2_client_ctx::eprintln!(
+                    "Comparing `{}` against `{}`",
+                    new_log.path().display(),
+                    old_log.path().display()
+                )?;
+
+                let old_actions = ActionCollector::collect(&old_log).await?;
+                let new_actions = ActionCollector::collect(&new_log).await?;
+
+                for (identity, inputs) in &new_actions {
+                    if inputs.cached && !include_cached {
+                        continue;
+                    }
+                    if let Some(filter) = &filter {
+                        if !identity.contains(filter.as_str()) {
+                            continue;
+                        }
+                    }
+                    let changes = match old_actions.get(identity) {
+                        Some(old_inputs) => differences(old_inputs, inputs),
+                        None => vec![ActionChange::New],
+                    };
+                    for change in &changes {
+                        match &mut output {
+                            LogCommandOutputFormatWithWriter::Tabulated(w) => {
+                                writeln!(w, "{}\t{}", identity, change)?;
+                            }
+                            LogCommandOutputFormatWithWriter::Json(w) => {
+                                serde_json::to_writer(
+                                    &mut *w,
+                                    &JsonRecord {
+                                        action: identity,
+                                        cached: inputs.cached,
+                                        change,
+                                    },
+                                )?;
+                                writeln!(w)?;
+                            }
+                            LogCommandOutputFormatWithWriter::Csv(writer) => {
+                                let change = change.to_string();
+                                let (kind, details) = change.split_once('\t').unwrap_or(("", ""));
+                                writer.write_record([identity.as_str(), kind, details])?;
+                            }
+                        }
+                    }
+                }
+
+                anyhow::Ok(())
+            })?;
+            anyhow::Ok(())
+        })?;
+
+        ExitResult::success()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use buck2_event_log::stream_value::StreamValue;
+
+    use super::*;
+
+    fn local(argv: &[&str], env: &[(&str, &str)], digest: &str) -> ActionInputs {
+        ActionInputs {
+            action_digest: Some(digest.to_owned()),
+            argv: Some(argv.iter().map(|s| (*s).to_owned()).collect()),
+            env: Some(
+                env.iter()
+                    .map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
+                    .collect(),
+            ),
+            inputs: None,
+            output_paths: None,
+            outputs: Vec::new(),
+            cached: false,
+        }
+    }
+
+    #[test]
+    fn test_argument_changed() {
+        assert_eq!(
+            vec![ActionChange::Argument {
+                index: 1,
+                old: Some("-O1".to_owned()),
+                new: Some("-O2".to_owned())
+            }],
+            differences(
+                &local(&["cc", "-O1"], &[], "a:1"),
+                &local(&["cc", "-O2"], &[], "b:1")
+            )
+        );
+        assert_eq!(
+            vec![ActionChange::Argument {
+                index: 2,
+                old: None,
+                new: Some("-g".to_owned())
+            }],
+            differences(
+                &local(&["cc", "-O1"], &[], "a:1"),
+                &local(&["cc", "-O1", "-g"], &[], "b:1")
+            )
+        );
+    }
+
+    #[test]
+    fn test_env_changed() {
+        assert_eq!(
+            vec![ActionChange::Env {
+                key: "LANG".to_owned(),
+                old: None,
+                new: Some("C".to_owned())
+            }],
+            differences(
+                &local(&["cc"], &[], "a:1"),
+                &local(&["cc"], &[("LANG", "C")], "b:1")
+            )
+        );
+    }
+
+    #[test]
+    fn test_input_changed() {
+        let with_inputs = |inputs: &[(&str, &str)], digest: &str| ActionInputs {
+            inputs: Some(
+                inputs
+                    .iter()
+                    .map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
+                    .collect(),
+            ),
+            ..local(&["cc"], &[], digest)
+        };
+        assert_eq!(
+            vec![ActionChange::Input {
+                path: "foo.c".to_owned(),
+                old: Some("x:1".to_owned()),
+                new: Some("y:1".to_owned())
+            }],
+            differences(
+                &with_inputs(&[("foo.c", "x:1"), ("foo.h", "z:1")], "a:1"),
+                &with_inputs(&[("foo.c", "y:1"), ("foo.h", "z:1")], "b:1")
+            )
+        );
+    }
+
+    #[test]
+    fn test_digest_changed() {
+        let remote = |digest: &str| ActionInputs {
+            action_digest: Some(digest.to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(
+            vec![ActionChange::Digest {
+                old: "a:1".to_owned(),
+                new: "b:1".to_owned()
+            }],
+            differences(&remote("a:1"), &remote("b:1"))
+        );
+        assert_eq!(
+            vec![ActionChange::None],
+            differences(&remote("a:1"), &remote("a:1"))
+        );
+        assert_eq!(
+            vec![ActionChange::Outputs],
+            differences(
+                &ActionInputs {
+                    outputs: vec!["x".to_owned()],
+                    ..remote("a:1")
+                },
+                &ActionInputs {
+                    outputs: vec!["y".to_owned()],
+                    ..remote("a:1")
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn test_every_path_changed() {
+        let with_paths = |inputs: &[(&str, &str)], outputs: &[&str]| ActionInputs {
+            inputs: Some(
+                inputs
+                    .iter()
+                    .map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
+                    .collect(),
+            ),
+            output_paths: Some(outputs.iter().map(|o| (*o).to_owned()).collect()),
+            ..local(&["cc"], &[], "a:1")
+        };
+        assert_eq!(
+            vec![
+                ActionChange::Input {
+                    path: "bar.c".to_owned(),
+                    old: Some("x:1".to_owned()),
+                    new: None
+                },
+                ActionChange::Input {
+                    path: "foo.c".to_owned(),
+                    old: Some("x:1".to_owned()),
+                    new: Some("y:1".to_owned())
+                },
+                ActionChange::Input {
+                    path: "foo.h".to_owned(),
+                    old: None,
+                    new: Some("z:1".to_owned())
+                },
+                ActionChange::OutputPath {
+                    path: "out/bar.o".to_owned(),
+                    added: false
+                },
+                ActionChange::OutputPath {
+                    path: "out/foo.d".to_owned(),
+                    added: true
+                },
+            ],
+            differences(
+                &with_paths(
+                    &[("bar.c", "x:1"), ("foo.c", "x:1")],
+                    &["out/bar.o", "out/foo.o"]
+                ),
+                &with_paths(
+                    &[("foo.c", "y:1"), ("foo.h", "z:1")],
+                    &["out/foo.d", "out/foo.o"]
+                ),
+            )
+        );
+    }
+
+    fn event(
+        span_id: u64,
+        parent_id: u64,
+        data: buck2_data::buck_event::Data,
+    ) -> anyhow::Result<StreamValue> {
+        Ok(StreamValue::Event(Box::new(buck2_data::BuckEvent {
+            span_id,
+            parent_id,
+            data: Some(data),
+            ..Default::default()
+        })))
+    }
+
+    #[tokio::test]
+    async fn test_collect_manifest() -> anyhow::Result<()> {
+        let key = buck2_data::ActionKey {
+            id: Default::default(),
+            owner: Some(buck2_data::action_key::Owner::TargetLabel(
+                buck2_data::ConfiguredTargetLabel {
+                    label: Some(buck2_data::TargetLabel {
+                        package: "root//pkg".to_owned(),
+                        name: "target".to_owned(),
+                    }),
+                    configuration: Some(buck2_data::Configuration {
+                        full_name: "conf".to_owned(),
+                    }),
+                    execution_configuration: None,
+                },
+            )),
+            key: String::new(),
+        };
+        let name = buck2_data::ActionName {
+            category: "cxx_compile".to_owned(),
+            identifier: "foo.c".to_owned(),
+        };
+        let events = vec![
+            event(
+                1,
+                0,
+                buck2_data::buck_event::Data::SpanStart(buck2_data::SpanStartEvent {
+                    data: Some(buck2_data::span_start_event::Data::ActionExecution(
+                        buck2_data::ActionExecutionStart {
+                            key: Some(key.clone()),
+                            name: Some(name.clone()),
+                            ..Default::default()
+                        },
+                    )),
+                }),
+            ),
+            event(
+                0,
+                1,
+                buck2_data::buck_event::Data::Instant(buck2_data::InstantEvent {
+                    data: Some(buck2_data::instant_event::Data::ActionInputManifest(
+                        buck2_data::ActionInputManifest {
+                            key: Some(key),
+                            name: Some(name),
+                            action_digest: "a:1".to_owned(),
+                            argv: vec!["cc".to_owned(), "foo.c".to_owned()],
+                            env: Vec::new(),
+                            inputs: vec![buck2_data::ActionInputManifestEntry {
+                                path: "pkg/foo.c".to_owned(),
+                                entry: Some(
+                                    buck2_data::action_input_manifest_entry::Entry::FileDigest(
+                                        "x:1".to_owned(),
+                                    ),
+                                ),
+                                is_executable: false,
+                            }],
+                            outputs: vec!["buck-out/v2/gen/foo.o".to_owned()],
+                        },
+                    )),
+                }),
+            ),
+            event(
+                1,
+                0,
+                buck2_data::buck_event::Data::SpanEnd(buck2_data::SpanEndEvent {
+                    data: Some(buck2_data::span_end_event::Data::ActionExecution(Box::new(
+                        buck2_data::ActionExecutionEnd {
+                            execution_kind: buck2_data::ActionExecutionKind::Local as i32,
+                            ..Default::default()
+                        },
+                    ))),
+                    ..Default::default()
+                }),
+            ),
+        ];
+
+        let mut collector = ActionCollector::default();
+        WhatRanCommandState::execute(
+            futures::stream::iter(events),
+            &ActionCollector::reproducer_options(),
+            &mut collector,
+        )
+        .await?;
+
+        let (identity, inputs) = collector.actions.first().context("No action collected")?;
+        assert!(identity.contains("root//pkg:target"), "{}", identity);
+        assert!(!inputs.cached);
+        assert_eq!(Some("a:1"), inputs.action_digest.as_deref());
+        assert_eq!(
+            Some(&vec!["cc".to_owned(), "foo.c".to_owned()]),
+            inputs.argv.as_ref()
+        );
+        assert_eq!(
+            Some(&BTreeMap::from([(
+                "pkg/foo.c".to_owned(),
+                "x:1".to_owned()
+            )])),
+            inputs.inputs.as_ref()
+        );
+        assert_eq!(
+            Some(&BTreeSet::from(["buck-out/v2/gen/foo.o".to_owned()])),
+            inputs.output_paths.as_ref()
+        );
+        Ok(())
+    }
+}
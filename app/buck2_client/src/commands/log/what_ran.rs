@@ -150,7 +150,15 @@ impl WhatRanCommand {
                     failed,
                     incomplete,
                 };
-                WhatRanCommandState::execute(events, &mut output, &options).await?;
+                WhatRanCommandState::execute(
+                    events,
+                    &options.options,
+                    &mut WhatRanEmitter {
+                        output: &mut output,
+                        options: &options,
+                    },
+                )
+                .await?;
 
                 anyhow::Ok(())
             })?;
@@ -161,12 +169,15 @@ impl WhatRanCommand {
 }
 
 #[allow(clippy::vec_box)]
-struct WhatRanEntry {
+pub(crate) struct WhatRanEntry {
     /// Known to be a WhatRanRelevantAction.
-    event: Box<buck2_data::BuckEvent>,
+    pub(crate) event: Box<buck2_data::BuckEvent>,
 
     /// Known to be a CommandReproducer.
-    reproducers: Vec<Box<buck2_data::BuckEvent>>,
+    pub(crate) reproducers: Vec<Box<buck2_data::BuckEvent>>,
+
+    /// Instant events emitted directly within the span, e.g. action input manifests.
+    pub(crate) instants: Vec<Box<buck2_data::BuckEvent>>,
 }
 
 impl WhatRanEntry {
@@ -197,6 +208,45 @@ impl WhatRanEntry {
     }
 }
 
+/// What to do with the entries found by `WhatRanCommandState`.
+pub(crate) trait WhatRanEntryHandler {
+    /// Called when the span of an entry ends.
+    fn finished(
+        &mut self,
+        entry: WhatRanEntry,
+        data: &Option<buck2_data::span_end_event::Data>,
+    ) -> anyhow::Result<()>;
+
+    /// Called at the end of the log for the entries whose span did not end.
+    fn unfinished(&mut self, entry: &WhatRanEntry) -> anyhow::Result<()>;
+}
+
+/// Emits the entries as `buck2 log what-ran` does.
+struct WhatRanEmitter<'a, W> {
+    output: &'a mut W,
+    options: &'a WhatRanCommandOptions,
+}
+
+impl<W: WhatRanOutputWriter> WhatRanEntryHandler for WhatRanEmitter<'_, W> {
+    fn finished(
+        &mut self,
+        entry: WhatRanEntry,
+        data: &Option<buck2_data::span_end_event::Data>,
+    ) -> anyhow::Result<()> {
+        if should_emit_finished_action(data, self.options) {
+            entry.emit_what_ran_entry(self.output, data, self.options)?;
+        }
+        Ok(())
+    }
+
+    fn unfinished(&mut self, entry: &WhatRanEntry) -> anyhow::Result<()> {
+        if should_emit_unfinished_action(self.options) {
+            entry.emit_what_ran_entry(self.output, &None, self.options)?;
+        }
+        Ok(())
+    }
+}
+
 /// The state for a WhatRan command. This is all the events we have seen that are
 /// we have seen that are WhatRanRelevantActions, and the CommandReproducer associated with them.
 #[derive(Default)]
@@ -215,20 +265,22 @@ impl WhatRanState for WhatRanCommandState {
 }
 
 impl WhatRanCommandState {
-    async fn execute(
+    /// Pass every relevant action in the events to `handler`, with the command reproducers
+    /// selected by `options`.
+    pub(crate) async fn execute(
         mut events: impl Stream<Item = anyhow::Result<StreamValue>> + Unpin + Send,
-        output: &mut impl WhatRanOutputWriter,
-        options: &WhatRanCommandOptions,
+        options: &WhatRanOptions,
+        handler: &mut impl WhatRanEntryHandler,
     ) -> anyhow::Result<()> {
         let mut cmd = Self::default();
 
         while let Some(event) = events.try_next().await? {
             match event {
-                StreamValue::Event(event) => cmd.event(event, output, options)?,
+                StreamValue::Event(event) => cmd.event(event, options, handler)?,
                 _ => {}
             }
         }
-        cmd.emit_remaining(output, options)
+        cmd.emit_remaining(handler)
     }
 
     /// Receive a new event. We store it if it's relevant and emmit them latter.
@@ -237,8 +289,8 @@ impl WhatRanCommandState {
     fn event(
         &mut self,
         event: Box<buck2_data::BuckEvent>,
-        output: &mut impl WhatRanOutputWriter,
-        options: &WhatRanCommandOptions,
+        options: &WhatRanOptions,
+        handler: &mut impl WhatRanEntryHandler,
     ) -> anyhow::Result<()> {
         if let Some(data) = &event.data {
             if WhatRanRelevantAction::from_buck_data(data).is_some() {
@@ -247,12 +299,13 @@ impl WhatRanCommandState {
                     WhatRanEntry {
                         event,
                         reproducers: Default::default(),
+                        instants: Default::default(),
                     },
                 );
                 return Ok(());
             }
 
-            if CommandReproducer::from_buck_data(data, &options.options).is_some() {
+            if CommandReproducer::from_buck_data(data, options).is_some() {
                 if let Some(parent_id) = SpanId::from_u64_opt(event.parent_id) {
                     if let Some(entry) = self.known_actions.get_mut(&parent_id) {
                         entry.reproducers.push(event);
@@ -266,8 +319,13 @@ impl WhatRanCommandState {
                     if let Some(entry) =
                         self.known_actions.remove(&SpanId::from_u64(event.span_id)?)
                     {
-                        if should_emit_finished_action(&span.data, options) {
-                            entry.emit_what_ran_entry(output, &span.data, options)?;
+                        handler.finished(entry, &span.data)?;
+                    }
+                }
+                buck2_data::buck_event::Data::Instant(_) => {
+                    if let Some(parent_id) = SpanId::from_u64_opt(event.parent_id) {
+                        if let Some(entry) = self.known_actions.get_mut(&parent_id) {
+                            entry.instants.push(event);
                         }
                     }
                 }
@@ -278,15 +336,9 @@ impl WhatRanCommandState {
         Ok(())
     }
 
-    fn emit_remaining(
-        &self,
-        output: &mut impl WhatRanOutputWriter,
-        options: &WhatRanCommandOptions,
-    ) -> anyhow::Result<()> {
+    fn emit_remaining(&self, handler: &mut impl WhatRanEntryHandler) -> anyhow::Result<()> {
         for (_, entry) in self.known_actions.iter() {
-            if should_emit_unfinished_action(options) {
-                entry.emit_what_ran_entry(output, &None, options)?;
-            }
+            handler.unfinished(entry)?;
         }
         Ok(())
     }
@@ -103,6 +103,7 @@ enum BuckProfileMode {
     Bytecode,
     BytecodePairs,
     Typecheck,
+    Coverage,
 }
 
 #[derive(Debug, clap::Parser)]
@@ -155,6 +156,9 @@ pub struct ProfileCommonOptions {
     /// This is probably what you want when profiling analysis.
     ///
     /// `-allocated` means allocated memory, including memory which is later garbage collected.
+    ///
+    /// `coverage` writes statement and branch coverage of the executed Starlark files
+    /// in LCOV format.
     #[clap(long, value_enum)]
     mode: BuckProfileMode,
 }
@@ -175,6 +179,7 @@ fn profile_mode_to_profile(mode: &BuckProfileMode) -> Profiler {
         BuckProfileMode::Bytecode => Profiler::Bytecode,
         BuckProfileMode::BytecodePairs => Profiler::BytecodePairs,
         BuckProfileMode::Typecheck => Profiler::Typecheck,
+        BuckProfileMode::Coverage => Profiler::Coverage,
     }
 }
 
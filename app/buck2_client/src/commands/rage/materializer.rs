@@ -14,8 +14,7 @@ use buck2_audit::AuditCommand;
 use buck2_cli_proto::ClientContext;
 use buck2_client_ctx::command_outcome::CommandOutcome;
 use buck2_client_ctx::daemon::client::connect::BootstrapBuckdClient;
-use buck2_client_ctx::events_ctx::PartialResultCtx;
-use buck2_client_ctx::events_ctx::PartialResultHandler;
+use buck2_client_ctx::daemon::client::CaptureStdout;
 use buck2_client_ctx::manifold::ManifoldClient;
 use buck2_client_ctx::subscribers::subscriber::EventSubscriber;
 use buck2_client_ctx::subscribers::subscribers::EventSubscribers;
@@ -72,31 +71,6 @@ pub async fn upload_materializer_data(
     buf_to_manifold(manifold, &capture.buf, manifold_filename).await
 }
 
-/// Receive StdoutBytes, just capture them.
-struct CaptureStdout {
-    buf: Vec<u8>,
-}
-
-impl CaptureStdout {
-    fn new() -> Self {
-        Self { buf: Vec::new() }
-    }
-}
-
-#[async_trait]
-impl PartialResultHandler for CaptureStdout {
-    type PartialResult = buck2_cli_proto::StdoutBytes;
-
-    async fn handle_partial_result(
-        &mut self,
-        _ctx: PartialResultCtx<'_, '_>,
-        partial_res: Self::PartialResult,
-    ) -> anyhow::Result<()> {
-        self.buf.extend(partial_res.data);
-        Ok(())
-    }
-}
-
 struct TracingSubscriber;
 
 #[async_trait]
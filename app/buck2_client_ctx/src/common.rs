@@ -503,6 +503,17 @@ pub struct CommonBuildOptions {
     /// Materializes inputs for failed actions which ran on RE
     #[clap(long)]
     materialize_failed_inputs: bool,
+
+    /// Check that actions are deterministic: every command that needs to run is executed twice
+    /// locally, skipping the action cache, and commands whose outputs differ are reported as a
+    /// warning, in the event log and in the build report.
+    #[clap(long)]
+    check_determinism: bool,
+
+    /// Only check the determinism of actions of this category, e.g. `cxx_compile`. Can be passed
+    /// multiple times. The other actions use the action cache and run once as usual.
+    #[clap(long, value_name = "CATEGORY", requires = "check_determinism")]
+    check_determinism_category: Vec<String>,
 }
 
 impl CommonBuildOptions {
@@ -554,6 +565,8 @@ impl CommonBuildOptions {
             skip_missing_targets: self.skip_missing_targets,
             skip_incompatible_targets: self.skip_incompatible_targets,
             materialize_failed_inputs: self.materialize_failed_inputs,
+            check_determinism: self.check_determinism,
+            check_determinism_categories: self.check_determinism_category.clone(),
             unstable_include_failures_build_report,
             unstable_include_package_project_relative_paths,
         }
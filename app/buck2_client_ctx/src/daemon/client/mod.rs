@@ -349,6 +349,32 @@ impl PartialResultHandler for StdoutPartialResultHandler {
     }
 }
 
+/// Receive StdoutBytes, just capture them.
+#[derive(Default)]
+pub struct CaptureStdout {
+    pub buf: Vec<u8>,
+}
+
+impl CaptureStdout {
+    pub fn new() -> Self {
+        Self { buf: Vec::new() }
+    }
+}
+
+#[async_trait]
+impl PartialResultHandler for CaptureStdout {
+    type PartialResult = buck2_cli_proto::StdoutBytes;
+
+    async fn handle_partial_result(
+        &mut self,
+        _ctx: PartialResultCtx<'_, '_>,
+        partial_res: Self::PartialResult,
+    ) -> anyhow::Result<()> {
+        self.buf.extend(partial_res.data);
+        Ok(())
+    }
+}
+
 /// Implement a streaming method with full event reporting.
 macro_rules! stream_method {
     ($method: ident, $req: ty, $res: ty, $message: ty) => {
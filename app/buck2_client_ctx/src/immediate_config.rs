@@ -7,6 +7,7 @@
  * of this source tree.
  */
 
+use std::collections::BTreeMap;
 use std::path::Path;
 use std::sync::OnceLock;
 use std::time::SystemTime;
@@ -30,6 +31,7 @@ use prost::Message;
 struct ImmediateConfigContextData {
     cell_resolver: CellResolver,
     daemon_startup_config: DaemonStartupConfig,
+    bxl_aliases: BTreeMap<String, String>,
     project_filesystem: ProjectRoot,
 }
 
@@ -65,6 +67,24 @@ impl<'a> ImmediateConfigContext<'a> {
         Ok(&self.data()?.daemon_startup_config)
     }
 
+    /// The bxl function label that the subcommand `name` is an alias for, if any.
+    pub fn bxl_alias(&self, name: &str) -> anyhow::Result<Option<&str>> {
+        Ok(self
+            .data()?
+            .bxl_aliases
+            .get(name)
+            .map(|label| label.as_str()))
+    }
+
+    /// Cells of the project, from the root buckconfig.
+    pub fn cell_resolver(&self) -> anyhow::Result<&CellResolver> {
+        Ok(&self.data()?.cell_resolver)
+    }
+
+    pub fn project_root(&self) -> anyhow::Result<&ProjectRoot> {
+        Ok(&self.data()?.project_filesystem)
+    }
+
     /// Resolves an argument which can possibly be a cell-relative path.
     /// If the argument is not a cell-relative path, it returns `None`.
     /// Otherwise, it tries to resolve the cell and returns a `Result`.
@@ -129,6 +149,7 @@ impl<'a> ImmediateConfigContext<'a> {
                 anyhow::Ok(ImmediateConfigContextData {
                     cell_resolver: cfg.cell_resolver,
                     daemon_startup_config,
+                    bxl_aliases: cfg.bxl_aliases,
                     project_filesystem,
                 })
             })
@@ -109,6 +109,12 @@ impl InvocationPaths {
             .join(ForwardRelativePath::unchecked_new("build_count"))
     }
 
+    /// SQLite database of past builds, populated by `buck2 log ingest`.
+    pub fn build_history_db_path(&self) -> AbsNormPathBuf {
+        self.buck_out_path()
+            .join(ForwardRelativePath::unchecked_new("build_history.sqlite"))
+    }
+
     pub fn dice_dump_dir(&self) -> AbsNormPathBuf {
         self.buck_out_path()
             .join(ForwardRelativePath::unchecked_new("dice_dump"))
@@ -8,6 +8,7 @@
  */
 
 use std::cell::OnceCell;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 
@@ -94,10 +95,21 @@ impl BuckConfigBasedCells {
             .get(cells.cell_resolver.root_cell())
             .context("No config for root cell")?;
 
+        let bxl_aliases = root_config
+            .get_section("bxl_aliases")
+            .map(|section| {
+                section
+                    .iter()
+                    .map(|(name, label)| (name.to_owned(), label.as_str().to_owned()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
         Ok(ImmediateConfig {
             cell_resolver: cells.cell_resolver,
             daemon_startup_config: DaemonStartupConfig::new(root_config)
                 .context("Error loading daemon startup config")?,
+            bxl_aliases,
         })
     }
 
@@ -419,6 +431,9 @@ fn get_buckconfig_paths_for_cell(
 pub struct ImmediateConfig {
     pub cell_resolver: CellResolver,
     pub daemon_startup_config: DaemonStartupConfig,
+    /// Custom subcommands implemented by bxl functions, from the `[bxl_aliases]` section: maps a
+    /// subcommand name to a bxl function label.
+    pub bxl_aliases: BTreeMap<String, String>,
 }
 
 #[cfg(test)]
@@ -77,6 +77,14 @@ impl BuckConfigTargetAliasResolver {
         Self { config }
     }
 
+    /// Names of all the aliases in the `[alias]` section, sorted.
+    pub fn aliases(&self) -> Vec<&str> {
+        match self.config.get_section("alias") {
+            Some(section) => section.keys().map(|k| k.as_str()).collect(),
+            None => Vec::new(),
+        }
+    }
+
     /// Resolves an alias in the `[alias]` section. Aliases can refer to other aliases. Any
     /// string containing ":" is considered to be the end of the alias resolution.
     fn resolve_alias<'a>(&'a self, alias: &str) -> Result<&'a str, AliasResolutionError> {
@@ -223,6 +231,12 @@ mod tests {
 
         let target_alias_resolver = BuckConfigTargetAliasResolver::new(config);
 
+        assert_eq!(
+            vec![
+                "bar", "bar2", "baz", "chain1", "chain2", "cycle1", "cycle2", "cycle3", "foo"
+            ],
+            target_alias_resolver.aliases()
+        );
         assert_eq!("//:foo", target_alias_resolver.resolve_alias("foo")?);
         assert_eq!("//:foo", target_alias_resolver.resolve_alias("bar")?);
         assert_eq!("//:foo", target_alias_resolver.resolve_alias("bar2")?);
@@ -11,6 +11,8 @@ use std::cmp::Ordering;
 use std::collections::BTreeMap;
 use std::fmt::Write;
 
+use dupe::Dupe;
+
 use crate::configuration::constraints::ConstraintKey;
 use crate::configuration::constraints::ConstraintValue;
 use crate::configuration::data::ConfigurationData;
@@ -145,10 +147,37 @@ pub fn cfg_diff(a: &ConfigurationData, b: &ConfigurationData) -> Result<(), Stri
     Err(diff.s)
 }
 
+/// Constraint values of the constraint settings which are not set to the same value in both
+/// configurations: the values in `a`, and the values in `b`. Constraint settings unset in a
+/// configuration are omitted from its map.
+pub fn cfg_constraint_changes(
+    a: &ConfigurationData,
+    b: &ConfigurationData,
+) -> (
+    BTreeMap<ConstraintKey, ConstraintValue>,
+    BTreeMap<ConstraintKey, ConstraintValue>,
+) {
+    fn changed(
+        x: &BTreeMap<ConstraintKey, ConstraintValue>,
+        y: &BTreeMap<ConstraintKey, ConstraintValue>,
+    ) -> BTreeMap<ConstraintKey, ConstraintValue> {
+        x.iter()
+            .filter(|(k, v)| y.get(*k) != Some(*v))
+            .map(|(k, v)| (k.dupe(), v.dupe()))
+            .collect()
+    }
+
+    let empty = BTreeMap::new();
+    let a = a.data().map_or(&empty, |d| &d.constraints);
+    let b = b.data().map_or(&empty, |d| &d.constraints);
+    (changed(a, b), changed(b, a))
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::BTreeMap;
 
+    use crate::configuration::cfg_diff::cfg_constraint_changes;
     use crate::configuration::cfg_diff::cfg_diff;
     use crate::configuration::constraints::ConstraintKey;
     use crate::configuration::constraints::ConstraintValue;
@@ -201,5 +230,27 @@ mod tests {
             ",
             diff
         );
+
+        let (removed, added) = cfg_constraint_changes(&x, &y);
+        assert_eq!(
+            BTreeMap::from_iter([(
+                ConstraintKey(TargetLabel::testing_parse("foo//qux:c")),
+                ConstraintValue(TargetLabel::testing_parse("foo//qux:vx")),
+            )]),
+            removed
+        );
+        assert_eq!(
+            BTreeMap::from_iter([
+                (
+                    ConstraintKey(TargetLabel::testing_parse("foo//baz:c")),
+                    ConstraintValue(TargetLabel::testing_parse("foo//baz:vy")),
+                ),
+                (
+                    ConstraintKey(TargetLabel::testing_parse("foo//qux:c")),
+                    ConstraintValue(TargetLabel::testing_parse("foo//qux:vy")),
+                ),
+            ]),
+            added
+        );
     }
 }
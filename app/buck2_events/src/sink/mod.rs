@@ -11,6 +11,7 @@
 //! sink during normal operation.
 pub(crate) mod channel;
 pub(crate) mod null;
+pub mod otlp;
 pub mod scribe;
 pub(crate) mod smart_truncate_event;
 pub mod tee;
@@ -0,0 +1,682 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! A Sink for exporting span events to an OpenTelemetry collector using OTLP.
+//!
+//! Only span end events are exported: they carry the span id, the parent span id and the duration of the span, which
+//! is everything an OTLP span needs. Events are converted synchronously in `send` and handed to a background task
+//! through a bounded queue. When the queue is full the span is dropped (and counted as such), so a slow or
+//! unavailable collector never blocks the build.
+
+use std::str::FromStr;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use anyhow::Context;
+use buck2_data::span_end_event;
+use gazebo::variants::VariantName;
+use prost::Message;
+use tokio::sync::mpsc;
+
+use crate::metadata;
+use crate::BuckEvent;
+use crate::Event;
+use crate::EventSink;
+use crate::EventSinkStats;
+use crate::EventSinkWithStats;
+
+/// The subset of the OTLP protocol (`opentelemetry/proto/collector/trace/v1/trace_service.proto` and the messages it
+/// references) that we need to export spans. Field numbers must match the upstream definitions.
+mod proto {
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ExportTraceServiceRequest {
+        #[prost(message, repeated, tag = "1")]
+        pub resource_spans: Vec<ResourceSpans>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ExportTraceServiceResponse {}
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ResourceSpans {
+        #[prost(message, optional, tag = "1")]
+        pub resource: Option<Resource>,
+        #[prost(message, repeated, tag = "2")]
+        pub scope_spans: Vec<ScopeSpans>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Resource {
+        #[prost(message, repeated, tag = "1")]
+        pub attributes: Vec<KeyValue>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ScopeSpans {
+        #[prost(message, optional, tag = "1")]
+        pub scope: Option<InstrumentationScope>,
+        #[prost(message, repeated, tag = "2")]
+        pub spans: Vec<Span>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct InstrumentationScope {
+        #[prost(string, tag = "1")]
+        pub name: String,
+        #[prost(string, tag = "2")]
+        pub version: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Span {
+        #[prost(bytes = "vec", tag = "1")]
+        pub trace_id: Vec<u8>,
+        #[prost(bytes = "vec", tag = "2")]
+        pub span_id: Vec<u8>,
+        #[prost(bytes = "vec", tag = "4")]
+        pub parent_span_id: Vec<u8>,
+        #[prost(string, tag = "5")]
+        pub name: String,
+        #[prost(int32, tag = "6")]
+        pub kind: i32,
+        #[prost(fixed64, tag = "7")]
+        pub start_time_unix_nano: u64,
+        #[prost(fixed64, tag = "8")]
+        pub end_time_unix_nano: u64,
+        #[prost(message, repeated, tag = "9")]
+        pub attributes: Vec<KeyValue>,
+        #[prost(message, optional, tag = "15")]
+        pub status: Option<Status>,
+    }
+
+    /// `SPAN_KIND_INTERNAL`.
+    pub const SPAN_KIND_INTERNAL: i32 = 1;
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Status {
+        #[prost(string, tag = "2")]
+        pub message: String,
+        #[prost(int32, tag = "3")]
+        pub code: i32,
+    }
+
+    /// `STATUS_CODE_OK`.
+    pub const STATUS_CODE_OK: i32 = 1;
+    /// `STATUS_CODE_ERROR`.
+    pub const STATUS_CODE_ERROR: i32 = 2;
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct KeyValue {
+        #[prost(string, tag = "1")]
+        pub key: String,
+        #[prost(message, optional, tag = "2")]
+        pub value: Option<AnyValue>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct AnyValue {
+        #[prost(oneof = "any_value::Value", tags = "1, 2, 3")]
+        pub value: Option<any_value::Value>,
+    }
+
+    pub mod any_value {
+        #[derive(Clone, PartialEq, prost::Oneof)]
+        pub enum Value {
+            #[prost(string, tag = "1")]
+            StringValue(String),
+            #[prost(bool, tag = "2")]
+            BoolValue(bool),
+            #[prost(int64, tag = "3")]
+            IntValue(i64),
+        }
+    }
+}
+
+const EXPORT_TRACE_GRPC_PATH: &str = "/opentelemetry.proto.collector.trace.v1.TraceService/Export";
+const EXPORT_TRACE_HTTP_PATH: &str = "/v1/traces";
+
+#[derive(Debug, buck2_error::Error)]
+enum OtlpError {
+    #[error("Invalid OTLP protocol `{0}`, expected `grpc` or `http`")]
+    InvalidProtocol(String),
+    #[error("Invalid OTLP resource attribute `{0}`, expected `key=value`")]
+    InvalidResourceAttribute(String),
+    #[error("OTLP collector returned HTTP status `{0}`")]
+    HttpStatus(http::StatusCode),
+}
+
+/// The transport used to talk to the collector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OtlpProtocol {
+    /// OTLP/gRPC, usually served on port 4317.
+    Grpc,
+    /// OTLP/HTTP with a binary protobuf payload, usually served on port 4318.
+    Http,
+}
+
+impl FromStr for OtlpProtocol {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "grpc" => Ok(OtlpProtocol::Grpc),
+            "http" => Ok(OtlpProtocol::Http),
+            _ => Err(OtlpError::InvalidProtocol(s.to_owned()).into()),
+        }
+    }
+}
+
+/// Configuration of the OTLP sink, read from the `[buck2_otlp]` buckconfig section.
+#[derive(Debug, Clone)]
+pub struct OtlpSinkConfig {
+    /// Collector endpoint, e.g. `http://localhost:4317`.
+    pub endpoint: String,
+    pub protocol: OtlpProtocol,
+    /// Maximum number of spans sent in a single export request.
+    pub batch_size: usize,
+    /// Maximum number of spans waiting to be exported. Spans are dropped when this is exceeded.
+    pub buffer_size: usize,
+    /// How long to wait for a batch to fill up before exporting it anyway.
+    pub flush_interval: Duration,
+    /// Value of the `service.name` resource attribute.
+    pub service_name: String,
+    /// Additional resource attributes attached to every exported span.
+    pub resource_attributes: Vec<(String, String)>,
+}
+
+impl OtlpSinkConfig {
+    /// Parses resource attributes given as a comma-separated list of `key=value` pairs.
+    pub fn parse_resource_attributes(s: &str) -> anyhow::Result<Vec<(String, String)>> {
+        s.split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|kv| {
+                let (k, v) = kv
+                    .split_once('=')
+                    .ok_or_else(|| OtlpError::InvalidResourceAttribute(kv.to_owned()))?;
+                Ok((k.trim().to_owned(), v.trim().to_owned()))
+            })
+            .collect()
+    }
+}
+
+#[derive(Default)]
+struct OtlpSinkCounters {
+    successes: AtomicU64,
+    failures: AtomicU64,
+    buffered: AtomicU64,
+    dropped: AtomicU64,
+}
+
+/// An EventSink that exports spans to an OpenTelemetry collector.
+pub struct OtlpSink {
+    sender: mpsc::Sender<proto::Span>,
+    counters: Arc<OtlpSinkCounters>,
+}
+
+impl OtlpSink {
+    /// Creates the sink and spawns the exporter task. Must be called from within a Tokio runtime.
+    pub fn new(config: OtlpSinkConfig) -> anyhow::Result<OtlpSink> {
+        let exporter = OtlpExporter::new(&config)?;
+        let resource = resource(&config);
+        let (sender, receiver) = mpsc::channel(config.buffer_size.max(1));
+        let counters = Arc::new(OtlpSinkCounters::default());
+
+        tokio::spawn(export_loop(
+            exporter,
+            resource,
+            receiver,
+            config.batch_size.max(1),
+            config.flush_interval,
+            counters.clone(),
+        ));
+
+        Ok(OtlpSink { sender, counters })
+    }
+
+    fn offer(&self, span: proto::Span) {
+        // Count the span as buffered before it becomes visible to the exporter task, which decrements the count.
+        self.counters.buffered.fetch_add(1, Ordering::Relaxed);
+        if self.sender.try_send(span).is_err() {
+            self.counters.buffered.fetch_sub(1, Ordering::Relaxed);
+            self.counters.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+impl EventSink for OtlpSink {
+    fn send(&self, event: Event) {
+        match event {
+            Event::Buck(event) => {
+                if let Some(span) = to_otlp_span(&event) {
+                    self.offer(span);
+                }
+            }
+            Event::CommandResult(..) => {}
+            Event::PartialResult(..) => {}
+        }
+    }
+}
+
+impl EventSinkWithStats for OtlpSink {
+    fn to_event_sync(self: Arc<Self>) -> Arc<dyn EventSink> {
+        self as _
+    }
+
+    fn stats(&self) -> EventSinkStats {
+        EventSinkStats {
+            successes: self.counters.successes.load(Ordering::Relaxed),
+            failures_invalid_request: 0,
+            failures_unauthorized: 0,
+            failures_rate_limited: 0,
+            failures_pushed_back: 0,
+            failures_enqueue_failed: 0,
+            failures_internal_error: 0,
+            failures_timed_out: 0,
+            failures_unknown: self.counters.failures.load(Ordering::Relaxed),
+            buffered: self.counters.buffered.load(Ordering::Relaxed),
+            dropped: self.counters.dropped.load(Ordering::Relaxed),
+        }
+    }
+}
+
+enum OtlpExporter {
+    Grpc(tonic::client::Grpc<tonic::transport::Channel>),
+    Http {
+        client: hyper::Client<hyper::client::HttpConnector>,
+        uri: http::Uri,
+    },
+}
+
+impl OtlpExporter {
+    fn new(config: &OtlpSinkConfig) -> anyhow::Result<OtlpExporter> {
+        match config.protocol {
+            OtlpProtocol::Grpc => {
+                let channel = tonic::transport::Endpoint::from_shared(config.endpoint.clone())
+                    .with_context(|| format!("Invalid OTLP endpoint `{}`", config.endpoint))?
+                    .connect_lazy();
+                Ok(OtlpExporter::Grpc(tonic::client::Grpc::new(channel)))
+            }
+            OtlpProtocol::Http => {
+                let uri = format!(
+                    "{}{}",
+                    config.endpoint.trim_end_matches('/'),
+                    EXPORT_TRACE_HTTP_PATH
+                );
+                let uri = uri
+                    .parse()
+                    .with_context(|| format!("Invalid OTLP endpoint `{}`", config.endpoint))?;
+                Ok(OtlpExporter::Http {
+                    client: hyper::Client::new(),
+                    uri,
+                })
+            }
+        }
+    }
+
+    async fn export(&mut self, request: proto::ExportTraceServiceRequest) -> anyhow::Result<()> {
+        match self {
+            OtlpExporter::Grpc(grpc) => {
+                grpc.ready().await?;
+                let codec = tonic::codec::ProstCodec::<
+                    proto::ExportTraceServiceRequest,
+                    proto::ExportTraceServiceResponse,
+                >::default();
+                grpc.unary(
+                    tonic::Request::new(request),
+                    http::uri::PathAndQuery::from_static(EXPORT_TRACE_GRPC_PATH),
+                    codec,
+                )
+                .await?;
+                Ok(())
+            }
+            OtlpExporter::Http { client, uri } => {
+                let request = hyper::Request::post(uri.clone())
+                    .header(http::header::CONTENT_TYPE, "application/x-protobuf")
+                    .body(hyper::Body::from(request.encode_to_vec()))?;
+                let response = client.request(request).await?;
+                if !response.status().is_success() {
+                    return Err(OtlpError::HttpStatus(response.status()).into());
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+async fn export_loop(
+    mut exporter: OtlpExporter,
+    resource: proto::Resource,
+    mut receiver: mpsc::Receiver<proto::Span>,
+    batch_size: usize,
+    flush_interval: Duration,
+    counters: Arc<OtlpSinkCounters>,
+) {
+    // Wait for the first span of a batch, then keep collecting until the batch is full or the flush interval elapses.
+    while let Some(first) = receiver.recv().await {
+        let mut spans = Vec::with_capacity(batch_size);
+        spans.push(first);
+
+        let deadline = tokio::time::sleep(flush_interval);
+        tokio::pin!(deadline);
+        while spans.len() < batch_size {
+            tokio::select! {
+                span = receiver.recv() => match span {
+                    Some(span) => spans.push(span),
+                    None => break,
+                },
+                _ = &mut deadline => break,
+            }
+        }
+
+        let count = spans.len() as u64;
+        counters.buffered.fetch_sub(count, Ordering::Relaxed);
+        let request = proto::ExportTraceServiceRequest {
+            resource_spans: vec![proto::ResourceSpans {
+                resource: Some(resource.clone()),
+                scope_spans: vec![proto::ScopeSpans {
+                    scope: Some(proto::InstrumentationScope {
+                        name: "buck2".to_owned(),
+                        version: buck2_build_info::revision().unwrap_or_default().to_owned(),
+                    }),
+                    spans,
+                }],
+            }],
+        };
+        // Failed batches are not retried: export is best-effort and retrying would only grow the backlog.
+        match exporter.export(request).await {
+            Ok(()) => counters.successes.fetch_add(count, Ordering::Relaxed),
+            Err(_) => counters.failures.fetch_add(count, Ordering::Relaxed),
+        };
+    }
+}
+
+fn resource(config: &OtlpSinkConfig) -> proto::Resource {
+    let info = metadata::system_info();
+    let mut attributes = vec![
+        key_value("service.name", config.service_name.clone()),
+        key_value("os.type", info.os),
+    ];
+    if let Some(hostname) = info.hostname {
+        attributes.push(key_value("host.name", hostname));
+    }
+    if let Some(username) = info.username {
+        attributes.push(key_value("enduser.id", username));
+    }
+    if let Some(rev) = buck2_build_info::revision() {
+        attributes.push(key_value("service.version", rev.to_owned()));
+    }
+    attributes.push(key_value(
+        "buck2.daemon_uuid",
+        crate::daemon_id::DAEMON_UUID.to_string(),
+    ));
+    for (k, v) in &config.resource_attributes {
+        attributes.push(key_value(k, v.clone()));
+    }
+    proto::Resource { attributes }
+}
+
+fn key_value(key: &str, value: impl Into<AttributeValue>) -> proto::KeyValue {
+    let value = match value.into() {
+        AttributeValue::String(s) => proto::any_value::Value::StringValue(s),
+        AttributeValue::Bool(b) => proto::any_value::Value::BoolValue(b),
+        AttributeValue::Int(i) => proto::any_value::Value::IntValue(i),
+    };
+    proto::KeyValue {
+        key: key.to_owned(),
+        value: Some(proto::AnyValue { value: Some(value) }),
+    }
+}
+
+#[derive(derive_more::From)]
+enum AttributeValue {
+    String(String),
+    Bool(bool),
+    Int(i64),
+}
+
+fn unix_nanos(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64)
+}
+
+fn display_configured_target_label(label: &buck2_data::ConfiguredTargetLabel) -> String {
+    let (package, name) = label
+        .label
+        .as_ref()
+        .map_or(("", ""), |l| (l.package.as_str(), l.name.as_str()));
+    match &label.configuration {
+        Some(cfg) => format!("{}:{} ({})", package, name, cfg.full_name),
+        None => format!("{}:{}", package, name),
+    }
+}
+
+/// Converts a span end event into an OTLP span, if it is one of the span kinds we export.
+fn to_otlp_span(event: &BuckEvent) -> Option<proto::Span> {
+    let end = event.span_end_event()?;
+    let data = end.data.as_ref()?;
+    let span_id = event.span_id()?;
+
+    let mut attributes = Vec::new();
+    let mut error = None;
+
+    match data {
+        span_end_event::Data::Command(command) => {
+            if let Some(data) = &command.data {
+                attributes.push(key_value("buck2.command", data.variant_name().to_owned()));
+            }
+            if !command.is_success {
+                error = Some(
+                    command
+                        .errors
+                        .first()
+                        .map(|e| e.message.clone())
+                        .unwrap_or_default(),
+                );
+            }
+        }
+        span_end_event::Data::ActionExecution(action) => {
+            if let Some(owner) = action.key.as_ref().and_then(|k| k.owner.as_ref()) {
+                use buck2_data::action_key::Owner;
+                let owner = match owner {
+                    Owner::TargetLabel(l)
+                    | Owner::TestTargetLabel(l)
+                    | Owner::LocalResourceSetup(l) => display_configured_target_label(l),
+                    Owner::BxlKey(k) => k
+                        .label
+                        .as_ref()
+                        .map_or_else(String::new, |l| format!("{}:{}", l.bxl_path, l.name)),
+                    Owner::AnonTarget(t) => t
+                        .name
+                        .as_ref()
+                        .map_or_else(String::new, |l| format!("{}:{}", l.package, l.name)),
+                };
+                attributes.push(key_value("buck2.action.owner", owner));
+            }
+            if let Some(name) = &action.name {
+                attributes.push(key_value("buck2.action.category", name.category.clone()));
+                if !name.identifier.is_empty() {
+                    attributes.push(key_value(
+                        "buck2.action.identifier",
+                        name.identifier.clone(),
+                    ));
+                }
+            }
+            if let Some(kind) = buck2_data::ActionExecutionKind::from_i32(action.execution_kind) {
+                attributes.push(key_value(
+                    "buck2.action.execution_kind",
+                    kind.as_str_name().to_owned(),
+                ));
+            }
+            if action.failed {
+                error = Some(String::new());
+            }
+        }
+        span_end_event::Data::Analysis(analysis) => {
+            if let Some(buck2_data::analysis_end::Target::StandardTarget(label)) = &analysis.target
+            {
+                attributes.push(key_value(
+                    "buck2.target",
+                    display_configured_target_label(label),
+                ));
+            }
+            attributes.push(key_value("buck2.rule", analysis.rule.clone()));
+        }
+        span_end_event::Data::Load(load) => {
+            attributes.push(key_value("buck2.package", load.module_id.clone()));
+            error = load.error.clone();
+        }
+        span_end_event::Data::Materialization(materialization) => {
+            attributes.push(key_value("buck2.path", materialization.path.clone()));
+            attributes.push(key_value(
+                "buck2.materialization.total_bytes",
+                materialization.total_bytes as i64,
+            ));
+            attributes.push(key_value(
+                "buck2.materialization.file_count",
+                materialization.file_count as i64,
+            ));
+            if !materialization.success {
+                error = Some(materialization.error.clone().unwrap_or_default());
+            }
+        }
+        span_end_event::Data::FinalMaterialization(..) => {}
+        span_end_event::Data::CacheUpload(upload) => {
+            attributes.push(key_value("buck2.cache_upload.success", upload.success));
+        }
+        span_end_event::Data::TestDiscovery(..) => {}
+        span_end_event::Data::TestEnd(test) => {
+            if let Some(suite) = &test.suite {
+                attributes.push(key_value("buck2.test.suite", suite.suite_name.clone()));
+                if let Some(label) = &suite.target_label {
+                    attributes.push(key_value(
+                        "buck2.target",
+                        display_configured_target_label(label),
+                    ));
+                }
+            }
+        }
+        span_end_event::Data::BxlExecution(..) => {}
+        _ => return None,
+    }
+
+    let end_time = event.timestamp();
+    let start_time = end
+        .duration
+        .clone()
+        .and_then(|d| Duration::try_from(d).ok())
+        .and_then(|d| end_time.checked_sub(d))
+        .unwrap_or(end_time);
+
+    let trace_id = event.trace_id().ok()?;
+
+    Some(proto::Span {
+        trace_id: trace_id.as_bytes().to_vec(),
+        span_id: u64::from(span_id).to_be_bytes().to_vec(),
+        parent_span_id: event
+            .parent_id()
+            .map_or_else(Vec::new, |p| u64::from(p).to_be_bytes().to_vec()),
+        name: data.variant_name().to_owned(),
+        kind: proto::SPAN_KIND_INTERNAL,
+        start_time_unix_nano: unix_nanos(start_time),
+        end_time_unix_nano: unix_nanos(end_time),
+        attributes,
+        status: Some(match error {
+            Some(message) => proto::Status {
+                message,
+                code: proto::STATUS_CODE_ERROR,
+            },
+            None => proto::Status {
+                message: String::new(),
+                code: proto::STATUS_CODE_OK,
+            },
+        }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use buck2_data::LoadBuildFileEnd;
+    use buck2_data::SpanEndEvent;
+    use buck2_wrapper_common::invocation_id::TraceId;
+
+    use super::*;
+    use crate::span::SpanId;
+
+    fn span_end(data: span_end_event::Data, duration: Duration) -> BuckEvent {
+        BuckEvent::new(
+            SystemTime::now(),
+            TraceId::new(),
+            Some(SpanId::next()),
+            Some(SpanId::next()),
+            SpanEndEvent {
+                stats: None,
+                duration: duration.try_into().ok(),
+                data: Some(data),
+            }
+            .into(),
+        )
+    }
+
+    #[test]
+    fn test_load_span() {
+        let event = span_end(
+            LoadBuildFileEnd {
+                module_id: "root//foo".to_owned(),
+                cell: "root".to_owned(),
+                error: None,
+                starlark_peak_allocated_bytes: None,
+                cpu_instruction_count: None,
+            }
+            .into(),
+            Duration::from_millis(10),
+        );
+        let span = to_otlp_span(&event).unwrap();
+        assert_eq!("Load", span.name);
+        assert_eq!(16, span.trace_id.len());
+        assert_eq!(
+            u64::from(event.span_id().unwrap()).to_be_bytes().to_vec(),
+            span.span_id
+        );
+        assert_eq!(
+            u64::from(event.parent_id().unwrap()).to_be_bytes().to_vec(),
+            span.parent_span_id
+        );
+        assert_eq!(
+            10_000_000,
+            span.end_time_unix_nano - span.start_time_unix_nano
+        );
+        assert_eq!(proto::STATUS_CODE_OK, span.status.unwrap().code);
+    }
+
+    #[test]
+    fn test_uninteresting_span_is_skipped() {
+        let event = span_end(
+            buck2_data::SpanCancelled {}.into(),
+            Duration::from_millis(1),
+        );
+        assert_eq!(None, to_otlp_span(&event));
+    }
+
+    #[test]
+    fn test_parse_resource_attributes() {
+        assert_eq!(
+            vec![
+                ("team".to_owned(), "build".to_owned()),
+                ("env".to_owned(), "ci".to_owned())
+            ],
+            OtlpSinkConfig::parse_resource_attributes("team=build, env=ci,").unwrap()
+        );
+        assert!(OtlpSinkConfig::parse_resource_attributes("team").is_err());
+    }
+}
@@ -0,0 +1,141 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! A structured, human-readable description of everything that goes into an action's digest.
+//! This is what you want when an action misses the cache and you need to find out why.
+
+use buck2_core::directory::Directory;
+use buck2_core::directory::DirectoryEntry;
+use buck2_core::directory::DirectoryIterator;
+use buck2_core::fs::artifact_path_resolver::ArtifactFs;
+
+use crate::directory::ActionDirectoryMember;
+use crate::directory::ActionImmutableDirectory;
+use crate::execute::prepared::PreparedAction;
+use crate::execute::request::CommandExecutionRequest;
+use crate::execute::target::CommandExecutionTarget;
+
+/// Build the input manifest for a command that is about to be executed.
+pub fn action_input_manifest(
+    fs: &ArtifactFs,
+    target: &dyn CommandExecutionTarget,
+    request: &CommandExecutionRequest,
+    prepared_action: &PreparedAction,
+) -> buck2_data::ActionInputManifest {
+    buck2_data::ActionInputManifest {
+        key: Some(target.as_proto_action_key()),
+        name: Some(target.as_proto_action_name()),
+        action_digest: prepared_action.digest().to_string(),
+        argv: request.all_args_vec(),
+        // `env` is a sorted map, so this is sorted by key.
+        env: request
+            .env()
+            .iter()
+            .map(|(key, value)| buck2_data::EnvironmentEntry {
+                key: key.clone(),
+                value: value.clone(),
+            })
+            .collect(),
+        inputs: input_manifest_entries(request.paths().input_directory()),
+        outputs: request
+            .outputs()
+            .map(|output| output.resolve(fs).into_path().to_string())
+            .collect(),
+    }
+}
+
+/// List all the leaves of an input directory, sorted by path.
+fn input_manifest_entries(
+    input_directory: &ActionImmutableDirectory,
+) -> Vec<buck2_data::ActionInputManifestEntry> {
+    let mut entries = Vec::new();
+    let mut walk = input_directory.ordered_walk();
+    while let Some((path, entry)) = walk.next() {
+        let (entry, is_executable) = match entry {
+            DirectoryEntry::Dir(_) => continue,
+            DirectoryEntry::Leaf(ActionDirectoryMember::File(meta)) => (
+                buck2_data::action_input_manifest_entry::Entry::FileDigest(meta.digest.to_string()),
+                meta.is_executable,
+            ),
+            DirectoryEntry::Leaf(ActionDirectoryMember::Symlink(symlink)) => (
+                buck2_data::action_input_manifest_entry::Entry::SymlinkTarget(
+                    symlink.target().to_string(),
+                ),
+                false,
+            ),
+            DirectoryEntry::Leaf(ActionDirectoryMember::ExternalSymlink(symlink)) => (
+                buck2_data::action_input_manifest_entry::Entry::SymlinkTarget(symlink.to_string()),
+                false,
+            ),
+        };
+        entries.push(buck2_data::ActionInputManifestEntry {
+            path: path.get().to_string(),
+            entry: Some(entry),
+            is_executable,
+        });
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use buck2_common::file_ops::FileMetadata;
+    use buck2_core::fs::project_rel_path::ProjectRelativePath;
+
+    use super::*;
+    use crate::digest_config::DigestConfig;
+    use crate::directory::insert_file;
+    use crate::directory::insert_symlink;
+    use crate::directory::ActionDirectoryBuilder;
+    use crate::directory::Symlink;
+
+    #[test]
+    fn test_input_manifest_entries() -> anyhow::Result<()> {
+        let digest_config = DigestConfig::testing_default();
+
+        let mut builder = ActionDirectoryBuilder::empty();
+        insert_file(
+            &mut builder,
+            ProjectRelativePath::new("b/file")?,
+            FileMetadata::empty(digest_config.cas_digest_config()),
+        )?;
+        insert_symlink(
+            &mut builder,
+            ProjectRelativePath::new("a/link")?,
+            Arc::new(Symlink::new("../b/file".into())),
+        )?;
+        let dir = builder.fingerprint(digest_config.as_directory_serializer());
+
+        let entries = input_manifest_entries(&dir);
+        assert_eq!(
+            vec!["a/link", "b/file"],
+            entries.iter().map(|e| e.path.as_str()).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Some(
+                buck2_data::action_input_manifest_entry::Entry::SymlinkTarget(
+                    "../b/file".to_owned()
+                )
+            ),
+            entries[0].entry
+        );
+        assert_eq!(
+            Some(buck2_data::action_input_manifest_entry::Entry::FileDigest(
+                FileMetadata::empty(digest_config.cas_digest_config())
+                    .digest
+                    .to_string()
+            )),
+            entries[1].entry
+        );
+
+        Ok(())
+    }
+}
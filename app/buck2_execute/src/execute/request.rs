@@ -8,6 +8,7 @@
  */
 
 use std::fmt::Display;
+use std::sync::Arc;
 use std::time::Duration;
 
 use allocative::Allocative;
@@ -285,12 +286,14 @@ pub struct WorkerSpec {
 }
 
 /// The data contains the information about the command to be executed.
+#[derive(Clone)]
 pub struct CommandExecutionRequest {
     /// Optional arguments including executable prepended to `args` to get full command line.
     /// This is used by workers to separate worker arguments from executable arguments.
     exe: Vec<String>,
     args: Vec<String>,
-    paths: CommandExecutionPaths,
+    /// Shared, so a request can be cloned to rerun the command with different settings.
+    paths: Arc<CommandExecutionPaths>,
     env: SortedVectorMap<String, String>,
     timeout: Option<Duration>,
     executor_preference: ExecutorPreference,
@@ -333,7 +336,7 @@ impl CommandExecutionRequest {
         Self {
             exe,
             args,
-            paths,
+            paths: Arc::new(paths),
             env,
             timeout: None,
             executor_preference: ExecutorPreference::Default,
@@ -108,6 +108,7 @@ impl PreparedCommandExecutor for DryRunExecutor {
     }
 
     fn is_local_execution_possible(&self, _executor_preference: ExecutorPreference) -> bool {
-        false
+        // Commands are "run" on the local file system.
+        true
     }
 }
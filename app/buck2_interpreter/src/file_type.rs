@@ -78,4 +78,17 @@ impl StarlarkFileType {
             Self::Bxl => bxl_dialect,
         }
     }
+
+    /// Dialect used when `buck2.strict_build_files` is enabled for the cell.
+    /// Only `BUCK` files are stricter: they cannot reassign or mutate global variables.
+    pub fn strict_dialect(&self, disable_starlark_types: bool) -> Dialect {
+        let dialect = self.dialect(disable_starlark_types);
+        match self {
+            Self::Buck => Dialect {
+                enable_mutable_globals: false,
+                ..dialect
+            },
+            Self::Bzl | Self::Bxl | Self::Package => dialect,
+        }
+    }
 }
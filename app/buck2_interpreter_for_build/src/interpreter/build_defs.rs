@@ -139,8 +139,11 @@ pub fn starlark_library_extensions_for_buck2() -> &'static [LibraryExtension] {
         LibraryExtension::Prepr,
         LibraryExtension::Print,
         LibraryExtension::RecordType,
+        LibraryExtension::SetType,
         LibraryExtension::StructType,
+        LibraryExtension::Toml,
         LibraryExtension::Typing,
+        LibraryExtension::Yaml,
         LibraryExtension::Internal,
         LibraryExtension::CallStack,
     ]
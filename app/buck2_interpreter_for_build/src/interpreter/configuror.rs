@@ -177,6 +177,7 @@ impl BuildInterpreterConfiguror {
         package_boundary_exception: bool,
         loaded_modules: &LoadedModules,
         implicit_import: Option<&Arc<ImplicitImport>>,
+        strict_build_files: bool,
     ) -> anyhow::Result<ModuleInternals> {
         let record_target_call_stack = self.record_target_call_stack;
         let skip_targets_with_duplicate_names = self.skip_targets_with_duplicate_names;
@@ -213,6 +214,7 @@ impl BuildInterpreterConfiguror {
             skip_targets_with_duplicate_names,
             package_listing,
             super_package,
+            strict_build_files,
         ))
     }
 
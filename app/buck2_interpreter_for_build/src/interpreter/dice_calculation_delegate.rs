@@ -108,11 +108,16 @@ impl<'c, 'd> HasCalculationDelegate<'c, 'd> for DiceComputations<'d> {
                 let cell = cell_resolver.get(self.0)?;
 
                 let implicit_import_paths = ctx.import_paths_for_cell(self.1).await?;
+                let strict_build_files = ctx
+                    .parse_legacy_config_property(self.0, "buck2", "strict_build_files")
+                    .await?
+                    .unwrap_or(false);
 
                 Ok(Arc::new(InterpreterForCell::new(
                     cell.cell_alias_resolver().dupe(),
                     global_state.dupe(),
                     implicit_import_paths,
+                    strict_build_files,
                 )?))
             }
 
@@ -16,6 +16,7 @@ use starlark::values::StringValueLike;
 use starlark::values::Value;
 
 use crate::interpreter::build_context::BuildContext;
+use crate::interpreter::strict_build_files::check_not_called_from_build_file;
 
 #[starlark_module]
 pub fn register_read_config(globals: &mut GlobalsBuilder) {
@@ -39,6 +40,9 @@ pub fn register_read_config(globals: &mut GlobalsBuilder) {
     ///
     /// In general the use of `.buckconfig` is discouraged in favour of `select`,
     /// but it can still be useful.
+    ///
+    /// When `buck2.strict_build_files` is enabled, `BUCK` files cannot call it directly,
+    /// only through macros defined in `.bzl` files.
     #[starlark(speculative_exec_safe)]
     fn read_config<'v>(
         section: StringValue,
@@ -46,6 +50,7 @@ pub fn register_read_config(globals: &mut GlobalsBuilder) {
         default: Option<Value<'v>>,
         eval: &mut Evaluator<'v, '_, '_>,
     ) -> anyhow::Result<Value<'v>> {
+        check_not_called_from_build_file(eval, "read_config")?;
         let buckconfigs = &BuildContext::from_context(eval)?.buckconfigs;
         match buckconfigs.current_cell_get(section, key)? {
             Some(v) => Ok(v.to_value()),
@@ -63,6 +68,7 @@ pub fn register_read_config(globals: &mut GlobalsBuilder) {
         #[starlark(require = pos, default = NoneOr::None)] default: NoneOr<StringValue<'v>>,
         eval: &mut Evaluator<'v, '_, '_>,
     ) -> anyhow::Result<NoneOr<StringValue<'v>>> {
+        check_not_called_from_build_file(eval, "read_root_config")?;
         let buckconfigs = &BuildContext::from_context(eval)?.buckconfigs;
         match buckconfigs.root_cell_get(section, key)? {
             Some(v) => Ok(NoneOr::Other(v.to_string_value())),
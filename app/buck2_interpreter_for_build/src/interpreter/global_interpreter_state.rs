@@ -28,6 +28,7 @@ use starlark::environment::Globals;
 use crate::interpreter::cell_info::InterpreterCellInfo;
 use crate::interpreter::configuror::BuildInterpreterConfiguror;
 use crate::interpreter::context::HasInterpreterContext;
+use crate::interpreter::parse_cache::ParseCache;
 
 /// Information shared across interpreters. Contains no cell-specific
 /// information.
@@ -63,6 +64,9 @@ pub struct GlobalInterpreterState {
 
     /// Static typechecking for bzl and bxl files.
     pub unstable_typecheck: bool,
+
+    /// Parsed files, reused when a file is evaluated again without changes.
+    pub(crate) parse_cache: ParseCache,
 }
 
 impl GlobalInterpreterState {
@@ -97,6 +101,7 @@ impl GlobalInterpreterState {
             configuror: interpreter_configuror,
             disable_starlark_types,
             unstable_typecheck,
+            parse_cache: ParseCache::default(),
         })
     }
 
@@ -184,6 +184,32 @@ impl GlobSpec {
     }
 }
 
+/// Does `glob(include, exclude = exclude)` match any file in the listing.
+/// Errors if the patterns are invalid.
+pub fn glob_matches_any<P: AsRef<str>, Q: AsRef<str>>(
+    include: &[P],
+    exclude: &[Q],
+    listing: &PackageFileListing,
+) -> anyhow::Result<bool> {
+    Ok(GlobSpec::new(include, exclude)?
+        .resolve_glob(listing)
+        .next()
+        .is_some())
+}
+
+/// The paths which `glob(include, exclude = exclude)` matches, out of `paths`.
+/// Errors if the patterns are invalid.
+pub fn glob_matching_paths<'a, P: AsRef<str>, Q: AsRef<str>>(
+    include: &[P],
+    exclude: &[Q],
+    paths: impl IntoIterator<Item = &'a ForwardRelativePath>,
+) -> anyhow::Result<impl Iterator<Item = &'a ForwardRelativePath>> {
+    let spec = GlobSpec::new(include, exclude)?;
+    Ok(paths
+        .into_iter()
+        .filter(move |path| spec.matches(path.as_str())))
+}
+
 #[cfg(test)]
 mod tests {
     use buck2_core::package::package_relative_path::PackageRelativePathBuf;
@@ -266,6 +292,28 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_glob_matching_paths() -> anyhow::Result<()> {
+        let paths = [
+            "defs.bzl",
+            ".hidden.bzl",
+            "dir/defs.bzl",
+            "src/lib.rs",
+            "src/a/b/lib.rs",
+            "src/a/test_lib.rs",
+        ]
+        .map(ForwardRelativePath::unchecked_new);
+
+        let matches: Vec<_> =
+            glob_matching_paths(&["*.bzl", "src/**/*.rs"], &["src/**/test_*.rs"], paths)?
+                .map(ForwardRelativePath::as_str)
+                .collect();
+        assert_eq!(vec!["defs.bzl", "src/lib.rs", "src/a/b/lib.rs"], matches);
+
+        assert!(glob_matching_paths(&["a/../b"], &[""; 0], paths).is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_resolve_glob_dot() {
         fn glob(pattern: &str, listing: &PackageFileListing) -> Vec<PackageRelativePathBuf> {
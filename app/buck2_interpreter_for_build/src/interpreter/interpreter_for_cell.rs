@@ -66,6 +66,7 @@ use crate::interpreter::extra_value::InterpreterExtraValue;
 use crate::interpreter::global_interpreter_state::GlobalInterpreterState;
 use crate::interpreter::module_internals::ModuleInternals;
 use crate::interpreter::package_file_extra::FrozenPackageFileExtra;
+use crate::interpreter::strict_build_files::check_typed_macros;
 use crate::super_package::eval_ctx::PackageFileEvalCtx;
 
 #[derive(Debug, buck2_error::Error)]
@@ -160,6 +161,9 @@ pub(crate) struct InterpreterForCell {
     /// Implicit imports. These are only used for build files (e.g. `BUCK`),
     /// not for `bzl` or other files, because we only have implicit imports for build files.
     implicit_import_paths: Arc<ImplicitImportPaths>,
+    /// `buck2.strict_build_files` of the cell: `BUCK` files are parsed with
+    /// the strict dialect and evaluated with the checks in `strict_build_files.rs`.
+    strict_build_files: bool,
 }
 
 struct InterpreterLoadResolver {
@@ -287,6 +291,7 @@ impl InterpreterForCell {
         cell_names: CellAliasResolver,
         global_state: Arc<GlobalInterpreterState>,
         implicit_import_paths: Arc<ImplicitImportPaths>,
+        strict_build_files: bool,
     ) -> anyhow::Result<Self> {
         Ok(Self {
             global_state,
@@ -294,6 +299,7 @@ impl InterpreterForCell {
             verbose_gc: Self::verbose_gc()?,
             ignore_attrs_for_profiling: Self::is_ignore_attrs_for_profiling()?,
             implicit_import_paths,
+            strict_build_files,
         })
     }
 
@@ -350,6 +356,7 @@ impl InterpreterForCell {
             package_boundary_exception,
             loaded_modules,
             self.package_import(build_file),
+            self.strict_build_files,
         )?;
         let env = self.create_env(StarlarkPath::BuildFile(build_file), loaded_modules)?;
 
@@ -440,10 +447,15 @@ impl InterpreterForCell {
             .resolve_path(import.path().as_ref().as_ref())?;
 
         let disable_starlark_types = self.global_state.disable_starlark_types;
-        let ast = match AstModule::parse(
+        let dialect = if self.strict_build_files {
+            import.file_type().strict_dialect(disable_starlark_types)
+        } else {
+            import.file_type().dialect(disable_starlark_types)
+        };
+        let ast = match self.global_state.parse_cache.parse(
             project_relative_path.as_str(),
             content,
-            &import.file_type().dialect(disable_starlark_types),
+            &dialect,
         ) {
             Ok(ast) => ast,
             Err(e) => {
@@ -652,6 +664,13 @@ impl InterpreterForCell {
         eval_provider: &mut dyn StarlarkEvaluatorProvider,
         unstable_typecheck: bool,
     ) -> anyhow::Result<EvaluationResultWithStats> {
+        if self.strict_build_files {
+            check_typed_macros(
+                &ast,
+                &self.load_resolver(StarlarkPath::BuildFile(build_file)),
+                &loaded_modules,
+            )?;
+        }
         let (env, internals) = self.create_build_env(
             build_file,
             &listing,
@@ -28,5 +28,7 @@ pub mod module_internals;
 pub mod natives;
 pub mod package_file_calculation;
 pub mod package_file_extra;
+pub(crate) mod parse_cache;
 pub mod selector;
+pub(crate) mod strict_build_files;
 pub mod testing;
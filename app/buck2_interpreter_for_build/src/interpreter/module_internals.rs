@@ -87,6 +87,8 @@ pub struct ModuleInternals {
     /// The files owned by this directory. Is `None` for .bzl files.
     package_listing: PackageListing,
     pub(crate) super_package: SuperPackage,
+    /// `buck2.strict_build_files` is enabled for the cell of this build file.
+    strict_build_files: bool,
 }
 
 #[derive(Debug)]
@@ -126,6 +128,7 @@ impl ModuleInternals {
         skip_targets_with_duplicate_names: bool,
         package_listing: PackageListing,
         super_package: SuperPackage,
+        strict_build_files: bool,
     ) -> Self {
         Self {
             attr_coercion_context,
@@ -137,6 +140,7 @@ impl ModuleInternals {
             skip_targets_with_duplicate_names,
             package_listing,
             super_package,
+            strict_build_files,
         }
     }
 
@@ -220,6 +224,10 @@ impl ModuleInternals {
         self.record_target_call_stacks
     }
 
+    pub(crate) fn strict_build_files(&self) -> bool {
+        self.strict_build_files
+    }
+
     pub(crate) fn resolve_glob<'a>(
         &'a self,
         spec: &'a GlobSpec,
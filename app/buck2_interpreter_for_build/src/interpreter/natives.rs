@@ -14,6 +14,7 @@ use starlark::values::none::NoneType;
 use starlark::values::Value;
 
 use crate::interpreter::module_internals::ModuleInternals;
+use crate::interpreter::strict_build_files::check_order_independent;
 
 #[starlark_module]
 pub fn register_module_natives(globals: &mut GlobalsBuilder) {
@@ -22,8 +23,12 @@ pub fn register_module_natives(globals: &mut GlobalsBuilder) {
     ///
     /// Note that this function checks for the existence of a _target_ rather than a _rule_.
     /// In general use of this function is discouraged, as it makes definitions of rules not compose.
+    ///
+    /// Not available when `buck2.strict_build_files` is enabled.
     fn rule_exists(name: &str, eval: &mut Evaluator) -> anyhow::Result<bool> {
-        Ok(ModuleInternals::from_context(eval, "rule_exists")?.target_exists(name))
+        let internals = ModuleInternals::from_context(eval, "rule_exists")?;
+        check_order_independent(internals, "rule_exists")?;
+        Ok(internals.target_exists(name))
     }
 
     /// Called in a `BUCK` file to declare the oncall contact details for
@@ -0,0 +1,141 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use allocative::Allocative;
+use allocative::Key;
+use allocative::Visitor;
+use sha2::Digest;
+use sha2::Sha256;
+use starlark::syntax::AstModule;
+use starlark::syntax::Dialect;
+
+struct ParseCacheEntry {
+    /// Sha256 of the content the AST was parsed from.
+    content_hash: [u8; 32],
+    content_len: usize,
+    ast: Arc<AstModule>,
+}
+
+/// Parsed Starlark files, shared by all the evaluations with the same global interpreter state.
+///
+/// When a `.bzl` file changes, the files loading it are evaluated again, but their content
+/// is unchanged, so their AST is taken from this cache instead of being parsed again.
+///
+/// Entries are keyed by the file name (which is part of the AST, for error messages) and
+/// the dialect, and are reused only if the file content has the same hash. There is at most
+/// one entry per file and dialect, so an entry is replaced when the file changes.
+///
+/// Only the AST is cached: the compiled bytecode refers to values of the module heap and of
+/// the loaded modules, so it cannot be shared across evaluations.
+#[derive(Default)]
+pub(crate) struct ParseCache {
+    entries: Mutex<HashMap<(String, Dialect), ParseCacheEntry>>,
+}
+
+impl ParseCache {
+    /// Same as `AstModule::parse`, but reusing the AST of a previous parse of the same content.
+    pub(crate) fn parse(
+        &self,
+        filename: &str,
+        content: String,
+        dialect: &Dialect,
+    ) -> starlark::Result<AstModule> {
+        let content_hash: [u8; 32] = Sha256::digest(content.as_bytes()).into();
+        let key = (filename.to_owned(), dialect.clone());
+        let cached = self
+            .entries
+            .lock()
+            .unwrap()
+            .get(&key)
+            .filter(|entry| entry.content_hash == content_hash)
+            .map(|entry| entry.ast.clone());
+        if let Some(ast) = cached {
+            // Evaluation consumes the AST, cloning it is still much cheaper than parsing.
+            return Ok((*ast).clone());
+        }
+
+        let content_len = content.len();
+        let ast = AstModule::parse(filename, content, dialect)?;
+        self.entries.lock().unwrap().insert(
+            key,
+            ParseCacheEntry {
+                content_hash,
+                content_len,
+                ast: Arc::new(ast.clone()),
+            },
+        );
+        Ok(ast)
+    }
+}
+
+impl Allocative for ParseCache {
+    fn visit<'a, 'b: 'a>(&self, visitor: &'a mut Visitor<'b>) {
+        let mut visitor = visitor.enter_self_sized::<Self>();
+        if let Ok(entries) = self.entries.try_lock() {
+            for ((filename, _), entry) in entries.iter() {
+                visitor.visit_field(Key::new("filename"), filename);
+                // The AST does not implement `Allocative`, so report the size of the source,
+                // which the size of the AST is roughly proportional to.
+                visitor.visit_simple(Key::new("ast"), entry.content_len);
+            }
+        }
+        visitor.exit();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cache() {
+        let cache = ParseCache::default();
+        // The number of statements, to tell the parsed files apart.
+        let parse = |filename: &str, content: &str, dialect: &Dialect| {
+            cache
+                .parse(filename, content.to_owned(), dialect)
+                .map(|ast| ast.stmt_locations().len())
+        };
+        let entries = || cache.entries.lock().unwrap().len();
+
+        assert_eq!(1, parse("a.bzl", "x = 1", &Dialect::Extended).unwrap());
+        assert_eq!(1, parse("a.bzl", "x = 1", &Dialect::Extended).unwrap());
+        assert_eq!(1, entries());
+
+        // A changed file replaces the entry.
+        assert_eq!(
+            2,
+            parse("a.bzl", "x = 1\ny = 2", &Dialect::Extended).unwrap()
+        );
+        assert_eq!(1, entries());
+
+        // The file name and the dialect are part of the key.
+        assert_eq!(
+            2,
+            parse("b.bzl", "x = 1\ny = 2", &Dialect::Extended).unwrap()
+        );
+        assert_eq!(
+            2,
+            parse("a.bzl", "x = 1\ny = 2", &Dialect::Standard).unwrap()
+        );
+        assert_eq!(3, entries());
+
+        // Parse errors are not cached.
+        assert!(parse("a.bzl", "def f(:", &Dialect::Extended).is_err());
+        assert_eq!(
+            2,
+            parse("a.bzl", "x = 1\ny = 2", &Dialect::Extended).unwrap()
+        );
+        assert_eq!(3, entries());
+    }
+}
@@ -0,0 +1,143 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Checks for `BUCK` files in cells with `buck2.strict_build_files = true`.
+//!
+//! Reassignment and mutation of globals are rejected when parsing
+//! (see `StarlarkFileType::strict_dialect`), everything else is checked here.
+
+use buck2_interpreter::error::BuckStarlarkError;
+use buck2_interpreter::file_loader::LoadResolver;
+use buck2_interpreter::file_loader::LoadedModules;
+use starlark::codemap::FileSpan;
+use starlark::docs::DocItem;
+use starlark::docs::DocParam;
+use starlark::eval::Evaluator;
+use starlark::syntax::AstModule;
+use starlark::typing::Ty;
+
+use crate::interpreter::build_context::BuildContext;
+use crate::interpreter::build_context::PerFileTypeContext;
+use crate::interpreter::module_internals::ModuleInternals;
+
+#[derive(Debug, buck2_error::Error)]
+enum StrictBuildFileError {
+    #[error(
+        "`{0}()` cannot be called directly from a `BUCK` file when `buck2.strict_build_files` is enabled, \
+        call it from a `PACKAGE` file or from a macro in a `.bzl` file"
+    )]
+    #[buck2(user)]
+    CalledFromBuildFile(String),
+    #[error(
+        "`{0}()` cannot be used when `buck2.strict_build_files` is enabled, \
+        because its result depends on the order in which targets are declared"
+    )]
+    #[buck2(user)]
+    OrderDependent(String),
+    #[error(
+        "Macro `{0}` is loaded by a `BUCK` file with `buck2.strict_build_files` enabled, \
+        so all its parameters must have type annotations, but these do not: {1}"
+    )]
+    #[buck2(user)]
+    UntypedMacro(String, String),
+}
+
+/// Fail if `function` is called directly from a strict `BUCK` file.
+/// Calls from macros in `.bzl` files are fine.
+pub(crate) fn check_not_called_from_build_file(
+    eval: &Evaluator,
+    function: &str,
+) -> anyhow::Result<()> {
+    let ctx = BuildContext::from_context(eval)?;
+    let internals = match &ctx.additional {
+        PerFileTypeContext::Build(internals) if internals.strict_build_files() => internals,
+        _ => return Ok(()),
+    };
+    let build_file = ctx
+        .cell_resolver()
+        .resolve_path(internals.buildfile_path().path().as_ref())?;
+    match eval.call_stack_top_location() {
+        Some(location) if location.filename() == build_file.as_str() => {
+            Err(StrictBuildFileError::CalledFromBuildFile(function.to_owned()).into())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Fail if `function`, whose result depends on the order targets are declared in,
+/// is called while evaluating a strict `BUCK` file.
+pub(crate) fn check_order_independent(
+    internals: &ModuleInternals,
+    function: &str,
+) -> anyhow::Result<()> {
+    if internals.strict_build_files() {
+        Err(StrictBuildFileError::OrderDependent(function.to_owned()).into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Check that every `def` a strict `BUCK` file loads has type annotations
+/// for all its parameters, except `*args` and `**kwargs`.
+///
+/// Parameters annotated as `typing.Any` are indistinguishable from unannotated ones.
+pub(crate) fn check_typed_macros(
+    ast: &AstModule,
+    resolver: &dyn LoadResolver,
+    loaded_modules: &LoadedModules,
+) -> anyhow::Result<()> {
+    for load in ast.loads() {
+        let path = resolver.resolve_load(load.module_id, Some(&load.span))?;
+        let module = match loaded_modules.map.get(&path.borrow()) {
+            Some(module) => module,
+            None => continue,
+        };
+        for (local, their) in &load.symbols {
+            let value = match module.env().get_option(their)? {
+                Some(value) => value,
+                None => continue,
+            };
+            let value = value.value();
+            if value.parameters_spec().is_none() {
+                // Not a `def`, e.g. a rule or a constant.
+                continue;
+            }
+            let params = match value.documentation() {
+                Some(DocItem::Function(f)) => f.params,
+                _ => continue,
+            };
+            let untyped: Vec<String> = params
+                .into_iter()
+                .filter_map(|p| match p {
+                    DocParam::Arg { name, typ, .. } if typ == Ty::any() => {
+                        Some(format!("`{}`", name))
+                    }
+                    _ => None,
+                })
+                .collect();
+            if !untyped.is_empty() {
+                return Err(spanned(
+                    &load.span,
+                    StrictBuildFileError::UntypedMacro((*local).to_owned(), untyped.join(", "))
+                        .into(),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn spanned(span: &FileSpan, e: anyhow::Error) -> anyhow::Error {
+    BuckStarlarkError::new(starlark::Error::new_spanned(
+        starlark::ErrorKind::Other(e),
+        span.span,
+        &span.file,
+    ))
+    .into()
+}
@@ -187,13 +187,18 @@ impl Tester {
     }
 
     fn interpreter(&self) -> anyhow::Result<Arc<InterpreterForCell>> {
+        let config = self
+            .configs
+            .get(self.cell_alias_resolver.resolve_self())
+            .unwrap();
         let import_paths = ImplicitImportPaths::parse(
-            self.configs
-                .get(self.cell_alias_resolver.resolve_self())
-                .unwrap(),
+            config,
             BuildFileCell::new(self.cell_alias_resolver.resolve_self()),
             &self.cell_alias_resolver,
         )?;
+        let strict_build_files = config
+            .parse("buck2", "strict_build_files")?
+            .unwrap_or(false);
         let additional_globals = self.additional_globals.clone();
         Ok(Arc::new(InterpreterForCell::new(
             self.cell_alias_resolver.dupe(),
@@ -222,6 +227,7 @@ impl Tester {
                 true,
             )?),
             Arc::new(import_paths),
+            strict_build_files,
         )?))
     }
 
@@ -16,6 +16,7 @@ mod functions;
 pub mod interpreter;
 mod rule;
 pub mod select;
+mod strict_build_files;
 mod super_package;
 mod tests;
 mod uncategorized;
@@ -0,0 +1,184 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use buck2_common::package_listing::listing::testing::PackageListingExt;
+use buck2_common::package_listing::listing::PackageListing;
+use buck2_core::bzl::ImportPath;
+use buck2_interpreter_for_build::interpreter::functions::read_config::register_read_config;
+use buck2_interpreter_for_build::interpreter::natives::register_module_natives;
+use buck2_interpreter_for_build::interpreter::testing::cells;
+use buck2_interpreter_for_build::interpreter::testing::Tester;
+use indoc::indoc;
+
+fn tester(strict: bool) -> Tester {
+    let config = if strict {
+        "[buck2]\n    strict_build_files = true\n"
+    } else {
+        ""
+    };
+    let mut tester = Tester::with_cells(cells(Some(config)).unwrap()).unwrap();
+    tester.additional_globals(register_read_config);
+    tester.additional_globals(register_module_natives);
+    tester
+        .add_import(
+            &ImportPath::testing_new("root//some/package:defs.bzl"),
+            indoc!(
+                r#"
+                def typed(name: str, *args, **kwargs):
+                    return read_config("section", name)
+                def untyped(name, count: int):
+                    pass
+                def exists(name: str):
+                    return rule_exists(name)
+                CONSTANT = [1, 2]
+                "#
+            ),
+        )
+        .unwrap();
+    tester
+}
+
+fn eval(tester: &Tester, content: &str) -> anyhow::Result<()> {
+    tester.eval_build_file(
+        &Tester::build_file_path(),
+        content,
+        PackageListing::testing_empty(),
+    )?;
+    Ok(())
+}
+
+fn eval_err(tester: &Tester, content: &str, expected: &str) {
+    let err = format!("{:?}", eval(tester, content).unwrap_err());
+    assert!(
+        err.contains(expected),
+        "Expected `{}` in:\n{}",
+        expected,
+        err
+    );
+}
+
+#[test]
+fn test_strict_build_files_disabled() {
+    let tester = tester(false);
+    eval(
+        &tester,
+        indoc!(
+            r#"
+            load(":defs.bzl", "untyped", "exists")
+            x = [read_config("section", "key")]
+            x += [rule_exists("foo"), exists("bar")]
+            x.append(1)
+            for y in [1, 2]:
+                z = y
+            untyped("foo", 1)
+            "#
+        ),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_strict_build_files_reassignment() {
+    let tester = tester(true);
+    eval_err(
+        &tester,
+        "x = 1\nx = 2\n",
+        "Global variable `x` is already defined and cannot be reassigned",
+    );
+    eval_err(
+        &tester,
+        "x = [1]\nx += [2]\n",
+        "Global variable `x` cannot be modified with augmented assignment",
+    );
+}
+
+#[test]
+fn test_strict_build_files_mutation() {
+    let tester = tester(true);
+    eval_err(
+        &tester,
+        "x = []\nx.append('a')\n",
+        "Global variable `x` cannot be mutated",
+    );
+    eval_err(
+        &tester,
+        "x = {}\nx['a'] = 1\n",
+        "Global variable `x` cannot be mutated",
+    );
+    eval_err(
+        &tester,
+        "x = []\nfor y in [1, 2]:\n    x.extend([y])\n",
+        "Global variable `x` cannot be mutated",
+    );
+    eval(&tester, "x = [1]\ny = x + [2]\nz = x.index(1)\n").unwrap();
+}
+
+#[test]
+fn test_strict_build_files_for_loop() {
+    let tester = tester(true);
+    eval_err(
+        &tester,
+        "for x in [1, 2]:\n    y = x\n",
+        "Global variable `y` cannot be assigned in a top-level `for` loop",
+    );
+    eval_err(
+        &tester,
+        "x = 'a'\nfor x in ['b', 'c']:\n    pass\n",
+        "Global variable `x` is already defined",
+    );
+    eval(
+        &tester,
+        "load(':defs.bzl', 'typed')\nfor x in ['a', 'b']:\n    typed(x)\n",
+    )
+    .unwrap();
+    // Top-level loops may reuse the same variable.
+    eval(
+        &tester,
+        "load(':defs.bzl', 'typed')\nfor x in ['a', 'b']:\n    typed(x)\nfor x in ['c']:\n    typed(x)\n",
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_strict_build_files_read_config() {
+    let tester = tester(true);
+    eval_err(
+        &tester,
+        "x = read_config('section', 'key')\n",
+        "`read_config()` cannot be called directly from a `BUCK` file",
+    );
+    eval_err(
+        &tester,
+        "x = read_root_config('section', 'key')\n",
+        "`read_root_config()` cannot be called directly from a `BUCK` file",
+    );
+    eval(&tester, "load(':defs.bzl', 'typed')\nx = typed('key')\n").unwrap();
+}
+
+#[test]
+fn test_strict_build_files_rule_exists() {
+    let tester = tester(true);
+    eval_err(
+        &tester,
+        "load(':defs.bzl', 'exists')\nx = exists('foo')\n",
+        "`rule_exists()` cannot be used when `buck2.strict_build_files` is enabled",
+    );
+}
+
+#[test]
+fn test_strict_build_files_typed_macros() {
+    let tester = tester(true);
+    eval_err(
+        &tester,
+        "load(':defs.bzl', my_macro = 'untyped')\n",
+        "Macro `my_macro` is loaded by a `BUCK` file with `buck2.strict_build_files` enabled, \
+        so all its parameters must have type annotations, but these do not: `name`",
+    );
+    eval(&tester, "load(':defs.bzl', 'typed', 'CONSTANT')\n").unwrap();
+}
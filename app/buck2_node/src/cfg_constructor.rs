@@ -15,6 +15,7 @@ use std::sync::Arc;
 use allocative::Allocative;
 use async_trait::async_trait;
 use buck2_core::configuration::data::ConfigurationData;
+use buck2_core::target::label::TargetLabel;
 use buck2_util::late_binding::LateBinding;
 use dice::DiceComputations;
 
@@ -65,4 +66,12 @@ pub trait CfgConstructorCalculationImpl: Send + Sync + 'static {
         cli_modifiers: &Arc<Vec<String>>,
         rule_name: &RuleType,
     ) -> anyhow::Result<ConfigurationData>;
+
+    /// Checks that modifiers passed on the command line are configuration targets,
+    /// and warns about modifiers overriding each other.
+    async fn validate_cli_modifiers(
+        &self,
+        ctx: &mut DiceComputations<'_>,
+        cli_modifiers: &[TargetLabel],
+    ) -> anyhow::Result<()>;
 }
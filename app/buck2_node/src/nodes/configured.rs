@@ -65,6 +65,7 @@ use crate::nodes::attributes::PACKAGE;
 use crate::nodes::attributes::PLUGINS;
 use crate::nodes::attributes::TARGET_CONFIGURATION;
 use crate::nodes::attributes::TYPE;
+use crate::nodes::configured_edge::ConfiguredEdgeKind;
 use crate::nodes::unconfigured::RuleKind;
 use crate::nodes::unconfigured::TargetNode;
 use crate::provider_id_set::ProviderIdSet;
@@ -484,6 +485,37 @@ impl ConfiguredTargetNode {
         }
     }
 
+    /// How `dep`, one of the deps of this node, got its configuration.
+    pub fn dep_edge_kind(&self, dep: &ConfiguredTargetNode) -> ConfiguredEdgeKind {
+        let target_node = match &self.0.target_node {
+            TargetNodeOrForward::TargetNode(target_node) => target_node,
+            TargetNodeOrForward::Forward(_, transitioned) => {
+                return match &transitioned.0.target_node {
+                    TargetNodeOrForward::TargetNode(n) => match &n.rule.cfg {
+                        Some(tr) => ConfiguredEdgeKind::IncomingTransition(tr.dupe()),
+                        None => ConfiguredEdgeKind::Dep,
+                    },
+                    TargetNodeOrForward::Forward(..) => ConfiguredEdgeKind::Dep,
+                };
+            }
+        };
+        if self.exec_deps().any(|d| d.label() == dep.label()) {
+            return ConfiguredEdgeKind::Exec;
+        }
+        if dep.rule_kind() == RuleKind::Toolchain {
+            return ConfiguredEdgeKind::Toolchain;
+        }
+        if dep.label().cfg() != self.label().cfg() {
+            if let Some((_, tr)) = target_node
+                .transition_deps()
+                .find(|(label, _)| *label == dep.label().unconfigured())
+            {
+                return ConfiguredEdgeKind::OutgoingTransition(tr.dupe());
+            }
+        }
+        ConfiguredEdgeKind::Dep
+    }
+
     #[inline]
     pub fn uses_plugins(&self) -> &[PluginKind] {
         self.as_ref().uses_plugins()
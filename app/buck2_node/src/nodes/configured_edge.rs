@@ -0,0 +1,47 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::sync::Arc;
+
+use buck2_core::configuration::transition::id::TransitionId;
+use derive_more::Display;
+
+/// How a dependency of a configured target node got its configuration.
+#[derive(Debug, Clone, Eq, PartialEq, Display)]
+pub enum ConfiguredEdgeKind {
+    /// Regular dependency, configured like the node depending on it.
+    #[display(fmt = "dep")]
+    Dep,
+    /// Dependency configured for the execution platform of the node depending on it.
+    #[display(fmt = "exec dep")]
+    Exec,
+    /// Toolchain dependency.
+    #[display(fmt = "toolchain dep")]
+    Toolchain,
+    /// Dependency configured by a transition (or split transition) on the attribute.
+    #[display(fmt = "outgoing transition {}", _0)]
+    OutgoingTransition(Arc<TransitionId>),
+    /// Edge from a forward node to the same target configured by the incoming transition of its
+    /// rule (`cfg` parameter of `rule()`).
+    #[display(fmt = "incoming transition {}", _0)]
+    IncomingTransition(Arc<TransitionId>),
+}
+
+impl ConfiguredEdgeKind {
+    /// The transition which changed the configuration on this edge, if any.
+    pub fn transition(&self) -> Option<&Arc<TransitionId>> {
+        match self {
+            ConfiguredEdgeKind::OutgoingTransition(tr)
+            | ConfiguredEdgeKind::IncomingTransition(tr) => Some(tr),
+            ConfiguredEdgeKind::Dep | ConfiguredEdgeKind::Exec | ConfiguredEdgeKind::Toolchain => {
+                None
+            }
+        }
+    }
+}
@@ -8,6 +8,7 @@
  */
 
 pub mod configured;
+pub mod configured_edge;
 pub mod configured_frontend;
 pub mod configured_node_ref;
 pub mod configured_node_visit_all_deps;
@@ -45,6 +46,9 @@ pub mod attributes {
     /// The resolved execution platform for this node.
     pub static EXECUTION_PLATFORM: &str = "buck.execution_platform";
 
+    /// The transitions applied on the edges from this node to its deps. Only in `cquery`.
+    pub static TRANSITIONS: &str = "buck.transitions";
+
     /// The resolved target configuration for this node.
     pub static TARGET_CONFIGURATION: &str = "buck.target_configuration";
 
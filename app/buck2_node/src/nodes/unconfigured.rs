@@ -598,6 +598,7 @@ pub mod testing {
     use crate::attrs::coerced_deps_collector::CoercedDepsCollector;
     use crate::attrs::fmt_context::AttrFmtContext;
     use crate::attrs::inspect_options::AttrInspectOptions;
+    use crate::attrs::internal::internal_attrs;
     use crate::attrs::spec::AttributeSpec;
     use crate::attrs::values::AttrValues;
     use crate::nodes::targets_map::TargetsMap;
@@ -617,9 +618,12 @@ pub mod testing {
             rule_type: RuleType,
             attrs: Vec<(&str, Attribute, CoercedAttr)>,
         ) -> TargetNode {
+            // Internal attributes (like `visibility`) may be given values, but are already in
+            // the spec.
             let attr_spec = AttributeSpec::testing_new(
                 attrs
                     .iter()
+                    .filter(|(name, _, _)| !internal_attrs().contains_key(name))
                     .map(|(name, attr, _)| ((*name).to_owned(), attr.clone()))
                     .collect(),
             );
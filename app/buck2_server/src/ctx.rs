@@ -16,6 +16,8 @@ use std::sync::Arc;
 use allocative::Allocative;
 use anyhow::Context;
 use async_trait::async_trait;
+use buck2_build_api::actions::execute::determinism::DeterminismChecker;
+use buck2_build_api::actions::execute::determinism::HasDeterminismChecker;
 use buck2_build_api::actions::execute::dice_data::set_fallback_executor_config;
 use buck2_build_api::actions::execute::dice_data::SetCommandExecutor;
 use buck2_build_api::actions::execute::dice_data::SetReClient;
@@ -418,6 +420,11 @@ impl<'a> ServerCommandContext<'a> {
                 .build_options
                 .as_ref()
                 .map_or(false, |opts| opts.materialize_failed_inputs),
+            check_determinism: self
+                .build_options
+                .as_ref()
+                .filter(|opts| opts.check_determinism)
+                .map(|opts| opts.check_determinism_categories.clone()),
         }
     }
 
@@ -525,6 +532,8 @@ struct DiceCommandDataProvider {
     paranoid: Option<ParanoidDownloader>,
     spawner: Arc<BuckSpawner>,
     materialize_failed_inputs: bool,
+    /// The categories of the actions to check the determinism of, if enabled.
+    check_determinism: Option<Vec<String>>,
 }
 
 #[async_trait]
@@ -602,6 +611,9 @@ impl DiceDataProvider for DiceCommandDataProvider {
         run_action_knobs.use_network_action_output_cache |= root_config
             .parse::<bool>("buck2", "use_network_action_output_cache")?
             .unwrap_or(false);
+        run_action_knobs.log_action_input_manifests = root_config
+            .parse::<bool>("buck2", "log_action_input_manifests")?
+            .unwrap_or(false);
 
         let mut data = UserComputationData {
             data,
@@ -648,6 +660,9 @@ impl DiceDataProvider for DiceCommandDataProvider {
         data.set_starlark_debugger_handle(self.starlark_debugger.clone().map(|v| Box::new(v) as _));
         data.set_keep_going(self.keep_going);
         data.set_critical_path_backend(critical_path_backend);
+        if let Some(categories) = &self.check_determinism {
+            data.set_determinism_checker(Arc::new(DeterminismChecker::new(categories.clone())));
+        }
         data.spawner = self.spawner.dupe();
 
         let tags = vec![
@@ -26,6 +26,7 @@ use buck2_common::io::IoProvider;
 use buck2_common::legacy_configs::cells::BuckConfigBasedCells;
 use buck2_common::legacy_configs::init::DaemonStartupConfig;
 use buck2_common::legacy_configs::init::Timeout;
+use buck2_common::legacy_configs::LegacyBuckConfig;
 use buck2_core::buck2_env;
 use buck2_core::cells::name::CellName;
 use buck2_core::facebook_only;
@@ -36,9 +37,13 @@ use buck2_core::is_open_source;
 use buck2_core::rollout_percentage::RolloutPercentage;
 use buck2_core::tag_result;
 use buck2_events::dispatch::EventDispatcher;
+use buck2_events::sink::otlp::OtlpProtocol;
+use buck2_events::sink::otlp::OtlpSink;
+use buck2_events::sink::otlp::OtlpSinkConfig;
 use buck2_events::sink::scribe;
 use buck2_events::sink::tee::TeeSink;
 use buck2_events::source::ChannelEventSource;
+use buck2_events::EventSink;
 use buck2_events::EventSinkWithStats;
 use buck2_execute::digest_config::DigestConfig;
 use buck2_execute::execute::blocking::BlockingExecutor;
@@ -135,6 +140,10 @@ pub struct DaemonStateData {
     #[allocative(skip)]
     pub scribe_sink: Option<Arc<dyn EventSinkWithStats>>,
 
+    /// Exports spans to an OpenTelemetry collector, if configured via `[buck2_otlp]`.
+    #[allocative(skip)]
+    pub otlp_sink: Option<Arc<dyn EventSinkWithStats>>,
+
     /// Whether or not to hash all commands
     pub hash_all_commands: bool,
 
@@ -467,6 +476,8 @@ impl DaemonState {
                 message_batch_size,
             )
             .context("failed to init scribe sink")?;
+            let otlp_sink =
+                Self::init_otlp_sink(root_config).context("failed to init OTLP sink")?;
 
             let enable_restarter = root_config
                 .parse::<RolloutPercentage>("buck2", "restarter")?
@@ -498,6 +509,7 @@ impl DaemonState {
                 materializer,
                 forkserver,
                 scribe_sink,
+                otlp_sink,
                 hash_all_commands,
                 use_network_action_output_cache,
                 disk_state_options,
@@ -610,8 +622,44 @@ impl DaemonState {
         .map(|maybe_scribe| maybe_scribe.map(|scribe| Arc::new(scribe) as _))
     }
 
+    fn init_otlp_sink(
+        root_config: &LegacyBuckConfig,
+    ) -> anyhow::Result<Option<Arc<dyn EventSinkWithStats>>> {
+        let Some(endpoint) = root_config.get("buck2_otlp", "endpoint") else {
+            return Ok(None);
+        };
+        let config = OtlpSinkConfig {
+            endpoint: endpoint.to_owned(),
+            protocol: root_config
+                .parse("buck2_otlp", "protocol")?
+                .unwrap_or(OtlpProtocol::Grpc),
+            batch_size: root_config
+                .parse("buck2_otlp", "batch_size")?
+                .unwrap_or(512),
+            buffer_size: root_config
+                .parse("buck2_otlp", "buffer_size")?
+                .unwrap_or(10000),
+            flush_interval: Duration::from_millis(
+                root_config
+                    .parse("buck2_otlp", "flush_interval_ms")?
+                    .unwrap_or(1000),
+            ),
+            service_name: root_config
+                .get("buck2_otlp", "service_name")
+                .unwrap_or("buck2")
+                .to_owned(),
+            resource_attributes: OtlpSinkConfig::parse_resource_attributes(
+                root_config
+                    .get("buck2_otlp", "resource_attributes")
+                    .unwrap_or(""),
+            )?,
+        };
+        Ok(Some(Arc::new(OtlpSink::new(config)?)))
+    }
+
     /// Prepares an event stream for a request by bootstrapping an event source and EventDispatcher pair. The given
-    /// EventDispatcher will log to the returned EventSource and (optionally) to Scribe if enabled via buckconfig.
+    /// EventDispatcher will log to the returned EventSource and (optionally) to Scribe and an OpenTelemetry collector
+    /// if enabled via buckconfig.
     pub async fn prepare_events(
         &self,
         trace_id: TraceId,
@@ -620,12 +668,14 @@ impl DaemonState {
         facebook_only();
         let (events, sink) = buck2_events::create_source_sink_pair();
         let data = self.data()?;
-        let dispatcher = if let Some(scribe_sink) = data.scribe_sink.dupe() {
-            EventDispatcher::new(trace_id, TeeSink::new(scribe_sink.to_event_sync(), sink))
-        } else {
-            EventDispatcher::new(trace_id, sink)
-        };
-        Ok((events, dispatcher))
+        let mut sink: Arc<dyn EventSink> = Arc::new(sink);
+        if let Some(otlp_sink) = data.otlp_sink.dupe() {
+            sink = Arc::new(TeeSink::new(otlp_sink.to_event_sync(), sink));
+        }
+        if let Some(scribe_sink) = data.scribe_sink.dupe() {
+            sink = Arc::new(TeeSink::new(scribe_sink.to_event_sync(), sink));
+        }
+        Ok((events, EventDispatcher::new(trace_id, sink)))
     }
 
     /// Prepares a ServerCommandContext for processing a complex command (that accesses the dice computation graph, for example).
@@ -20,6 +20,7 @@ use buck2_artifact::artifact::artifact_dump::ExternalSymlinkInfo;
 use buck2_artifact::artifact::artifact_dump::FileInfo;
 use buck2_artifact::artifact::artifact_dump::SymlinkInfo;
 use buck2_build_api::actions::artifact::get_artifact_fs::GetArtifactFs;
+use buck2_build_api::actions::execute::determinism::HasDeterminismChecker;
 use buck2_build_api::build;
 use buck2_build_api::build::build_report::generate_build_report;
 use buck2_build_api::build::build_report::BuildReportOpts;
@@ -334,6 +335,9 @@ async fn process_build_result(
             server_ctx.events().trace_id(),
             &build_result.configured,
             &build_result.other_errors,
+            ctx.per_transaction_data()
+                .get_determinism_checker()
+                .as_deref(),
         )?
     } else {
         None
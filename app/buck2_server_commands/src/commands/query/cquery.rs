@@ -7,6 +7,7 @@
  * of this source tree.
  */
 
+use std::collections::BTreeMap;
 use std::io::Write;
 
 use async_trait::async_trait;
@@ -17,7 +18,10 @@ use buck2_build_api::query::oneshot::QUERY_FRONTEND;
 use buck2_cli_proto::CqueryRequest;
 use buck2_cli_proto::CqueryResponse;
 use buck2_common::dice::cells::HasCellResolver;
+use buck2_core::configuration::cfg_diff::cfg_constraint_changes;
 use buck2_core::configuration::compatibility::MaybeCompatible;
+use buck2_core::configuration::constraints::ConstraintKey;
+use buck2_core::configuration::constraints::ConstraintValue;
 use buck2_core::provider::label::ConfiguredProvidersLabel;
 use buck2_core::provider::label::ProvidersName;
 use buck2_error::BuckErrorContext;
@@ -26,6 +30,7 @@ use buck2_node::attrs::display::AttrDisplayWithContextExt;
 use buck2_node::attrs::fmt_context::AttrFmtContext;
 use buck2_node::attrs::serialize::AttrSerializeWithContext;
 use buck2_node::nodes::configured::ConfiguredTargetNode;
+use buck2_node::nodes::configured_edge::ConfiguredEdgeKind;
 use buck2_query::query::environment::AttrFmtOptions;
 use buck2_query::query::syntax::simple::eval::values::QueryEvaluationResult;
 use buck2_server_ctx::ctx::ServerCommandContextTrait;
@@ -41,6 +46,7 @@ use dupe::Dupe;
 use crate::commands::query::printer::ProviderLookUp;
 use crate::commands::query::printer::QueryResultPrinter;
 use crate::commands::query::printer::ShouldPrintProviders;
+use crate::commands::query::query_target_ext::EdgeTransition;
 use crate::commands::query::query_target_ext::QueryCommandTarget;
 
 impl QueryCommandTarget for ConfiguredTargetNode {
@@ -48,6 +54,34 @@ impl QueryCommandTarget for ConfiguredTargetNode {
         ConfiguredTargetNode::call_stack(self)
     }
 
+    fn edge_transitions(&self) -> Vec<EdgeTransition> {
+        ConfiguredTargetNode::deps(self)
+            .filter_map(|dep| {
+                let (kind, transition) = match self.dep_edge_kind(dep) {
+                    ConfiguredEdgeKind::IncomingTransition(tr) => ("incoming", tr),
+                    ConfiguredEdgeKind::OutgoingTransition(tr) => ("outgoing", tr),
+                    ConfiguredEdgeKind::Dep
+                    | ConfiguredEdgeKind::Exec
+                    | ConfiguredEdgeKind::Toolchain => return None,
+                };
+                let (input, output) = cfg_constraint_changes(self.label().cfg(), dep.label().cfg());
+                let to_strings = |constraints: BTreeMap<ConstraintKey, ConstraintValue>| {
+                    constraints
+                        .into_iter()
+                        .map(|(k, v)| (k.to_string(), v.to_string()))
+                        .collect()
+                };
+                Some(EdgeTransition {
+                    dep: dep.label().to_string(),
+                    kind,
+                    transition: transition.to_string(),
+                    input: to_strings(input),
+                    output: to_strings(output),
+                })
+            })
+            .collect()
+    }
+
     fn attr_to_string_alternate(&self, options: AttrFmtOptions, attr: &Self::Attr<'_>) -> String {
         format!(
             "{:#}",
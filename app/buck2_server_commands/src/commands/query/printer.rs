@@ -20,6 +20,7 @@ use buck2_build_api::interpreter::rule_defs::provider::collection::FrozenProvide
 use buck2_cli_proto::QueryOutputFormat;
 use buck2_core::cells::CellResolver;
 use buck2_core::configuration::compatibility::MaybeCompatible;
+use buck2_node::nodes::attributes::TRANSITIONS;
 use buck2_query::query::environment::QueryTarget;
 use buck2_query::query::environment::QueryTargets;
 use buck2_query::query::syntax::simple::eval::file_set::FileSet;
@@ -166,6 +167,15 @@ impl<'a, T: QueryCommandTarget> Serialize for PrintableQueryTarget<'a, T> {
             Ok(())
         })?;
 
+        if let Some(attr_regex) = self.attributes {
+            if attr_regex.is_match(TRANSITIONS) {
+                let transitions = self.value.edge_transitions();
+                if !transitions.is_empty() {
+                    map.serialize_entry(TRANSITIONS, &transitions)?;
+                }
+            }
+        }
+
         if self.target_call_stacks {
             map.serialize_entry("buck.target_call_stack", &self.value.call_stack())?;
         }
@@ -7,16 +7,37 @@
  * of this source tree.
  */
 
+use std::collections::BTreeMap;
 use std::fmt::Formatter;
 
 use buck2_query::query::environment::AttrFmtOptions;
 use buck2_query::query::environment::QueryTarget;
 use dupe::Dupe;
 
+/// A transition which changed the configuration on the edge from a target to one of its deps,
+/// printed as the `buck.transitions` attribute.
+#[derive(Debug, serde::Serialize)]
+pub(crate) struct EdgeTransition {
+    /// The dep, as printed in the query output.
+    pub(crate) dep: String,
+    /// `incoming` (the `cfg` of the rule of the dep) or `outgoing` (on the attribute).
+    pub(crate) kind: &'static str,
+    pub(crate) transition: String,
+    /// Values of the constraint settings changed by the transition, before it was applied.
+    pub(crate) input: BTreeMap<String, String>,
+    /// Values of the constraint settings changed by the transition, after it was applied.
+    pub(crate) output: BTreeMap<String, String>,
+}
+
 /// Extensions of `QueryTarget` needed in query commands.
 pub(crate) trait QueryCommandTarget: QueryTarget {
     fn call_stack(&self) -> Option<String>;
 
+    /// Transitions applied on the edges to the deps of this target.
+    fn edge_transitions(&self) -> Vec<EdgeTransition> {
+        Vec::new()
+    }
+
     fn attr_to_string_alternate(&self, _options: AttrFmtOptions, attr: &Self::Attr<'_>) -> String;
 
     fn attr_serialize<S: serde::Serializer>(
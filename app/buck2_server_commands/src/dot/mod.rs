@@ -69,6 +69,16 @@ pub trait DotNode {
 pub struct DotEdge<'a> {
     from: &'a str,
     to: &'a str,
+    label: Option<&'a str>,
+}
+
+impl<'a> Display for DotEdge<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.label {
+            Some(label) => write!(f, " [label={}]", escape_id(label)),
+            None => Ok(()),
+        }
+    }
 }
 
 pub trait DotDigraph<'a> {
@@ -119,7 +129,13 @@ impl Dot {
             let attrs = node.attrs()?;
             writeln!(w, "  {} [{}];", escape_id(&node.id()), attrs)?;
             graph.for_each_edge(node, |edge| {
-                writeln!(w, "  {} -> {};", escape_id(edge.from), escape_id(edge.to))?;
+                writeln!(
+                    w,
+                    "  {} -> {}{};",
+                    escape_id(edge.from),
+                    escape_id(edge.to),
+                    edge
+                )?;
                 Ok(())
             })?;
             Ok(())
@@ -162,9 +178,10 @@ impl DotCompact {
             graph.for_each_edge(node, |edge| {
                 writeln!(
                     w,
-                    "  {} -> {};",
+                    "  {} -> {}{};",
                     name_to_number(&escape_id(edge.from)),
-                    name_to_number(&escape_id(edge.to))
+                    name_to_number(&escape_id(edge.to)),
+                    edge
                 )?;
                 Ok(())
             })?;
@@ -7,6 +7,7 @@
  * of this source tree.
  */
 
+use buck2_node::nodes::attributes::TRANSITIONS;
 use buck2_query::query::environment::AttrFmtOptions;
 use buck2_query::query::environment::QueryTarget;
 use buck2_query::query::environment::QueryTargets;
@@ -50,12 +51,22 @@ impl<'a, T: QueryCommandTarget> DotDigraph<'a> for DotTargetGraph<T> {
         node: &Self::Node,
         mut f: F,
     ) -> anyhow::Result<()> {
+        let transitions = match &self.attributes {
+            Some(attr_regex) if attr_regex.is_match(TRANSITIONS) => node.0.edge_transitions(),
+            _ => Vec::new(),
+        };
         for dep in node.0.deps() {
             // Only include edges to other nodes within the subgraph.
             if self.targets.contains(dep) {
+                let to = dep.to_string();
+                let label = transitions
+                    .iter()
+                    .find(|t| t.dep == to)
+                    .map(|t| format!("{} {}", t.kind, t.transition));
                 f(&DotEdge {
                     from: &node.0.node_key().to_string(),
-                    to: &dep.to_string(),
+                    to: &to,
+                    label: label.as_deref(),
                 })?;
             }
         }
@@ -7,6 +7,7 @@
  * of this source tree.
  */
 
+use anyhow::Context;
 use buck2_cli_proto::ClientContext;
 use buck2_common::dice::cells::HasCellResolver;
 use buck2_common::global_cfg_options::GlobalCfgOptions;
@@ -16,7 +17,9 @@ use buck2_core::cells::cell_path::CellPath;
 use buck2_core::cells::CellResolver;
 use buck2_core::fs::project_rel_path::ProjectRelativePath;
 use buck2_core::pattern::pattern_type::PatternType;
+use buck2_core::pattern::pattern_type::TargetPatternExtra;
 use buck2_core::pattern::ParsedPattern;
+use buck2_node::cfg_constructor::CFG_CONSTRUCTOR_CALCULATION_IMPL;
 use dice::DiceComputations;
 use gazebo::prelude::*;
 
@@ -91,8 +94,28 @@ pub async fn global_cfg_options_from_client_context(
         None
     };
 
+    let cli_modifiers = if !client_context.cli_modifiers.is_empty() {
+        // Resolve modifiers like other targets on the command line, so `[alias]` entries and
+        // relative labels work, then fail early on modifiers which can never apply. Modifiers
+        // overriding each other are only warned about: the last one wins.
+        let parser = PatternParser::new(dice_ctx, working_dir).await?;
+        let labels = client_context.cli_modifiers.try_map(|modifier| {
+            parser
+                .parse_pattern::<TargetPatternExtra>(modifier)
+                .and_then(|p| p.as_target_label(modifier))
+                .with_context(|| format!("Invalid modifier `{}`", modifier))
+        })?;
+        CFG_CONSTRUCTOR_CALCULATION_IMPL
+            .get()?
+            .validate_cli_modifiers(dice_ctx, &labels)
+            .await?;
+        labels.map(|l| l.to_string())
+    } else {
+        Vec::new()
+    };
+
     Ok(GlobalCfgOptions {
         target_platform: target_platform_label,
-        cli_modifiers: client_context.cli_modifiers.clone().into(),
+        cli_modifiers: cli_modifiers.into(),
     })
 }
@@ -70,6 +70,8 @@ fn capabilities() -> serde_json::Value {
         "supportsSetVariable": true,
         "supportsStepInTargetsRequest": true,
         "supportsConditionalBreakpoints": true,
+        "supportsHitConditionalBreakpoints": true,
+        "supportsLogPoints": true,
         // note that some capabilities have the word "support" and some "supports" this seems to be according to the spec
         "supportTerminateDebuggee": false,
         "supportSuspendDebuggee": false,
@@ -211,6 +213,11 @@ impl BuckStarlarkDebuggerServer {
         self.maybe_to_state(ServerMessage::EvalStopped { hook_id });
     }
 
+    /// Called when a starlark evaluation produces output for the user (e.g. at a logpoint).
+    pub(crate) fn event_output(&self, hook_id: HookId, output: String) {
+        self.maybe_to_state(ServerMessage::EvalOutput { hook_id, output });
+    }
+
     /// Called to forward along requests from the DAP client.
     pub(crate) fn send_request(&self, req: dap::Request) -> anyhow::Result<()> {
         // If the state encountered an error or is shutting down, it may never see this
@@ -250,6 +257,10 @@ enum ServerMessage {
     EvalStopped {
         hook_id: HookId,
     },
+    EvalOutput {
+        hook_id: HookId,
+        output: String,
+    },
     Detach,
 }
 
@@ -741,6 +752,7 @@ impl ServerState {
                 self.to_client.send(ToClientMessage::Response(response))?;
             }
             ServerMessage::EvalStopped { hook_id } => self.eval_stopped(hook_id)?,
+            ServerMessage::EvalOutput { hook_id, output } => self.eval_output(hook_id, output)?,
             ServerMessage::Detach => {
                 self.detach();
                 return Ok(false);
@@ -848,6 +860,31 @@ impl ServerState {
         Ok(())
     }
 
+    fn eval_output(&self, hook_id: HookId, output: String) -> anyhow::Result<()> {
+        // Logpoint output goes both to the buck2 console of the command being debugged
+        // and to the DAP client's debug console.
+        if let Some(command) = self
+            .current_hooks
+            .get(&hook_id)
+            .and_then(|hook| self.current_commands.get(&hook.handle_id))
+        {
+            command.events.console_message(output.clone());
+        }
+
+        let msg = dap::OutputEventBody {
+            category: Some("console".to_owned()),
+            output: format!("{}\n", output),
+            variables_reference: None,
+            source: None,
+            line: None,
+            column: None,
+            data: None,
+        };
+        self.to_client
+            .send(ToClientMessage::Event(dap_event("output", Some(&msg))))?;
+        Ok(())
+    }
+
     fn detach(&mut self) {
         // Dropping the DapAdapter should make any hooked Evaluator continue freely.
         self.current_hooks.clear();
@@ -899,6 +936,13 @@ impl DapAdapterClient for BuckStarlarkDapAdapterClient {
     fn event_stopped(&self) {
         self.handle.0.server.event_stopped(self.hook_id)
     }
+
+    fn event_output(&self, output: &str) {
+        self.handle
+            .0
+            .server
+            .event_output(self.hook_id, output.to_owned())
+    }
 }
 
 /// Information about ongoing commands held by the debugger server.
@@ -0,0 +1,258 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Lints which know what buck2 functions do, reported next to the generic Starlark lints.
+
+use buck2_common::package_listing::listing::PackageListing;
+use buck2_interpreter_for_build::interpreter::globspec::glob_matches_any;
+use starlark::analysis::calls::AstModuleCalls;
+use starlark::analysis::calls::Call;
+use starlark::analysis::calls::CallArgValue;
+use starlark::codemap::Span;
+use starlark::errors::EvalSeverity;
+use starlark::errors::Lint;
+use starlark::syntax::AstModule;
+
+use crate::lint::config::LintConfig;
+
+/// Lints only reported when listed in `starlark_lint.enabled`.
+pub(crate) const OFF_BY_DEFAULT: &[&str] = &["missing-visibility"];
+
+fn lint(ast: &AstModule, span: Span, short_name: &str, problem: String) -> Lint {
+    let location = ast.file_span(span);
+    Lint {
+        original: location.source_span().to_owned(),
+        location,
+        short_name: short_name.to_owned(),
+        severity: EvalSeverity::Warning,
+        problem,
+    }
+}
+
+/// The rule a call refers to, `native.cxx_library` is the same as `cxx_library`.
+fn rule_name(call: &Call) -> &str {
+    call.function
+        .strip_prefix("native.")
+        .unwrap_or(&call.function)
+}
+
+fn deprecated_attrs(ast: &AstModule, config: &LintConfig, call: &Call, res: &mut Vec<Lint>) {
+    let rule = rule_name(call);
+    for arg in &call.args {
+        if let Some(name) = &arg.name {
+            if config.is_deprecated_attr(rule, name) {
+                res.push(lint(
+                    ast,
+                    arg.span,
+                    "deprecated-attr",
+                    format!("Attribute `{name}` of `{rule}` is deprecated"),
+                ));
+            }
+        }
+    }
+}
+
+/// A target declaration in a `BUCK` file without `visibility` is only visible to its package,
+/// which is usually not intended.
+fn missing_visibility(ast: &AstModule, call: &Call, res: &mut Vec<Lint>) {
+    if call.top_level
+        && !call.has_star_args
+        && call.named_arg("name").is_some()
+        && call.named_arg("visibility").is_none()
+    {
+        res.push(lint(
+            ast,
+            call.span,
+            "missing-visibility",
+            format!(
+                "Target declared by `{}` has no `visibility`",
+                rule_name(call)
+            ),
+        ));
+    }
+}
+
+/// Globs with literal patterns which match no files in the package.
+/// Invalid patterns are ignored, evaluating the file reports them.
+fn glob_matches_nothing(
+    ast: &AstModule,
+    listing: &PackageListing,
+    call: &Call,
+    res: &mut Vec<Lint>,
+) {
+    if rule_name(call) != "glob" || call.has_star_args {
+        return;
+    }
+    let include = match call
+        .args
+        .iter()
+        .find(|a| a.name.is_none() || a.name.as_deref() == Some("include"))
+    {
+        Some(arg) => match &arg.value {
+            CallArgValue::StringList(xs) => xs,
+            _ => return,
+        },
+        None => return,
+    };
+    let exclude = match call.named_arg("exclude").map(|a| &a.value) {
+        Some(CallArgValue::StringList(xs)) => xs.as_slice(),
+        Some(_) => return,
+        None => &[],
+    };
+    if let Ok(false) = glob_matches_any(include, exclude, listing.files()) {
+        res.push(lint(
+            ast,
+            call.span,
+            "glob-matches-nothing",
+            "Glob does not match any files in the package".to_owned(),
+        ));
+    }
+}
+
+/// Lint the calls in a file. `listing` is the listing of the package for `BUCK` files,
+/// and `None` for other files.
+pub(crate) fn lint_calls(
+    ast: &AstModule,
+    config: &LintConfig,
+    listing: Option<&PackageListing>,
+) -> Vec<Lint> {
+    let mut res = Vec::new();
+    for call in ast.calls() {
+        deprecated_attrs(ast, config, &call, &mut res);
+        if let Some(listing) = listing {
+            missing_visibility(ast, &call, &mut res);
+            glob_matches_nothing(ast, listing, &call, &mut res);
+        }
+    }
+    res.retain(|x| !ast.is_suppressed(&x.short_name, x.location.span));
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use buck2_common::package_listing::listing::testing::PackageListingExt;
+    use starlark::syntax::Dialect;
+
+    use super::*;
+    use crate::lint::config::tests::config;
+
+    /// Lint `src` like `buck2 starlark lint` does, returning `(short_name, line)` pairs.
+    fn lints(
+        src: &str,
+        config: &LintConfig,
+        listing: Option<&PackageListing>,
+    ) -> Vec<(String, usize)> {
+        let ast = AstModule::parse("BUCK", src.to_owned(), &Dialect::Extended).unwrap();
+        lint_calls(&ast, config, listing)
+            .into_iter()
+            .filter(|x| config.is_enabled(&x.short_name))
+            .map(|x| (x.short_name, x.location.resolve_span().begin.line + 1))
+            .collect()
+    }
+
+    fn lint(short_name: &str, line: usize) -> (String, usize) {
+        (short_name.to_owned(), line)
+    }
+
+    #[test]
+    fn test_deprecated_attr() {
+        let src = r#"
+cxx_library(name = "a", licenses = ["LICENSE"], visibility = ["PUBLIC"])
+native.java_library(name = "b", licenses = ["LICENSE"], visibility = ["PUBLIC"])
+cxx_library(name = "c", srcs = [], visibility = ["PUBLIC"])
+"#;
+        assert_eq!(
+            vec![lint("deprecated-attr", 2), lint("deprecated-attr", 3)],
+            lints(src, &config(&[], &[], &["*.licenses"]), None)
+        );
+        assert_eq!(
+            vec![lint("deprecated-attr", 2)],
+            lints(src, &config(&[], &[], &["cxx_library.licenses"]), None)
+        );
+        assert_eq!(
+            Vec::<(String, usize)>::new(),
+            lints(
+                src,
+                &config(&["deprecated-attr"], &[], &["*.licenses"]),
+                None
+            )
+        );
+    }
+
+    #[test]
+    fn test_missing_visibility() {
+        let src = r#"
+cxx_library(name = "a")
+cxx_library(name = "b", visibility = ["PUBLIC"])
+cxx_library(**{"name": "c"})
+def f():
+    cxx_library(name = "d")
+"#;
+        let listing = PackageListing::testing_empty();
+        assert_eq!(
+            Vec::<(String, usize)>::new(),
+            lints(src, &config(&[], &[], &[]), Some(&listing))
+        );
+        assert_eq!(
+            vec![lint("missing-visibility", 2)],
+            lints(
+                src,
+                &config(&[], &["missing-visibility"], &[]),
+                Some(&listing)
+            )
+        );
+        // Only `BUCK` files declare targets.
+        assert_eq!(
+            Vec::<(String, usize)>::new(),
+            lints(src, &config(&[], &["missing-visibility"], &[]), None)
+        );
+    }
+
+    #[test]
+    fn test_glob_matches_nothing() {
+        let src = r#"
+glob(["*.cpp"])
+glob(["*.h"])
+glob(include = ["src/**/*.java"], exclude = ["src/Main.java"])
+glob(["*.cpp"], exclude = ["a.cpp"])
+glob(["../*.cpp"])
+glob(PATTERNS)
+"#;
+        let listing = PackageListing::testing_files(&["a.cpp", "src/Main.java"]);
+        assert_eq!(
+            vec![
+                lint("glob-matches-nothing", 3),
+                lint("glob-matches-nothing", 4),
+                lint("glob-matches-nothing", 5),
+            ],
+            lints(src, &config(&[], &[], &[]), Some(&listing))
+        );
+        assert_eq!(
+            Vec::<(String, usize)>::new(),
+            lints(
+                src,
+                &config(&["glob-matches-nothing"], &[], &[]),
+                Some(&listing)
+            )
+        );
+    }
+
+    #[test]
+    fn test_suppressed() {
+        let src = r#"
+glob(["*.h"])  # starlark-lint-disable glob-matches-nothing
+glob(["*.hpp"])
+"#;
+        let listing = PackageListing::testing_empty();
+        assert_eq!(
+            vec![lint("glob-matches-nothing", 3)],
+            lints(src, &config(&[], &[], &[]), Some(&listing))
+        );
+    }
+}
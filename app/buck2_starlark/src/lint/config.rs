@@ -0,0 +1,144 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::collections::HashSet;
+
+use buck2_common::legacy_configs::dice::HasLegacyConfigs;
+use buck2_core::cells::name::CellName;
+use dice::DiceTransaction;
+
+use crate::lint::buck::OFF_BY_DEFAULT;
+
+const SECTION: &str = "starlark_lint";
+
+#[derive(Debug, buck2_error::Error)]
+enum LintConfigError {
+    #[error(
+        "Invalid `starlark_lint.deprecated_attrs` entry `{0}`, expecting `rule.attr` or `*.attr`"
+    )]
+    InvalidDeprecatedAttr(String),
+}
+
+/// Configuration of `buck2 starlark lint` for the files in a cell,
+/// from the `[starlark_lint]` buckconfig section of that cell.
+#[derive(Debug)]
+pub(crate) struct LintConfig {
+    /// Lints which are never reported.
+    disabled: HashSet<String>,
+    /// Lints which are off by default, but reported in this cell.
+    enabled: HashSet<String>,
+    /// Rule attributes which should no longer be used, as `(rule, attr)` where `rule` may be `*`.
+    deprecated_attrs: Vec<(String, String)>,
+}
+
+impl LintConfig {
+    pub(crate) async fn new(dice: &DiceTransaction, cell: CellName) -> anyhow::Result<LintConfig> {
+        let config = dice.clone().get_legacy_config_for_cell(cell).await?;
+        let list = |key: &str| -> anyhow::Result<Vec<String>> {
+            Ok(config
+                .parse_list::<String>(SECTION, key)?
+                .unwrap_or_default()
+                .into_iter()
+                .map(|x| x.trim().to_owned())
+                .filter(|x| !x.is_empty())
+                .collect())
+        };
+        LintConfig::from_lists(
+            list("disabled")?,
+            list("enabled")?,
+            list("deprecated_attrs")?,
+        )
+    }
+
+    fn from_lists(
+        disabled: Vec<String>,
+        enabled: Vec<String>,
+        deprecated_attrs: Vec<String>,
+    ) -> anyhow::Result<LintConfig> {
+        let deprecated_attrs = deprecated_attrs
+            .into_iter()
+            .map(|x| match x.rsplit_once('.') {
+                Some((rule, attr)) if !rule.is_empty() && !attr.is_empty() => {
+                    Ok((rule.to_owned(), attr.to_owned()))
+                }
+                _ => Err(LintConfigError::InvalidDeprecatedAttr(x).into()),
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(LintConfig {
+            disabled: disabled.into_iter().collect(),
+            enabled: enabled.into_iter().collect(),
+            deprecated_attrs,
+        })
+    }
+
+    pub(crate) fn is_enabled(&self, short_name: &str) -> bool {
+        !self.disabled.contains(short_name)
+            && (!OFF_BY_DEFAULT.contains(&short_name) || self.enabled.contains(short_name))
+    }
+
+    pub(crate) fn is_deprecated_attr(&self, rule: &str, attr: &str) -> bool {
+        self.deprecated_attrs
+            .iter()
+            .any(|(r, a)| a == attr && (r == "*" || r == rule))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn config(
+        disabled: &[&str],
+        enabled: &[&str],
+        deprecated_attrs: &[&str],
+    ) -> LintConfig {
+        let list = |xs: &[&str]| xs.iter().map(|x| (*x).to_owned()).collect();
+        LintConfig::from_lists(list(disabled), list(enabled), list(deprecated_attrs)).unwrap()
+    }
+
+    #[test]
+    fn test_is_enabled() {
+        let default = config(&[], &[], &[]);
+        assert!(default.is_enabled("deprecated-attr"));
+        assert!(default.is_enabled("glob-matches-nothing"));
+        assert!(!default.is_enabled("missing-visibility"));
+
+        let configured = config(&["glob-matches-nothing"], &["missing-visibility"], &[]);
+        assert!(configured.is_enabled("deprecated-attr"));
+        assert!(!configured.is_enabled("glob-matches-nothing"));
+        assert!(configured.is_enabled("missing-visibility"));
+
+        // Disabling wins over enabling.
+        let both = config(&["missing-visibility"], &["missing-visibility"], &[]);
+        assert!(!both.is_enabled("missing-visibility"));
+    }
+
+    #[test]
+    fn test_deprecated_attrs() {
+        let config = config(
+            &[],
+            &[],
+            &["*.licenses", "cxx_library.exported_lang_preprocessor_flags"],
+        );
+        assert!(config.is_deprecated_attr("cxx_library", "licenses"));
+        assert!(config.is_deprecated_attr("java_library", "licenses"));
+        assert!(config.is_deprecated_attr("cxx_library", "exported_lang_preprocessor_flags"));
+        assert!(!config.is_deprecated_attr("cxx_binary", "exported_lang_preprocessor_flags"));
+        assert!(!config.is_deprecated_attr("cxx_library", "srcs"));
+    }
+
+    #[test]
+    fn test_invalid_deprecated_attr() {
+        for entry in ["licenses", "cxx_library.", ".licenses"] {
+            let err =
+                LintConfig::from_lists(Vec::new(), Vec::new(), vec![entry.to_owned()]).unwrap_err();
+            assert!(err.to_string().contains(entry), "{err}");
+        }
+    }
+}
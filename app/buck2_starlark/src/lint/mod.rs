@@ -20,6 +20,7 @@ use buck2_common::dice::cells::HasCellResolver;
 use buck2_common::dice::data::HasIoProvider;
 use buck2_common::dice::file_ops::DiceFileOps;
 use buck2_common::io::IoProvider;
+use buck2_common::package_listing::dice::DicePackageListingResolver;
 use buck2_core::cells::name::CellName;
 use buck2_core::cells::CellResolver;
 use buck2_interpreter::file_type::StarlarkFileType;
@@ -31,30 +32,66 @@ use dice::DiceTransaction;
 use dupe::Dupe;
 use dupe::OptionDupedExt;
 use starlark::analysis::AstModuleLint;
+use starlark::analysis::LintMessage;
 use starlark::codemap::FileSpan;
 use starlark::errors::EvalSeverity;
 use starlark::errors::Lint;
 use starlark::syntax::AstModule;
 
+use crate::lint::buck::lint_calls;
+use crate::lint::config::LintConfig;
+use crate::lint::sarif::sarif_log;
 use crate::util::environment::Environment;
 use crate::util::paths::starlark_files;
 use crate::StarlarkCommandCommonOptions;
 use crate::StarlarkOpaqueSubcommand;
 
+mod buck;
+mod config;
+mod sarif;
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Dupe,
+    PartialEq,
+    Eq,
+    clap::ArgEnum,
+    serde::Serialize,
+    serde::Deserialize
+)]
+#[clap(rename_all = "lower")]
+enum LintFormat {
+    /// One lint per line, as `path:line:column: problem`.
+    Text,
+    /// One JSON object per line.
+    Json,
+    /// A single SARIF 2.1.0 log, for code review tools.
+    Sarif,
+}
+
 #[derive(Debug, clap::Parser, serde::Serialize, serde::Deserialize)]
 #[clap(name = "starlark-lint", about = "Run the Starlark linter.")]
 pub struct StarlarkLintCommand {
     #[clap(flatten)]
     common_opts: StarlarkCommandCommonOptions,
 
+    /// How to print the lints.
+    #[clap(long, arg_enum, default_value = "text", ignore_case = true)]
+    format: LintFormat,
+
+    /// Files or directories to lint, e.g. `//...` for the whole cell.
     #[clap(value_name = "PATH", required = true)]
     paths: Vec<PathArg>,
 }
 
-/// The cache of names for a path, keyed by its CellName and its path type.
+/// The cache of names for a path, keyed by its CellName and its path type,
+/// and of the lint configuration of each cell.
 struct Cache<'a> {
     dice: &'a DiceTransaction,
     cached: HashMap<(CellName, StarlarkFileType), Arc<HashSet<String>>>,
+    configs: HashMap<CellName, Arc<LintConfig>>,
 }
 
 impl<'a> Cache<'a> {
@@ -62,9 +99,19 @@ impl<'a> Cache<'a> {
         Self {
             dice,
             cached: HashMap::new(),
+            configs: HashMap::new(),
         }
     }
 
+    pub(crate) async fn get_config(&mut self, cell: CellName) -> anyhow::Result<Arc<LintConfig>> {
+        if let Some(res) = self.configs.get(&cell) {
+            return Ok(res.dupe());
+        }
+        let res = Arc::new(LintConfig::new(self.dice, cell).await?);
+        self.configs.insert(cell, res.dupe());
+        Ok(res)
+    }
+
     pub(crate) async fn get_names(
         &mut self,
         path: &StarlarkPath<'_>,
@@ -95,7 +142,21 @@ async fn lint_file(
         .await?
         .with_context(|| format!("File not found: `{}`", path_str))?;
     match AstModule::parse(&path_str, content.clone(), &dialect) {
-        Ok(ast) => Ok(ast.lint(Some(&*cache.get_names(path).await?))),
+        Ok(ast) => {
+            let config = cache.get_config(path.cell()).await?;
+            let listing = match path {
+                StarlarkPath::BuildFile(path) => Some(
+                    DicePackageListingResolver(&mut cache.dice.clone())
+                        .resolve_package_listing(path.package())
+                        .await?,
+                ),
+                _ => None,
+            };
+            let mut lints = ast.lint(Some(&*cache.get_names(path).await?));
+            lints.extend(lint_calls(&ast, &config, listing.as_ref()));
+            lints.retain(|x| config.is_enabled(&x.short_name));
+            Ok(lints)
+        }
         Err(err) => {
             // There was a parse error, so we don't want to fail, we want to give a nice error message
             // Do the best we can - it is probably a `Diagnostic`, which gives us more precise info.
@@ -141,14 +202,37 @@ impl StarlarkOpaqueSubcommand for StarlarkLintCommand {
                     })
                     .await?;
                 let mut cache = Cache::new(&ctx);
+                // SARIF is a single document, so lints are written at the end.
+                let mut sarif_lints = Vec::new();
 
                 for file in &files {
                     let lints = lint_file(&file.borrow(), cell_resolver, &**io, &mut cache).await?;
                     lint_count += lints.len();
-                    for lint in lints {
-                        writeln!(stdout, "{}", lint)?;
+                    match self.format {
+                        LintFormat::Text => {
+                            for lint in lints {
+                                writeln!(stdout, "{}", lint)?;
+                            }
+                        }
+                        LintFormat::Json => {
+                            for lint in lints {
+                                writeln!(
+                                    stdout,
+                                    "{}",
+                                    serde_json::to_string(&LintMessage::new(lint.into()))?
+                                )?;
+                            }
+                        }
+                        LintFormat::Sarif => sarif_lints.extend(lints),
                     }
                 }
+                if self.format == LintFormat::Sarif {
+                    writeln!(
+                        stdout,
+                        "{}",
+                        serde_json::to_string_pretty(&sarif_log(&sarif_lints))?
+                    )?;
+                }
                 if lint_count > 0 {
                     Err(anyhow::anyhow!("Found {} lints", lint_count))
                 } else {
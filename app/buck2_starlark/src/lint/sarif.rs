@@ -0,0 +1,147 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Lints as a [SARIF](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html) log,
+//! which code review tools use to annotate diffs.
+
+use std::collections::HashSet;
+
+use serde_json::json;
+use starlark::errors::EvalSeverity;
+use starlark::errors::Lint;
+
+fn level(severity: EvalSeverity) -> &'static str {
+    match severity {
+        EvalSeverity::Error => "error",
+        EvalSeverity::Warning => "warning",
+        EvalSeverity::Advice => "note",
+        EvalSeverity::Disabled => "none",
+    }
+}
+
+/// A SARIF 2.1.0 log with a single run containing `lints`.
+/// Paths are relative to the project root.
+pub(crate) fn sarif_log(lints: &[Lint]) -> serde_json::Value {
+    let mut seen = HashSet::new();
+    let rules: Vec<_> = lints
+        .iter()
+        .filter(|x| seen.insert(x.short_name.as_str()))
+        .map(|x| json!({ "id": x.short_name }))
+        .collect();
+    let results: Vec<_> = lints
+        .iter()
+        .map(|x| {
+            let span = x.location.resolve_span();
+            json!({
+                "ruleId": x.short_name,
+                "level": level(x.severity),
+                "message": { "text": x.problem },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": x.location.filename() },
+                        "region": {
+                            "startLine": span.begin.line + 1,
+                            "startColumn": span.begin.column + 1,
+                            "endLine": span.end.line + 1,
+                            "endColumn": span.end.column + 1,
+                        },
+                    },
+                }],
+            })
+        })
+        .collect();
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "buck2 starlark lint",
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use dupe::Dupe;
+    use starlark::codemap::CodeMap;
+    use starlark::codemap::FileSpan;
+    use starlark::codemap::Pos;
+    use starlark::codemap::Span;
+
+    use super::*;
+
+    fn lint(codemap: &CodeMap, begin: u32, end: u32, short_name: &str) -> Lint {
+        let location = FileSpan {
+            file: codemap.dupe(),
+            span: Span::new(Pos::new(begin), Pos::new(end)),
+        };
+        Lint {
+            original: location.source_span().to_owned(),
+            location,
+            short_name: short_name.to_owned(),
+            severity: EvalSeverity::Warning,
+            problem: format!("Problem {short_name}"),
+        }
+    }
+
+    #[test]
+    fn test_sarif_log() {
+        let codemap = CodeMap::new(
+            "foo/BUCK".to_owned(),
+            "glob([\"*.h\"])\nx = 1\n  glob([])\n".to_owned(),
+        );
+        let log = sarif_log(&[
+            lint(&codemap, 0, 13, "glob-matches-nothing"),
+            lint(&codemap, 14, 15, "unused-assign"),
+            lint(&codemap, 22, 30, "glob-matches-nothing"),
+        ]);
+        assert_eq!(
+            "https://json.schemastore.org/sarif-2.1.0.json",
+            log["$schema"]
+        );
+        assert_eq!("2.1.0", log["version"]);
+        let run = &log["runs"][0];
+        assert_eq!("buck2 starlark lint", run["tool"]["driver"]["name"]);
+        assert_eq!(
+            json!([{ "id": "glob-matches-nothing" }, { "id": "unused-assign" }]),
+            run["tool"]["driver"]["rules"]
+        );
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(3, results.len());
+        assert_eq!(
+            json!({
+                "ruleId": "glob-matches-nothing",
+                "level": "warning",
+                "message": { "text": "Problem glob-matches-nothing" },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": "foo/BUCK" },
+                        "region": {
+                            "startLine": 3,
+                            "startColumn": 3,
+                            "endLine": 3,
+                            "endColumn": 11,
+                        },
+                    },
+                }],
+            }),
+            results[2]
+        );
+        assert_eq!("unused-assign", results[1]["ruleId"]);
+        assert_eq!(
+            json!({ "startLine": 2, "startColumn": 1, "endLine": 2, "endColumn": 2 }),
+            results[1]["locations"][0]["physicalLocation"]["region"]
+        );
+    }
+}
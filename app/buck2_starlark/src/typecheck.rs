@@ -9,6 +9,7 @@
 
 use std::collections::HashMap;
 use std::io::Write;
+use std::path::Path;
 
 use anyhow::Context;
 use async_recursion::async_recursion;
@@ -31,9 +32,13 @@ use buck2_server_ctx::ctx::ServerCommandDiceContext;
 use buck2_server_ctx::partial_result_dispatcher::PartialResultDispatcher;
 use dice::DiceTransaction;
 use dupe::Dupe;
+use starlark::analysis::EvalMessage;
+use starlark::analysis::LintMessage;
 use starlark::environment::Globals;
+use starlark::typing::Approximation;
 use starlark::typing::AstModuleTypecheck;
 use starlark::typing::Interface;
+use starlark::typing::TypeMap;
 
 use crate::util::environment::Environment;
 use crate::util::paths::starlark_files;
@@ -46,26 +51,85 @@ pub struct StarlarkTypecheckCommand {
     #[clap(flatten)]
     common_opts: StarlarkCommandCommonOptions,
 
+    /// Print errors as JSON, one object per line.
+    #[clap(long)]
+    json: bool,
+
+    /// Print the inferred types of bindings and the approximations made by the typechecker.
+    #[clap(long)]
+    show_bindings: bool,
+
+    /// Files or directories to typecheck, e.g. `//...` for the whole cell.
     #[clap(value_name = "PATH", required = true)]
     paths: Vec<PathArg>,
 }
 
+/// Print a type error, as a [`LintMessage`] line with `--json`, otherwise as `full`.
+fn write_error(
+    out: &mut (dyn Write + Send + Sync),
+    json: bool,
+    message: EvalMessage,
+    full: &str,
+) -> anyhow::Result<()> {
+    if json {
+        writeln!(
+            out,
+            "{}",
+            serde_json::to_string(&LintMessage::new(message))?
+        )?;
+    } else {
+        writeln!(out, "{full}")?;
+    }
+    Ok(())
+}
+
+/// Print the output of `--show-bindings` for a file.
+fn write_bindings(
+    out: &mut (dyn Write + Send + Sync),
+    bindings: &TypeMap,
+    approximations: &[Approximation],
+) -> anyhow::Result<()> {
+    if !approximations.is_empty() {
+        writeln!(out, "\n\nAPPROXIMATIONS:")?;
+        for x in approximations {
+            writeln!(out, "{x}")?;
+        }
+    }
+    writeln!(out, "\n\nBINDINGS:\n{bindings}")?;
+    Ok(())
+}
+
 struct Cache<'a> {
     // Things we have access to get information
     dice: &'a DiceTransaction,
     io: &'a dyn IoProvider,
     cell_resolver: &'a CellResolver,
+    json: bool,
+    show_bindings: bool,
     // Things we have access to write information
     stdout: &'a mut (dyn Write + Send + Sync),
     stderr: &'a mut (dyn Write + Send + Sync),
     // Our accumulated state
     oracle: HashMap<(CellName, StarlarkFileType), Globals>,
     cache: HashMap<OwnedStarlarkModulePath, Interface>,
+    file_count: usize,
+    error_count: usize,
 }
 
 impl<'a> Cache<'a> {
     async fn typecheck(&mut self, path: OwnedStarlarkPath) -> anyhow::Result<()> {
-        self.run(path).await?;
+        // Files which are also loaded by other files are only checked once.
+        match path {
+            OwnedStarlarkPath::LoadFile(x) => {
+                self.get(OwnedStarlarkModulePath::LoadFile(x)).await?;
+            }
+            OwnedStarlarkPath::BxlFile(x) => {
+                self.get(OwnedStarlarkModulePath::BxlFile(x)).await?;
+            }
+            path => {
+                self.run(path).await?;
+            }
+        }
         Ok(())
     }
 
@@ -97,10 +161,19 @@ impl<'a> Cache<'a> {
         }
     }
 
+    fn report(&mut self, message: EvalMessage, full: String) -> anyhow::Result<()> {
+        self.error_count += 1;
+        write_error(self.stdout, self.json, message, &full)
+    }
+
+    /// Typecheck a file, reporting all the errors found.
+    /// When the file cannot be checked at all, an empty interface is returned,
+    /// so the files loading it are still checked.
     #[async_recursion]
     async fn run(&mut self, path: OwnedStarlarkPath) -> anyhow::Result<Interface> {
         let path_ref = path.borrow();
         writeln!(self.stderr, "Type checking: {path_ref}")?;
+        self.file_count += 1;
         let proj_path = self
             .cell_resolver
             .resolve_path(path_ref.path().as_ref().as_ref())?;
@@ -116,11 +189,29 @@ impl<'a> Cache<'a> {
             .get_interpreter_calculator(path_ref.cell(), path_ref.build_file_cell())
             .await?;
 
-        let ParseData(ast, _) = interp.prepare_eval_with_content(path_ref, src)??;
+        let ast = match interp.prepare_eval_with_content(path_ref, src)? {
+            Ok(ParseData(ast, _)) => ast,
+            Err(e) => {
+                let e = anyhow::Error::from(e);
+                self.report(
+                    EvalMessage::from_any_error(Path::new(&path_str), &e),
+                    format!("{e:#}"),
+                )?;
+                return Ok(Interface::default());
+            }
+        };
         let mut loads = HashMap::new();
         for x in ast.loads() {
-            let y = interp.resolve_load(path_ref, x.module_id).await?;
-            let interface = self.get(y).await?;
+            let interface = match interp.resolve_load(path_ref, x.module_id).await {
+                Ok(y) => self.get(y).await?,
+                Err(e) => {
+                    self.report(
+                        EvalMessage::from_any_error(Path::new(&path_str), &e),
+                        format!("{e:#}"),
+                    )?;
+                    Interface::default()
+                }
+            };
             loads.insert(x.module_id.to_owned(), interface);
         }
         let globals = self
@@ -128,25 +219,17 @@ impl<'a> Cache<'a> {
             .await?;
         let (errors, bindings, interface, approxiomations) = ast.typecheck(&globals, &loads);
 
-        if !approxiomations.is_empty() {
-            writeln!(self.stderr, "\n\nAPPROXIMATIONS:")?;
-            for x in approxiomations {
-                writeln!(self.stderr, "{x}")?;
-            }
+        if self.show_bindings {
+            write_bindings(self.stderr, &bindings, &approxiomations)?;
         }
 
-        writeln!(self.stderr, "\n\nBINDINGS:\n{bindings}")?;
-
-        let errors_count = errors.len();
-        if errors_count == 0 {
-            Ok(interface)
-        } else {
-            writeln!(self.stdout, "\n\nERRORS:")?;
-            for x in errors {
-                writeln!(self.stdout, "{x}")?;
-            }
-            Err(anyhow::anyhow!("Detected {errors_count} errors"))
+        for x in errors {
+            self.report(
+                EvalMessage::from_error(Path::new(&path_str), &x),
+                x.to_string(),
+            )?;
         }
+        Ok(interface)
     }
 }
 
@@ -181,17 +264,31 @@ impl StarlarkOpaqueSubcommand for StarlarkTypecheckCommand {
                     dice: &dice,
                     io: &**io,
                     cell_resolver,
+                    json: self.json,
+                    show_bindings: self.show_bindings,
                     stdout: &mut stdout,
                     stderr: &mut stderr,
                     oracle: HashMap::new(),
                     cache: HashMap::new(),
+                    file_count: 0,
+                    error_count: 0,
                 };
                 for file in files {
                     cache.typecheck(file).await?;
                 }
-                let file_count = cache.cache.len();
-                writeln!(stderr, "Found no type errors in {file_count} files")?;
-                Ok(())
+                let Cache {
+                    file_count,
+                    error_count,
+                    ..
+                } = cache;
+                if error_count > 0 {
+                    Err(anyhow::anyhow!(
+                        "Detected {error_count} type errors in {file_count} files"
+                    ))
+                } else {
+                    writeln!(stderr, "Found no type errors in {file_count} files")?;
+                    Ok(())
+                }
             })
             .await
     }
@@ -200,3 +297,97 @@ impl StarlarkOpaqueSubcommand for StarlarkTypecheckCommand {
         &self.common_opts
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use starlark::syntax::AstModule;
+    use starlark::syntax::Dialect;
+
+    use super::*;
+
+    const PATH: &str = "foo/defs.bzl";
+
+    fn typecheck(src: &str) -> (Vec<starlark::Error>, TypeMap, Vec<Approximation>) {
+        let ast = AstModule::parse(PATH, src.to_owned(), &Dialect::Extended).unwrap();
+        let (errors, bindings, _, approximations) =
+            ast.typecheck(&Globals::standard(), &HashMap::new());
+        (errors, bindings, approximations)
+    }
+
+    fn errors(src: &str, json: bool) -> String {
+        let mut out = Vec::new();
+        for x in typecheck(src).0 {
+            write_error(
+                &mut out,
+                json,
+                EvalMessage::from_error(Path::new(PATH), &x),
+                &x.to_string(),
+            )
+            .unwrap();
+        }
+        String::from_utf8(out).unwrap()
+    }
+
+    const SRC: &str = r#"
+def f(x: int) -> int:
+    return x
+
+def g() -> str:
+    return f("a")
+"#;
+
+    #[test]
+    fn test_json() {
+        let out = errors(SRC, true);
+        let lines: Vec<serde_json::Value> = out
+            .lines()
+            .map(|x| serde_json::from_str(x).unwrap())
+            .collect();
+        assert_eq!(
+            vec![serde_json::json!({
+                "path": "foo/defs.bzl",
+                "line": 6,
+                "char": 14,
+                "code": "STARLARK",
+                "severity": "error",
+                "name": "error",
+                "description": "Expected type `int` but got `str`",
+                "original": "\"a\"",
+            })],
+            lines
+        );
+    }
+
+    #[test]
+    fn test_text() {
+        let out = errors(SRC, false);
+        assert!(
+            out.starts_with("error: Expected type `int` but got `str`\n --> foo/defs.bzl:6:14\n"),
+            "{out}"
+        );
+    }
+
+    #[test]
+    fn test_show_bindings() {
+        let (_, bindings, approximations) = typecheck(SRC);
+        let mut out = Vec::new();
+        write_bindings(&mut out, &bindings, &approximations).unwrap();
+        assert_eq!(
+            "\n\nBINDINGS:\n\
+             f (foo/defs.bzl:2:5-6) = \"function\"\n\
+             g (foo/defs.bzl:5:5-6) = \"function\"\n\
+             x (foo/defs.bzl:2:7-8) = int\n\n",
+            String::from_utf8(out).unwrap()
+        );
+
+        let mut out = Vec::new();
+        let approximations = [Approximation::new("Unknown type", "foo")];
+        write_bindings(&mut out, &bindings, &approximations).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(
+            out.starts_with("\n\nAPPROXIMATIONS:\nApproximation: Unknown type = "),
+            "{out}"
+        );
+        assert!(out.contains("\n\nBINDINGS:\n"), "{out}");
+    }
+}
@@ -8,6 +8,7 @@
  */
 
 use std::ops::Deref;
+use std::path::Path;
 
 use async_recursion::async_recursion;
 use buck2_client_ctx::path_arg::PathArg;
@@ -18,6 +19,7 @@ use buck2_common::io::IoProvider;
 use buck2_core::build_file_path::BuildFilePath;
 use buck2_core::bzl::ImportPath;
 use buck2_core::cells::CellResolver;
+use buck2_core::fs::paths::abs_path::AbsPathBuf;
 use buck2_core::fs::paths::file_name::FileName;
 use buck2_core::fs::project_rel_path::ProjectRelativePathBuf;
 use buck2_core::package::PackageLabel;
@@ -121,6 +123,67 @@ async fn starlark_file(
     Ok(())
 }
 
+/// A path argument as written on the command line.
+#[derive(Debug, PartialEq)]
+enum PathArgKind<'a> {
+    /// A cell path `cell//path`, where the cell alias may be empty.
+    /// A trailing `...` is removed from the path.
+    Cell(&'a str, &'a str),
+    /// A directory relative to the working directory followed by `...`, e.g. `foo/...`,
+    /// with the `...` removed.
+    Recursive(&'a str),
+    /// Any other file system path.
+    Path,
+}
+
+fn parse_path_arg(path: &str) -> PathArgKind<'_> {
+    fn strip_recursive(path: &str) -> Option<&str> {
+        path.strip_suffix("...")
+            .map(|dir| dir.trim_end_matches('/'))
+    }
+
+    if let Some((cell_alias, cell_relative_path)) = path.split_once("//") {
+        PathArgKind::Cell(
+            cell_alias,
+            strip_recursive(cell_relative_path).unwrap_or(cell_relative_path),
+        )
+    } else if let Some(dir) = strip_recursive(path) {
+        PathArgKind::Recursive(dir)
+    } else {
+        PathArgKind::Path
+    }
+}
+
+/// Resolve a path argument, which is either a file system path, or a cell path like `cell//foo`.
+/// A trailing `...` (as in `//...` or `foo/...`) is accepted and means the whole directory.
+/// Both `buck2 starlark lint` and `buck2 starlark typecheck` accept paths this way.
+fn resolve_path_arg(
+    path: &PathArg,
+    context: &dyn ServerCommandContextTrait,
+    cell_resolver: &CellResolver,
+) -> anyhow::Result<AbsPathBuf> {
+    let Some(path_str) = path.path().to_str() else {
+        return Ok(path.resolve(context.working_dir_abs()));
+    };
+    match parse_path_arg(path_str) {
+        PathArgKind::Cell(cell_alias, cell_relative_path) => Ok(cell_resolver
+            .resolve_cell_relative_path(
+                cell_alias,
+                cell_relative_path,
+                context.project_root(),
+                context.working_dir_abs().path(),
+            )?
+            .into_abs_path_buf()),
+        PathArgKind::Recursive("") => Ok(context
+            .working_dir_abs()
+            .path()
+            .to_buf()
+            .into_abs_path_buf()),
+        PathArgKind::Recursive(dir) => Ok(context.working_dir_abs().resolve(Path::new(dir))),
+        PathArgKind::Path => Ok(path.resolve(context.working_dir_abs())),
+    }
+}
+
 /// Find the paths to apply Starlark to (e.g. linter, typecheck)
 pub(crate) async fn starlark_files(
     paths: &[PathArg],
@@ -132,10 +195,37 @@ pub(crate) async fn starlark_files(
     let mut files = Vec::new();
 
     for path in paths {
-        let path = path.resolve(context.working_dir_abs());
+        let path = resolve_path_arg(path, context, cell_resolver)?;
         let cell_path = cell_resolver.get_cell_path_from_abs_path(&path, context.project_root())?;
         let proj_path = cell_resolver.resolve_path(cell_path.as_ref())?;
         starlark_file(proj_path, None, cell_resolver, fs, io, &mut files).await?;
     }
     Ok(files)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_path_arg() {
+        assert_eq!(PathArgKind::Cell("", ""), parse_path_arg("//..."));
+        assert_eq!(PathArgKind::Cell("", "foo"), parse_path_arg("//foo/..."));
+        assert_eq!(
+            PathArgKind::Cell("cell", "foo/bar.bzl"),
+            parse_path_arg("cell//foo/bar.bzl")
+        );
+        assert_eq!(
+            PathArgKind::Cell("cell", "foo"),
+            parse_path_arg("cell//foo")
+        );
+        assert_eq!(PathArgKind::Recursive(""), parse_path_arg("..."));
+        assert_eq!(
+            PathArgKind::Recursive("foo/bar"),
+            parse_path_arg("foo/bar/...")
+        );
+        assert_eq!(PathArgKind::Path, parse_path_arg("foo/bar.bzl"));
+        assert_eq!(PathArgKind::Path, parse_path_arg("/abs/foo"));
+        assert_eq!(PathArgKind::Path, parse_path_arg(".."));
+    }
+}
@@ -101,6 +101,11 @@ impl TraceId {
         }
     }
 
+    /// The 16 raw bytes of this trace ID.
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        self.0.as_bytes()
+    }
+
     /// Generate short hash to be used as a message key for a Scribe client.
     pub fn hash(&self) -> i64 {
         let mut hasher = DefaultHasher::new();
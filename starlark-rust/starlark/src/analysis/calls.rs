@@ -0,0 +1,264 @@
+/*
+ * Copyright 2019 The Starlark in Rust Authors.
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Function calls in a module, for linters which know what the functions do.
+
+use starlark_syntax::syntax::ast::Argument;
+use starlark_syntax::syntax::ast::AstExpr;
+use starlark_syntax::syntax::ast::AstLiteral;
+use starlark_syntax::syntax::ast::AstStmt;
+use starlark_syntax::syntax::ast::Expr;
+use starlark_syntax::syntax::ast::Stmt;
+use starlark_syntax::syntax::module::AstModuleFields;
+use starlark_syntax::syntax::uniplate::Visit;
+
+use crate::codemap::Span;
+use crate::syntax::AstModule;
+
+/// Value of a [`CallArg`], if it is a literal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CallArgValue {
+    /// String literal.
+    String(String),
+    /// List or tuple of string literals.
+    StringList(Vec<String>),
+    /// Any other expression.
+    Other,
+}
+
+/// Argument of a [`Call`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallArg {
+    /// Name of a named argument, `None` for positional arguments.
+    pub name: Option<String>,
+    /// The whole argument, including the name.
+    pub span: Span,
+    /// The value passed.
+    pub value: CallArgValue,
+}
+
+/// Call of a function referred to by name, like `glob(...)` or `native.glob(...)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Call {
+    /// The function called, e.g. `glob` or `native.glob`.
+    pub function: String,
+    /// The whole call expression.
+    pub span: Span,
+    /// Is the call a statement at the top level of the module,
+    /// e.g. a target declaration in a `BUCK` file.
+    pub top_level: bool,
+    /// Positional and named arguments in order, without `*args` and `**kwargs`.
+    pub args: Vec<CallArg>,
+    /// Does the call pass `*args` or `**kwargs`, so `args` are not all the arguments.
+    pub has_star_args: bool,
+}
+
+impl Call {
+    /// Find a named argument.
+    pub fn named_arg(&self, name: &str) -> Option<&CallArg> {
+        self.args.iter().find(|a| a.name.as_deref() == Some(name))
+    }
+}
+
+/// Find the calls in a module.
+pub trait AstModuleCalls {
+    /// All calls of functions referred to by name or by `x.name`, in source order.
+    ///
+    /// NOTE: If the AST is exposed in the future, this function may be removed and implemented
+    ///       by specific programs instead.
+    fn calls(&self) -> Vec<Call>;
+}
+
+fn function_name(x: &AstExpr) -> Option<String> {
+    match &x.node {
+        Expr::Identifier(x) => Some(x.node.ident.clone()),
+        Expr::Dot(x, name) => match &x.node {
+            Expr::Identifier(x) => Some(format!("{}.{}", x.node.ident, name.node)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn string_literal(x: &AstExpr) -> Option<String> {
+    match &x.node {
+        Expr::Literal(AstLiteral::String(s)) => Some(s.node.clone()),
+        _ => None,
+    }
+}
+
+fn arg_value(x: &AstExpr) -> CallArgValue {
+    match &x.node {
+        Expr::Literal(AstLiteral::String(s)) => CallArgValue::String(s.node.clone()),
+        Expr::List(xs) | Expr::Tuple(xs) => match xs.iter().map(string_literal).collect() {
+            Some(xs) => CallArgValue::StringList(xs),
+            None => CallArgValue::Other,
+        },
+        _ => CallArgValue::Other,
+    }
+}
+
+fn expr(x: &AstExpr, top_level: bool, res: &mut Vec<Call>) {
+    if let Expr::Call(f, args) = &x.node {
+        if let Some(function) = function_name(f) {
+            let mut call = Call {
+                function,
+                span: x.span,
+                top_level,
+                args: Vec::new(),
+                has_star_args: false,
+            };
+            for arg in args {
+                let (name, value) = match &arg.node {
+                    Argument::Positional(e) => (None, e),
+                    Argument::Named(name, e) => (Some(name.node.clone()), e),
+                    Argument::Args(_) | Argument::KwArgs(_) => {
+                        call.has_star_args = true;
+                        continue;
+                    }
+                };
+                call.args.push(CallArg {
+                    name,
+                    span: arg.span,
+                    value: arg_value(value),
+                });
+            }
+            res.push(call);
+        }
+    }
+    x.visit_expr(|x| expr(x, false, res));
+}
+
+fn stmt(x: &AstStmt, top_level: bool, res: &mut Vec<Call>) {
+    match &x.node {
+        Stmt::Expression(e) => expr(e, top_level, res),
+        Stmt::Statements(_) => x.visit_stmt(|x| stmt(x, top_level, res)),
+        // `visit_expr` would also visit the expressions of nested statements.
+        _ => x.visit_children(|x| match x {
+            Visit::Stmt(x) => stmt(x, false, res),
+            Visit::Expr(x) => expr(x, false, res),
+        }),
+    }
+}
+
+impl AstModuleCalls for AstModule {
+    fn calls(&self) -> Vec<Call> {
+        let mut res = Vec::new();
+        stmt(self.statement(), true, &mut res);
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use starlark_syntax::syntax::module::AstModuleFields;
+
+    use crate::analysis::calls::AstModuleCalls;
+    use crate::analysis::calls::CallArgValue;
+    use crate::syntax::AstModule;
+    use crate::syntax::Dialect;
+
+    #[test]
+    fn test_calls() {
+        let module = AstModule::parse(
+            "BUCK",
+            r#"
+load(":defs.bzl", "my_macro")
+cxx_library(
+    name = "foo",
+    srcs = native.glob(["*.c"], exclude = ("x.c",)),
+    deps = [":bar"] + DEPS,
+)
+my_macro(name = "bar", **kwargs)
+x = f(g())
+"#
+            .to_owned(),
+            &Dialect::Standard,
+        )
+        .unwrap();
+        let calls = module.calls();
+        assert_eq!(
+            vec![
+                ("cxx_library", true),
+                ("native.glob", false),
+                ("my_macro", true),
+                ("f", false),
+                ("g", false),
+            ],
+            calls
+                .iter()
+                .map(|c| (c.function.as_str(), c.top_level))
+                .collect::<Vec<_>>()
+        );
+
+        let library = &calls[0];
+        assert_eq!(
+            Some(&CallArgValue::String("foo".to_owned())),
+            library.named_arg("name").map(|a| &a.value)
+        );
+        assert_eq!(
+            Some(&CallArgValue::Other),
+            library.named_arg("deps").map(|a| &a.value)
+        );
+        assert_eq!(
+            "srcs = native.glob([\"*.c\"], exclude = (\"x.c\",))",
+            module
+                .codemap()
+                .source_span(library.named_arg("srcs").unwrap().span)
+        );
+
+        let glob = &calls[1];
+        assert_eq!(None, glob.args[0].name);
+        assert_eq!(
+            CallArgValue::StringList(vec!["*.c".to_owned()]),
+            glob.args[0].value
+        );
+        assert_eq!(
+            Some(&CallArgValue::StringList(vec!["x.c".to_owned()])),
+            glob.named_arg("exclude").map(|a| &a.value)
+        );
+
+        assert!(!library.has_star_args);
+        assert!(calls[2].has_star_args);
+    }
+
+    #[test]
+    fn test_calls_nested() {
+        let module = AstModule::parse(
+            "BUCK",
+            r#"
+def f(x = g()):
+    if h():
+        for y in i():
+            j(y)
+    return k()
+"#
+            .to_owned(),
+            &Dialect::Standard,
+        )
+        .unwrap();
+        assert_eq!(
+            vec!["g", "h", "i", "j", "k"],
+            module
+                .calls()
+                .iter()
+                .map(|c| c.function.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert!(module.calls().iter().all(|c| !c.top_level));
+    }
+}
@@ -28,6 +28,7 @@ pub use unused_loads::remove::remove_unused_loads;
 use crate::analysis::types::LintT;
 use crate::syntax::AstModule;
 
+pub mod calls;
 mod dubious;
 pub mod find_call_name;
 mod flow;
@@ -27,6 +27,7 @@ use std::ptr;
 
 pub use starlark_derive::Coerce;
 use starlark_map::small_map::SmallMap;
+use starlark_map::small_set::SmallSet;
 
 /// A marker trait such that the existence of `From: Coerce<To>` implies
 /// that `From` can be treat as `To` without any data manipulation.
@@ -106,6 +107,8 @@ where
 {
 }
 
+unsafe impl<From, To> Coerce<SmallSet<To>> for SmallSet<From> where From: CoerceKey<To> {}
+
 unsafe impl<From1: Coerce<To1>, To1> Coerce<(To1,)> for (From1,) {}
 unsafe impl<From1: CoerceKey<To1>, To1> CoerceKey<(To1,)> for (From1,) {}
 
@@ -37,6 +37,7 @@ use crate::codemap::FileSpan;
 use crate::codemap::FileSpanRef;
 use crate::codemap::Span;
 use crate::debug::adapter::Breakpoint;
+use crate::debug::adapter::HitCondition;
 use crate::debug::adapter::ResolvedBreakpoints;
 use crate::debug::DapAdapter;
 use crate::debug::DapAdapterClient;
@@ -111,17 +112,9 @@ impl<'a, 'e: 'a> BeforeStmtFuncDyn<'a, 'e> for DapAdapterEvalHookImpl {
         let stop = if self.state.disable_breakpoints.load(Ordering::SeqCst) > 0 {
             false
         } else {
-            let breaks = self.state.breakpoints.lock().unwrap();
-            let breakpoint = breaks.at(span_loc);
-            match breakpoint {
-                Some(Breakpoint {
-                    condition: Some(condition),
-                    ..
-                }) => match evaluate_expr(&self.state, eval, condition.to_owned()) {
-                    Ok(v) => v.to_bool(),
-                    _ => true,
-                },
-                Some(..) => true,
+            let mut breaks = self.state.breakpoints.lock().unwrap();
+            match breaks.at(span_loc) {
+                Some(breakpoint) => breakpoint.hit(&self.state, eval),
                 None => false,
             }
         };
@@ -183,7 +176,108 @@ impl DapAdapterEvalHook for DapAdapterEvalHookImpl {
 #[derive(Debug)]
 struct BreakpointConfig {
     // maps a source filename to the breakpoint spans for the file
-    breakpoints: HashMap<String, HashMap<Span, Breakpoint>>,
+    breakpoints: HashMap<String, HashMap<Span, BreakpointState>>,
+}
+
+#[derive(Debug)]
+struct BreakpointState {
+    breakpoint: Breakpoint,
+    // Number of times the breakpoint was reached with its condition (if any) satisfied.
+    hits: u64,
+}
+
+impl BreakpointState {
+    /// Called when the breakpoint is reached, returns whether the evaluation should stop.
+    fn hit(&mut self, state: &SharedAdapterState, eval: &mut Evaluator) -> bool {
+        if let Some(condition) = &self.breakpoint.condition {
+            match evaluate_expr(state, eval, condition.to_owned()) {
+                Ok(v) if !v.to_bool() => return false,
+                // Stop on errors so the user can see something is wrong with the condition.
+                _ => {}
+            }
+        }
+        self.hits += 1;
+        if let Some(hit_condition) = self.breakpoint.hit_condition {
+            if !hit_condition.matches(self.hits) {
+                return false;
+            }
+        }
+        match &self.breakpoint.log_message {
+            Some(log_message) => {
+                let output = interpolate_log_message(state, eval, log_message);
+                state.client.event_output(&output);
+                false
+            }
+            None => true,
+        }
+    }
+}
+
+/// Expands the `{expr}` placeholders of a logpoint message,
+/// `{{` and `}}` are literal braces.
+fn interpolate_log_message(
+    state: &SharedAdapterState,
+    eval: &mut Evaluator,
+    log_message: &str,
+) -> String {
+    let mut res = String::new();
+    let mut rest = log_message;
+    while let Some(i) = rest.find(['{', '}']) {
+        res.push_str(&rest[..i]);
+        let c = &rest[i..i + 1];
+        rest = &rest[i + 1..];
+        if let Some(r) = rest.strip_prefix(c) {
+            res.push_str(c);
+            rest = r;
+        } else if c == "}" {
+            res.push('}');
+        } else if let Some(end) = rest.find('}') {
+            match evaluate_expr(state, eval, rest[..end].to_owned()) {
+                Ok(v) => res.push_str(&v.to_str()),
+                Err(e) => res.push_str(&format!("<error: {}>", e)),
+            }
+            rest = &rest[end + 1..];
+        } else {
+            res.push('{');
+        }
+    }
+    res.push_str(rest);
+    res
+}
+
+impl HitCondition {
+    /// Parses the `hitCondition` syntax used by debugpy:
+    /// `N`, `== N`, `> N`, `>= N`, `< N`, `<= N` or `% N`.
+    fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let ops: [(&str, fn(u64) -> HitCondition); 6] = [
+            (">=", HitCondition::GtEq),
+            ("<=", HitCondition::LtEq),
+            ("==", HitCondition::Eq),
+            (">", HitCondition::Gt),
+            ("<", HitCondition::Lt),
+            ("%", HitCondition::Mod),
+        ];
+        let (op, n): (fn(u64) -> HitCondition, &str) = ops
+            .iter()
+            .find_map(|(prefix, op)| s.strip_prefix(prefix).map(|n| (*op, n)))
+            .unwrap_or((HitCondition::Eq, s));
+        match op(n.trim().parse().ok()?) {
+            HitCondition::Mod(0) => None,
+            c => Some(c),
+        }
+    }
+
+    fn matches(self, hits: u64) -> bool {
+        match self {
+            HitCondition::Eq(n) => hits == n,
+            HitCondition::Gt(n) => hits > n,
+            HitCondition::GtEq(n) => hits >= n,
+            HitCondition::Lt(n) => hits < n,
+            HitCondition::LtEq(n) => hits <= n,
+            HitCondition::Mod(n) => hits % n == 0,
+        }
+    }
 }
 
 impl BreakpointConfig {
@@ -193,10 +287,10 @@ impl BreakpointConfig {
         }
     }
 
-    fn at(&self, span_loc: FileSpanRef) -> Option<&Breakpoint> {
+    fn at(&mut self, span_loc: FileSpanRef) -> Option<&mut BreakpointState> {
         self.breakpoints
-            .get(span_loc.filename())
-            .and_then(|file_breaks| file_breaks.get(&span_loc.span))
+            .get_mut(span_loc.filename())
+            .and_then(|file_breaks| file_breaks.get_mut(&span_loc.span))
     }
 
     fn set_breakpoints(
@@ -213,7 +307,15 @@ impl BreakpointConfig {
                     .0
                     .iter()
                     .filter_map(|x| x.clone())
-                    .map(|x| (x.span.span, x))
+                    .map(|x| {
+                        (
+                            x.span.span,
+                            BreakpointState {
+                                breakpoint: x,
+                                hits: 0,
+                            },
+                        )
+                    })
                     .collect(),
             );
         }
@@ -417,6 +519,10 @@ pub(crate) fn breakpoint(verified: bool) -> debugserver_types::Breakpoint {
     }
 }
 
+fn non_empty(s: &Option<String>) -> Option<&str> {
+    s.as_deref().filter(|s| !s.trim().is_empty())
+}
+
 pub(crate) fn resolve_breakpoints(
     args: &SetBreakpointsArguments,
     ast: &AstModule,
@@ -430,9 +536,16 @@ pub(crate) fn resolve_breakpoints(
         Vec::new(),
         |v| {
             v.map(|x| {
+                // An invalid hit condition leaves the breakpoint unverified.
+                let hit_condition = match non_empty(&x.hit_condition) {
+                    Some(c) => Some(HitCondition::parse(c)?),
+                    None => None,
+                };
                 poss.get(&(x.line as usize - 1)).map(|span| Breakpoint {
                     span: span.clone(),
                     condition: x.condition.clone(),
+                    hit_condition,
+                    log_message: non_empty(&x.log_message).map(|m| m.to_owned()),
                 })
             })
         },
@@ -39,6 +39,8 @@ mod tests;
 pub trait DapAdapterClient: Debug + Send + Sync + 'static {
     /// Indicates that the evaluation stopped at a breakpoint.
     fn event_stopped(&self);
+    /// Output produced by the debuggee which should be shown to the user, e.g. by a logpoint.
+    fn event_output(&self, output: &str);
 }
 
 /// Information about the variables scopes
@@ -405,6 +407,19 @@ pub trait DapAdapter: Debug + Send + 'static {
 pub(crate) struct Breakpoint {
     span: FileSpan,
     condition: Option<String>,
+    hit_condition: Option<HitCondition>,
+    log_message: Option<String>,
+}
+
+/// Parsed `hitCondition` of a breakpoint, e.g. `>= 3` or `% 2`.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub(crate) enum HitCondition {
+    Eq(u64),
+    Gt(u64),
+    GtEq(u64),
+    Lt(u64),
+    LtEq(u64),
+    Mod(u64),
 }
 
 /// Breakpoints resolved to their spans.
@@ -442,6 +457,8 @@ pub fn dap_capabilities() -> Capabilities {
         supports_set_variable: Some(true),
         supports_step_in_targets_request: Some(true),
         supports_conditional_breakpoints: Some(true),
+        supports_hit_conditional_breakpoints: Some(true),
+        supports_log_points: Some(true),
         ..Capabilities::default()
     }
 }
@@ -21,6 +21,7 @@ mod t {
     use std::sync::atomic::AtomicUsize;
     use std::sync::atomic::Ordering;
     use std::sync::Arc;
+    use std::sync::Mutex;
     use std::thread::ScopedJoinHandle;
     use std::time::Duration;
     use std::time::Instant;
@@ -36,6 +37,7 @@ mod t {
     use crate::debug::DapAdapterEvalHook;
     use crate::debug::StepKind;
     use crate::debug::VariablePath;
+    use crate::environment::FrozenModule;
     use crate::environment::GlobalsBuilder;
     use crate::environment::Module;
     use crate::eval::Evaluator;
@@ -48,11 +50,15 @@ mod t {
     #[derive(Debug)]
     struct Client {
         breakpoints_hit: Arc<AtomicUsize>,
+        outputs: Arc<Mutex<Vec<String>>>,
     }
 
     impl Client {
-        pub fn new(breakpoints_hit: Arc<AtomicUsize>) -> Self {
-            Self { breakpoints_hit }
+        pub fn new(breakpoints_hit: Arc<AtomicUsize>, outputs: Arc<Mutex<Vec<String>>>) -> Self {
+            Self {
+                breakpoints_hit,
+                outputs,
+            }
         }
     }
 
@@ -61,21 +67,34 @@ mod t {
             println!("stopped!");
             self.breakpoints_hit.fetch_add(1, Ordering::SeqCst);
         }
+
+        fn event_output(&self, output: &str) {
+            self.outputs.lock().unwrap().push(output.to_owned());
+        }
     }
 
     struct BreakpointController {
         breakpoints_hit: Arc<AtomicUsize>,
+        outputs: Arc<Mutex<Vec<String>>>,
     }
 
     impl BreakpointController {
         fn new() -> Self {
             Self {
                 breakpoints_hit: Arc::new(AtomicUsize::new(0)),
+                outputs: Arc::new(Mutex::new(Vec::new())),
             }
         }
 
         fn get_client(&self) -> Box<dyn DapAdapterClient> {
-            Box::new(Client::new(self.breakpoints_hit.dupe()))
+            Box::new(Client::new(
+                self.breakpoints_hit.dupe(),
+                self.outputs.dupe(),
+            ))
+        }
+
+        fn outputs(&self) -> Vec<String> {
+            self.outputs.lock().unwrap().clone()
         }
 
         fn wait_for_eval_stopped(&self, breakpoint_count: usize, timeout: Duration) {
@@ -100,13 +119,21 @@ mod t {
     }
 
     fn breakpoints_args(path: &str, lines: &[(i64, Option<&str>)]) -> SetBreakpointsArguments {
+        source_breakpoints_args(
+            path,
+            lines
+                .iter()
+                .map(|(line, condition)| breakpoint(*line, condition.as_deref()))
+                .collect(),
+        )
+    }
+
+    fn source_breakpoints_args(
+        path: &str,
+        breakpoints: Vec<SourceBreakpoint>,
+    ) -> SetBreakpointsArguments {
         SetBreakpointsArguments {
-            breakpoints: Some(
-                lines
-                    .iter()
-                    .map(|(line, condition)| breakpoint(*line, condition.as_deref()))
-                    .collect(),
-            ),
+            breakpoints: Some(breakpoints),
             lines: None,
             source: Source {
                 adapter_data: None,
@@ -126,8 +153,15 @@ mod t {
         ast: AstModule,
         hook: impl DapAdapterEvalHook,
     ) -> crate::Result<OwnedFrozenValue> {
-        let modules = HashMap::new();
-        let loader = ReturnFileLoader { modules: &modules };
+        eval_with_hook_and_modules(ast, hook, &HashMap::new())
+    }
+
+    fn eval_with_hook_and_modules(
+        ast: AstModule,
+        hook: impl DapAdapterEvalHook,
+        modules: &HashMap<&str, &FrozenModule>,
+    ) -> crate::Result<OwnedFrozenValue> {
+        let loader = ReturnFileLoader { modules };
         let globals = GlobalsBuilder::extended().with(test_functions).build();
         let env = Module::new();
         let res = {
@@ -178,10 +212,6 @@ print(x)
             let eval_result =
                 s.spawn(move || -> crate::Result<_> { eval_with_hook(ast, eval_hook) });
             controller.wait_for_eval_stopped(1, TIMEOUT);
-            // TODO(cjhopman): we currently hit breakpoints on top-level statements twice (once for the gc bytecode, once for the actual statement).
-            adapter.continue_()?;
-            controller.wait_for_eval_stopped(2, TIMEOUT);
-
             adapter.continue_()?;
 
             join_timeout(eval_result, TIMEOUT)?;
@@ -234,8 +264,190 @@ print(x)
                 s.spawn(move || -> crate::Result<_> { eval_with_hook(ast, eval_hook) });
             controller.wait_for_eval_stopped(1, TIMEOUT);
             adapter.continue_()?;
-            // TODO(cjhopman): we currently hit breakpoints on top-level statements twice (once for the gc bytecode, once for the actual statement).
+
+            join_timeout(eval_result, TIMEOUT)?;
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_breakpoint_with_hit_condition() -> crate::Result<()> {
+        if is_wasm() {
+            return Ok(());
+        }
+
+        let controller = BreakpointController::new();
+        let (adapter, eval_hook) = prepare_dap_adapter(controller.get_client());
+        let file_contents = "
+def f(x):
+    return x # line 3
+for i in range(5):
+    f(i)
+        ";
+        std::thread::scope(|s| {
+            let ast = AstModule::parse("test.bzl", file_contents.to_owned(), &Dialect::Extended)?;
+            let breakpoints = resolve_breakpoints(
+                &source_breakpoints_args(
+                    "test.bzl",
+                    vec![SourceBreakpoint {
+                        hit_condition: Some("% 2".to_owned()),
+                        ..breakpoint(3, None)
+                    }],
+                ),
+                &ast,
+            )?;
+            adapter.set_breakpoints("test.bzl", &breakpoints)?;
+            let eval_result =
+                s.spawn(move || -> crate::Result<_> { eval_with_hook(ast, eval_hook) });
+            controller.wait_for_eval_stopped(1, TIMEOUT);
+            assert_eq!("1", adapter.evaluate("x")?.result);
+            adapter.continue_()?;
             controller.wait_for_eval_stopped(2, TIMEOUT);
+            assert_eq!("3", adapter.evaluate("x")?.result);
+            adapter.continue_()?;
+
+            join_timeout(eval_result, TIMEOUT)?;
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_invalid_hit_condition() -> crate::Result<()> {
+        let ast = AstModule::parse("test.bzl", "x = 1\n".to_owned(), &Dialect::Extended)?;
+        let breakpoints = resolve_breakpoints(
+            &source_breakpoints_args(
+                "test.bzl",
+                vec![
+                    SourceBreakpoint {
+                        hit_condition: Some(">= 2".to_owned()),
+                        ..breakpoint(1, None)
+                    },
+                    SourceBreakpoint {
+                        hit_condition: Some("often".to_owned()),
+                        ..breakpoint(1, None)
+                    },
+                    SourceBreakpoint {
+                        hit_condition: Some("% 0".to_owned()),
+                        ..breakpoint(1, None)
+                    },
+                ],
+            ),
+            &ast,
+        )?;
+        assert_eq!(
+            vec![true, false, false],
+            breakpoints
+                .to_response()
+                .breakpoints
+                .iter()
+                .map(|b| b.verified)
+                .collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_logpoint() -> crate::Result<()> {
+        if is_wasm() {
+            return Ok(());
+        }
+
+        let controller = BreakpointController::new();
+        let (adapter, eval_hook) = prepare_dap_adapter(controller.get_client());
+        let file_contents = "
+def f(x):
+    return x # line 3
+for i in range(4):
+    f(i)
+        ";
+        std::thread::scope(|s| {
+            let ast = AstModule::parse("test.bzl", file_contents.to_owned(), &Dialect::Extended)?;
+            let breakpoints = resolve_breakpoints(
+                &source_breakpoints_args(
+                    "test.bzl",
+                    vec![SourceBreakpoint {
+                        log_message: Some("{{x}} = {x * 10}".to_owned()),
+                        ..breakpoint(3, Some("x != 2"))
+                    }],
+                ),
+                &ast,
+            )?;
+            adapter.set_breakpoints("test.bzl", &breakpoints)?;
+            let eval_result =
+                s.spawn(move || -> crate::Result<_> { eval_with_hook(ast, eval_hook) });
+            // Logpoints never stop the evaluation.
+            join_timeout(eval_result, TIMEOUT)?;
+            crate::Result::Ok(())
+        })?;
+        assert_eq!(
+            vec!["{x} = 0", "{x} = 10", "{x} = 30"],
+            controller.outputs()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_step_into_loaded_module() -> crate::Result<()> {
+        if is_wasm() {
+            return Ok(());
+        }
+
+        let lib = Module::new();
+        {
+            let globals = GlobalsBuilder::standard().build();
+            let mut eval = Evaluator::new(&lib);
+            let ast = AstModule::parse(
+                "lib.bzl",
+                "
+def adjust(y):
+    y[0] += 1
+    y[1] += 1
+"
+                .to_owned(),
+                &Dialect::Extended,
+            )?;
+            eval.eval_module(ast, &globals)?;
+        }
+        let lib = lib.freeze()?;
+
+        let controller = BreakpointController::new();
+        let (adapter, eval_hook) = prepare_dap_adapter(controller.get_client());
+        let file_contents = "
+load(\"lib.bzl\", \"adjust\")
+x = [1, 2, 3]
+adjust(x) # line 4
+print(x)
+        ";
+        std::thread::scope(|s| {
+            let ast = AstModule::parse("test.bzl", file_contents.to_owned(), &Dialect::Extended)?;
+            let breakpoints =
+                resolve_breakpoints(&breakpoints_args("test.bzl", &[(4, None)]), &ast)?;
+            adapter.set_breakpoints("test.bzl", &breakpoints)?;
+            let eval_result = s.spawn(|| -> crate::Result<_> {
+                let modules = HashMap::from([("lib.bzl", &lib)]);
+                eval_with_hook_and_modules(ast, eval_hook, &modules)
+            });
+            controller.wait_for_eval_stopped(1, TIMEOUT);
+
+            // into adjust, which lives in the loaded module
+            adapter.step(StepKind::Into)?;
+            controller.wait_for_eval_stopped(2, TIMEOUT);
+            let frame = adapter.top_frame()?.unwrap();
+            assert_eq!(Some("lib.bzl"), frame.source.unwrap().path.as_deref());
+            assert_eq!(3, frame.line);
+            assert_eq!("1", adapter.evaluate("y[0]")?.result);
+
+            adapter.step(StepKind::Over)?;
+            controller.wait_for_eval_stopped(3, TIMEOUT);
+            assert_eq!(4, adapter.top_frame()?.unwrap().line);
+
+            // out again into the loading module
+            adapter.step(StepKind::Out)?;
+            controller.wait_for_eval_stopped(4, TIMEOUT);
+            let frame = adapter.top_frame()?.unwrap();
+            assert_eq!(Some("test.bzl"), frame.source.unwrap().path.as_deref());
+            assert_eq!(5, frame.line);
+            assert_eq!("3", adapter.evaluate("x[1]")?.result);
             adapter.continue_()?;
 
             join_timeout(eval_result, TIMEOUT)?;
@@ -269,24 +481,18 @@ print(x)
             let eval_result =
                 s.spawn(move || -> crate::Result<_> { eval_with_hook(ast, eval_hook) });
             controller.wait_for_eval_stopped(1, TIMEOUT);
-            // TODO(cjhopman): we currently hit breakpoints on top-level statements twice (once for the gc bytecode, once for the actual statement).
-            adapter.continue_()?;
-            controller.wait_for_eval_stopped(2, TIMEOUT);
 
             assert_eq!("1", adapter.evaluate("x[0]")?.result);
             assert_eq!("2", adapter.evaluate("x[1]")?.result);
             assert_eq!("3", adapter.evaluate("x[2]")?.result);
             adapter.step(StepKind::Over)?;
-            controller.wait_for_eval_stopped(3, TIMEOUT);
+            controller.wait_for_eval_stopped(2, TIMEOUT);
             assert_eq!("2", adapter.evaluate("x[0]")?.result);
             assert_eq!("3", adapter.evaluate("x[1]")?.result);
             assert_eq!("4", adapter.evaluate("x[2]")?.result);
 
-            // TODO(cjhopman): we currently hit breakpoints on top-level statements twice (once for the gc bytecode, once for the actual statement).
-            adapter.step(StepKind::Over)?;
-            controller.wait_for_eval_stopped(4, TIMEOUT);
             adapter.step(StepKind::Over)?;
-            controller.wait_for_eval_stopped(5, TIMEOUT);
+            controller.wait_for_eval_stopped(3, TIMEOUT);
             assert_eq!("3", adapter.evaluate("x[0]")?.result);
             assert_eq!("4", adapter.evaluate("x[1]")?.result);
             assert_eq!("5", adapter.evaluate("x[2]")?.result);
@@ -322,9 +528,6 @@ print(x)
             let eval_result =
                 s.spawn(move || -> crate::Result<_> { eval_with_hook(ast, eval_hook) });
             controller.wait_for_eval_stopped(1, TIMEOUT);
-            // TODO(cjhopman): we currently hit breakpoints on top-level statements twice (once for the gc bytecode, once for the actual statement).
-            adapter.continue_()?;
-            controller.wait_for_eval_stopped(2, TIMEOUT);
 
             assert_eq!("1", adapter.evaluate("x[0]")?.result);
             assert_eq!("2", adapter.evaluate("x[1]")?.result);
@@ -332,34 +535,30 @@ print(x)
 
             // into adjust
             adapter.step(StepKind::Into)?;
-            controller.wait_for_eval_stopped(3, TIMEOUT);
+            controller.wait_for_eval_stopped(2, TIMEOUT);
             assert_eq!("1", adapter.evaluate("y[0]")?.result);
             assert_eq!("2", adapter.evaluate("y[1]")?.result);
             assert_eq!("3", adapter.evaluate("y[2]")?.result);
 
             // into should go to next line
             adapter.step(StepKind::Into)?;
-            controller.wait_for_eval_stopped(4, TIMEOUT);
+            controller.wait_for_eval_stopped(3, TIMEOUT);
             assert_eq!("2", adapter.evaluate("y[0]")?.result);
             assert_eq!("2", adapter.evaluate("y[1]")?.result);
             assert_eq!("3", adapter.evaluate("y[2]")?.result);
 
             // two more intos should get us out of the function call
             adapter.step(StepKind::Into)?;
-            controller.wait_for_eval_stopped(5, TIMEOUT);
+            controller.wait_for_eval_stopped(4, TIMEOUT);
             adapter.step(StepKind::Into)?;
-            controller.wait_for_eval_stopped(6, TIMEOUT);
+            controller.wait_for_eval_stopped(5, TIMEOUT);
             assert_eq!("2", adapter.evaluate("x[0]")?.result);
             assert_eq!("3", adapter.evaluate("x[1]")?.result);
             assert_eq!("4", adapter.evaluate("x[2]")?.result);
 
             // and once more back into the function
             adapter.step(StepKind::Into)?;
-            controller.wait_for_eval_stopped(7, TIMEOUT);
-
-            // TODO(cjhopman): unfortunately, gc being marked as statements causes us to need to step_into again.
-            adapter.step(StepKind::Into)?;
-            controller.wait_for_eval_stopped(8, TIMEOUT);
+            controller.wait_for_eval_stopped(6, TIMEOUT);
 
             assert_eq!("2", adapter.evaluate("y[0]")?.result);
             assert_eq!("3", adapter.evaluate("y[1]")?.result);
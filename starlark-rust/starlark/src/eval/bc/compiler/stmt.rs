@@ -135,7 +135,11 @@ impl StmtCompiled {
 
 impl IrSpanned<StmtCompiled> {
     fn write_bc(&self, compiler: &StmtCompileContext, bc: &mut BcWriter) {
-        bc.mark_before_stmt(self.span);
+        // GC points share the span with the following statement,
+        // marking them would invoke `before_stmt` twice for that statement.
+        if !matches!(self.node, StmtCompiled::PossibleGc) {
+            bc.mark_before_stmt(self.span);
+        }
         self.write_bc_inner(compiler, bc);
         self.mark_definitely_assigned_after(bc);
     }
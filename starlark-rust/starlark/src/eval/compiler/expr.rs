@@ -159,7 +159,7 @@ impl Builtin1 {
                     .ok()
             }
             Builtin1::Dot(field) => {
-                Some(ExprCompiled::compile_time_getattr(v, field, ctx)?.to_value())
+                Some(ExprCompiled::compile_time_getattr_value(v, field, ctx)?.to_value())
             }
         }
     }
@@ -866,13 +866,27 @@ impl ExprCompiled {
         }
     }
 
+    /// Like `compile_time_getattr`, but does not bind methods.
+    /// Each evaluation of `x.method` creates a new bound method,
+    /// and bound methods are compared by identity.
+    fn compile_time_getattr_value(
+        left: FrozenValue,
+        attr: &Symbol,
+        ctx: &mut OptCtx,
+    ) -> Option<FrozenValue> {
+        match get_attr_hashed_raw(left.to_value(), attr, ctx.heap()).ok()? {
+            MemberOrValue::Member(_) => None,
+            MemberOrValue::Value(v) => v.unpack_frozen(),
+        }
+    }
+
     pub(crate) fn dot(
         object: IrSpanned<ExprCompiled>,
         field: &Symbol,
         ctx: &mut OptCtx,
     ) -> ExprCompiled {
         if let Some(left) = object.as_value() {
-            if let Some(v) = Self::compile_time_getattr(left, field, ctx) {
+            if let Some(v) = Self::compile_time_getattr_value(left, field, ctx) {
                 return ExprCompiled::Value(v);
             }
         }
@@ -1411,17 +1425,20 @@ impl<'v, 'a, 'e> Compiler<'v, 'a, 'e, '_> {
                     node: ExprCompiled::Value(heap.alloc(format.node.as_str())),
                     span: fstring_span,
                 };
-                let method = IrSpanned {
-                    node: ExprCompiled::dot(format, &Symbol::new("format"), &mut self.opt_ctx()),
-                    span: fstring_span,
-                };
 
                 let mut args = ArgsCompiledValue::default();
                 for expr in expressions {
                     args.push_pos(self.expr(expr));
                 }
 
-                CallCompiled::call(span, method, args, &mut self.opt_ctx())
+                CallCompiled::new_method(
+                    span,
+                    format,
+                    &Symbol::new("format"),
+                    fstring_span,
+                    args,
+                    &mut self.opt_ctx(),
+                )
             }
         };
         IrSpanned { node: expr, span }
@@ -36,6 +36,7 @@ pub use runtime::params::ParametersParser;
 pub use runtime::params::ParametersSpec;
 pub use runtime::params::ParametersSpecBuilder;
 pub use runtime::profile::data::ProfileData;
+pub use runtime::profile::heap_diff::HeapProfileDiff;
 pub use runtime::profile::ProfileMode;
 pub use starlark_syntax::call_stack::CallStack;
 use starlark_syntax::slice_vec_ext::SliceExt;
@@ -64,6 +65,8 @@ impl<'v, 'a, 'e> Evaluator<'v, 'a, 'e> {
 
         let (codemap, statement, dialect, typecheck) = ast.into_parts();
 
+        self.stmt_profile.add_module(&codemap, &dialect);
+
         let codemap = self
             .module_env
             .frozen_heap()
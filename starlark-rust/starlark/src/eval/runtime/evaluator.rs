@@ -93,10 +93,6 @@ enum EvaluatorError {
     ProfileOrInstrumentationAlreadyEnabled,
     #[error("Top frame is not def (internal error)")]
     TopFrameNotDef,
-    #[error(
-        "Coverage profile generation not implemented (but can be obtained with `.coverage()` function)"
-    )]
-    CoverageNotImplemented,
     #[error("Coverage not enabled")]
     CoverageNotEnabled,
     #[error("Local variable `{0}` referenced before assignment")]
@@ -144,7 +140,7 @@ pub struct Evaluator<'v, 'a, 'e> {
     // Profiling or instrumentation enabled.
     pub(crate) profile_or_instrumentation_mode: ProfileOrInstrumentationMode,
     // Used for line profiling
-    stmt_profile: StmtProfile,
+    pub(crate) stmt_profile: StmtProfile,
     // Holds things that require hooking into evaluation.
     eval_instrumentation: EvaluationInstrumentation<'a, 'e>,
     // Total time spent in runtime typechecking.
@@ -357,7 +353,7 @@ impl<'v, 'a, 'e: 'a> Evaluator<'v, 'a, 'e> {
                 Err(EvaluatorError::RetainedMemoryProfilingCannotBeObtainedFromEvaluator.into())
             }
             ProfileMode::Statement => self.stmt_profile.gen(),
-            ProfileMode::Coverage => Err(EvaluatorError::CoverageNotImplemented.into()),
+            ProfileMode::Coverage => self.stmt_profile.gen_lcov(),
             ProfileMode::Bytecode => self.gen_bc_profile(),
             ProfileMode::BytecodePairs => self.gen_bc_pairs_profile(),
             ProfileMode::TimeFlame => self.time_flame_profile.gen(),
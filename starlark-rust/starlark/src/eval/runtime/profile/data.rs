@@ -25,6 +25,7 @@ use starlark_syntax::slice_vec_ext::SliceExt;
 use crate::eval::runtime::profile::bc::BcPairsProfileData;
 use crate::eval::runtime::profile::bc::BcProfileData;
 use crate::eval::runtime::profile::flamegraph::FlameGraphData;
+use crate::eval::runtime::profile::lcov::LcovData;
 use crate::eval::ProfileMode;
 use crate::values::AggregateHeapProfileInfo;
 
@@ -47,6 +48,8 @@ pub(crate) enum ProfileDataImpl {
     AggregateHeapProfileInfo(Box<AggregateHeapProfileInfo>),
     /// Flame graph data is in milliseconds.
     TimeFlameProfile(FlameGraphData),
+    /// Code coverage, written in LCOV format.
+    Coverage(LcovData),
     Other(String),
 }
 
@@ -87,6 +90,10 @@ impl ProfileData {
             (ProfileDataImpl::TimeFlameProfile(_), _) => {
                 Err(ProfileDataError::ProfileDataNotConsistent.into())
             }
+            (ProfileDataImpl::Coverage(data), ProfileMode::Coverage) => Ok(data.write()),
+            (ProfileDataImpl::Coverage(_), _) => {
+                Err(ProfileDataError::ProfileDataNotConsistent.into())
+            }
         }
     }
 
@@ -152,6 +159,14 @@ impl ProfileData {
                 let profile = FlameGraphData::merge(profiles);
                 ProfileDataImpl::TimeFlameProfile(profile)
             }
+            ProfileMode::Coverage => {
+                let profiles = profiles.try_map(|p| match &p.profile {
+                    ProfileDataImpl::Coverage(data) => Ok(data),
+                    _ => Err(ProfileDataError::ProfileDataNotConsistent),
+                })?;
+                let profile = LcovData::merge(profiles);
+                ProfileDataImpl::Coverage(profile)
+            }
             profile_mode => {
                 return Err(ProfileDataError::MergeNotImplemented(profile_mode.dupe()).into());
             }
@@ -0,0 +1,332 @@
+/*
+ * Copyright 2019 The Starlark in Rust Authors.
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Compare two heap profiles.
+
+use std::collections::HashSet;
+
+use starlark_map::small_map::SmallMap;
+
+use crate::eval::runtime::profile::csv::CsvWriter;
+
+#[derive(Debug, thiserror::Error)]
+enum HeapProfileDiffError {
+    #[error(
+        "Invalid heap profile line {0}: `{1}`, \
+        expecting output of `heap-flame-allocated` or `heap-flame-retained` profile mode"
+    )]
+    InvalidLine(usize, String),
+}
+
+/// Heap profile in flame graph format, aggregated by function and by type.
+#[derive(Debug, Default)]
+struct HeapFlameSummary {
+    /// Total bytes, including unused capacity.
+    total: u64,
+    /// Memory allocated in bump, but unused.
+    unused_capacity: u64,
+    /// Bytes allocated directly by the function.
+    self_bytes: SmallMap<String, u64>,
+    /// Bytes allocated by the function and everything it calls.
+    total_bytes: SmallMap<String, u64>,
+    /// Bytes allocated for values of the type.
+    type_bytes: SmallMap<String, u64>,
+}
+
+impl HeapFlameSummary {
+    fn parse(profile: &str) -> anyhow::Result<HeapFlameSummary> {
+        let mut summary = HeapFlameSummary::default();
+        for (i, line) in profile.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let invalid = || HeapProfileDiffError::InvalidLine(i + 1, line.to_owned());
+            let (stack, bytes) = line.rsplit_once(' ').ok_or_else(invalid)?;
+            let bytes: u64 = bytes.parse().map_err(|_| invalid())?;
+            let mut frames: Vec<&str> = stack.split(';').collect();
+            // The last frame is the type of the allocated values,
+            // the frames before it are the call stack.
+            let ty = frames.pop().ok_or_else(invalid)?;
+            summary.total += bytes;
+            if frames.is_empty() && ty == "unused_capacity" {
+                summary.unused_capacity += bytes;
+                continue;
+            }
+            *summary.type_bytes.entry(ty.to_owned()).or_default() += bytes;
+            let func = frames.last().copied().unwrap_or("(root)");
+            *summary.self_bytes.entry(func.to_owned()).or_default() += bytes;
+            // Recursive functions are only counted once per stack.
+            let mut seen = HashSet::new();
+            for frame in frames.iter().copied().chain(["(root)"]) {
+                if seen.insert(frame) {
+                    *summary.total_bytes.entry(frame.to_owned()).or_default() += bytes;
+                }
+            }
+        }
+        Ok(summary)
+    }
+}
+
+/// Difference between two heap profiles written in
+/// `heap-flame-allocated` or `heap-flame-retained` mode.
+///
+/// Profiles in `heap-flame-retained` mode describe memory kept in the frozen heap,
+/// so diffing those shows what changed in the frozen heap.
+#[derive(Debug)]
+pub struct HeapProfileDiff {
+    before: HeapFlameSummary,
+    after: HeapFlameSummary,
+}
+
+struct DiffRow<'a> {
+    kind: &'static str,
+    name: &'a str,
+    before: u64,
+    after: u64,
+    self_before: u64,
+    self_after: u64,
+}
+
+impl DiffRow<'_> {
+    fn delta(&self) -> i64 {
+        self.after as i64 - self.before as i64
+    }
+
+    fn self_delta(&self) -> i64 {
+        self.self_after as i64 - self.self_before as i64
+    }
+}
+
+fn keys<'a>(before: &'a SmallMap<String, u64>, after: &'a SmallMap<String, u64>) -> Vec<&'a str> {
+    let mut keys: Vec<&str> = before.keys().map(|k| k.as_str()).collect();
+    keys.extend(
+        after
+            .keys()
+            .filter(|k| !before.contains_key(*k))
+            .map(|k| k.as_str()),
+    );
+    keys
+}
+
+fn get(map: &SmallMap<String, u64>, key: &str) -> u64 {
+    map.get(key).copied().unwrap_or_default()
+}
+
+/// Largest first: by bytes in the new profile (the retained bytes for
+/// `heap-flame-retained` profiles), then by bytes in the old profile.
+fn sort_rows(rows: &mut [DiffRow]) {
+    rows.sort_by(|a, b| {
+        b.after
+            .cmp(&a.after)
+            .then_with(|| b.before.cmp(&a.before))
+            .then_with(|| a.name.cmp(b.name))
+    });
+}
+
+impl HeapProfileDiff {
+    /// Compare two heap profiles, given as the text written by
+    /// `heap-flame-allocated` or `heap-flame-retained` profile mode.
+    pub fn new(before: &str, after: &str) -> anyhow::Result<HeapProfileDiff> {
+        Ok(HeapProfileDiff {
+            before: HeapFlameSummary::parse(before)?,
+            after: HeapFlameSummary::parse(after)?,
+        })
+    }
+
+    fn rows(&self) -> Vec<DiffRow<'_>> {
+        let (before, after) = (&self.before, &self.after);
+        let mut rows = vec![
+            DiffRow {
+                kind: "total",
+                name: "TOTALS",
+                before: before.total,
+                after: after.total,
+                self_before: before.total,
+                self_after: after.total,
+            },
+            DiffRow {
+                kind: "total",
+                name: "UNUSED CAPACITY",
+                before: before.unused_capacity,
+                after: after.unused_capacity,
+                self_before: before.unused_capacity,
+                self_after: after.unused_capacity,
+            },
+        ];
+
+        let mut functions = keys(&before.total_bytes, &after.total_bytes)
+            .into_iter()
+            .map(|name| DiffRow {
+                kind: "function",
+                name,
+                before: get(&before.total_bytes, name),
+                after: get(&after.total_bytes, name),
+                self_before: get(&before.self_bytes, name),
+                self_after: get(&after.self_bytes, name),
+            })
+            .collect::<Vec<_>>();
+        sort_rows(&mut functions);
+
+        let mut types = keys(&before.type_bytes, &after.type_bytes)
+            .into_iter()
+            .map(|name| DiffRow {
+                kind: "type",
+                name,
+                before: get(&before.type_bytes, name),
+                after: get(&after.type_bytes, name),
+                self_before: get(&before.type_bytes, name),
+                self_after: get(&after.type_bytes, name),
+            })
+            .collect::<Vec<_>>();
+        sort_rows(&mut types);
+
+        rows.extend(functions);
+        rows.extend(types);
+        rows
+    }
+
+    /// Write the difference in CSV format.
+    ///
+    /// Rows are totals, then functions, then allocated types,
+    /// each group sorted by retained bytes, largest first.
+    /// For functions, `Before`/`After` include callees and `Self*` columns do not.
+    pub fn gen_csv(&self) -> String {
+        let mut csv = CsvWriter::new([
+            "Kind",
+            "Name",
+            "Before",
+            "After",
+            "Delta",
+            "SelfBefore",
+            "SelfAfter",
+            "SelfDelta",
+        ]);
+        for row in self.rows() {
+            csv.write_value(row.kind);
+            csv.write_value(row.name);
+            csv.write_value(row.before);
+            csv.write_value(row.after);
+            csv.write_value(row.delta());
+            csv.write_value(row.self_before);
+            csv.write_value(row.self_after);
+            csv.write_value(row.self_delta());
+            csv.finish_row();
+        }
+        csv.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::environment::Globals;
+    use crate::environment::Module;
+    use crate::eval::runtime::profile::heap_diff::HeapProfileDiff;
+    use crate::eval::Evaluator;
+    use crate::eval::ProfileMode;
+    use crate::syntax::AstModule;
+    use crate::syntax::Dialect;
+
+    #[test]
+    fn test_diff() {
+        let before = "\
+list 10
+f;list 20
+f;g;dict 30
+unused_capacity 5
+";
+        let after = "\
+list 10
+f;list 20
+f;g;dict 100
+f;g;g;list 8
+h;str 4
+unused_capacity 7
+";
+        let diff = HeapProfileDiff::new(before, after).unwrap();
+        assert_eq!(
+            "\
+Kind,Name,Before,After,Delta,SelfBefore,SelfAfter,SelfDelta
+\"total\",\"TOTALS\",65,149,84,65,149,84
+\"total\",\"UNUSED CAPACITY\",5,7,2,5,7,2
+\"function\",\"(root)\",60,142,82,10,10,0
+\"function\",\"f\",50,128,78,20,20,0
+\"function\",\"g\",30,108,78,30,108,78
+\"function\",\"h\",0,4,4,0,4,4
+\"type\",\"dict\",30,100,70,30,100,70
+\"type\",\"list\",30,38,8,30,38,8
+\"type\",\"str\",0,4,4,0,4,4
+",
+            diff.gen_csv()
+        );
+    }
+
+    #[test]
+    fn test_diff_sorted_by_retained() {
+        let before = "a;list 100\nb;list 10\n";
+        let after = "b;list 20\nc;list 15\n";
+        let diff = HeapProfileDiff::new(before, after).unwrap();
+        let functions: Vec<&str> = diff
+            .rows()
+            .into_iter()
+            .filter(|r| r.kind == "function")
+            .map(|r| r.name)
+            .collect();
+        assert_eq!(vec!["(root)", "b", "c", "a"], functions);
+    }
+
+    #[test]
+    fn test_diff_invalid() {
+        let csv = "Function,Time(s)\n\"TOTALS\",0.000\n";
+        assert!(HeapProfileDiff::new(csv, csv).is_err());
+    }
+
+    fn heap_flame_profile(program: &str) -> String {
+        let ast = AstModule::parse("x.star", program.to_owned(), &Dialect::Extended).unwrap();
+        let globals = Globals::standard();
+        let module = Module::new();
+        let mut eval = Evaluator::new(&module);
+        eval.enable_profile(&ProfileMode::HeapFlameAllocated)
+            .unwrap();
+        eval.eval_module(ast, &globals).unwrap();
+        eval.gen_profile().unwrap().gen().unwrap()
+    }
+
+    #[test]
+    fn test_diff_profiles() {
+        let before = heap_flame_profile(
+            "\
+def f():
+    return [1, 2]
+_ignore = f()
+",
+        );
+        let after = heap_flame_profile(
+            "\
+def f():
+    return [[1, 2] for _ in range(10)]
+_ignore = f()
+",
+        );
+        let csv = HeapProfileDiff::new(&before, &after).unwrap().gen_csv();
+        let f = csv
+            .lines()
+            .find(|l| l.starts_with("\"function\",\"x.star.f\","))
+            .unwrap_or_else(|| panic!("{}", csv));
+        let delta: i64 = f.split(',').nth(4).unwrap().parse().unwrap();
+        assert!(delta > 0, "{}", csv);
+    }
+}
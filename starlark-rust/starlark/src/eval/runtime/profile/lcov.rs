@@ -0,0 +1,211 @@
+/*
+ * Copyright 2019 The Starlark in Rust Authors.
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Code coverage in the [LCOV](https://github.com/linux-test-project/lcov) tracefile format,
+//! which is understood by `genhtml` and most coverage viewers.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Coverage of a single file. Lines are 1-based, as in LCOV.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct LcovFile {
+    /// Line to execution count, for every line where a statement starts.
+    lines: BTreeMap<u32, u64>,
+    /// Line and name of a function to the number of times it was called.
+    functions: BTreeMap<(u32, String), u64>,
+    /// Line, block and branch to the number of times the branch was taken,
+    /// or `None` if the condition was never evaluated.
+    branches: BTreeMap<(u32, u32, u32), Option<u64>>,
+}
+
+/// Coverage of all the files which were executed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct LcovData {
+    files: BTreeMap<String, LcovFile>,
+}
+
+impl LcovFile {
+    /// Record a statement starting at the line. Multiple statements on the same line
+    /// report the count of the most executed one.
+    pub(crate) fn add_line(&mut self, line: u32, count: u64) {
+        let v = self.lines.entry(line).or_default();
+        *v = (*v).max(count);
+    }
+
+    pub(crate) fn add_function(&mut self, line: u32, name: &str, count: u64) {
+        *self.functions.entry((line, name.to_owned())).or_default() += count;
+    }
+
+    pub(crate) fn add_branch(&mut self, line: u32, block: u32, branch: u32, taken: Option<u64>) {
+        let v = self.branches.entry((line, block, branch)).or_default();
+        if let Some(taken) = taken {
+            *v = Some(v.unwrap_or_default() + taken);
+        }
+    }
+
+    fn merge(&mut self, other: &LcovFile) {
+        for (line, count) in &other.lines {
+            *self.lines.entry(*line).or_default() += count;
+        }
+        for ((line, name), count) in &other.functions {
+            *self.functions.entry((*line, name.clone())).or_default() += count;
+        }
+        for ((line, block, branch), taken) in &other.branches {
+            self.add_branch(*line, *block, *branch, *taken);
+        }
+    }
+
+    fn write(&self, filename: &str, out: &mut String) {
+        writeln!(out, "TN:").unwrap();
+        writeln!(out, "SF:{}", filename).unwrap();
+
+        for (line, name) in self.functions.keys() {
+            writeln!(out, "FN:{},{}", line, name).unwrap();
+        }
+        for ((_, name), count) in &self.functions {
+            writeln!(out, "FNDA:{},{}", count, name).unwrap();
+        }
+        writeln!(out, "FNF:{}", self.functions.len()).unwrap();
+        writeln!(
+            out,
+            "FNH:{}",
+            self.functions.values().filter(|c| **c != 0).count()
+        )
+        .unwrap();
+
+        for ((line, block, branch), taken) in &self.branches {
+            match taken {
+                Some(taken) => writeln!(out, "BRDA:{},{},{},{}", line, block, branch, taken),
+                None => writeln!(out, "BRDA:{},{},{},-", line, block, branch),
+            }
+            .unwrap();
+        }
+        writeln!(out, "BRF:{}", self.branches.len()).unwrap();
+        writeln!(
+            out,
+            "BRH:{}",
+            self.branches
+                .values()
+                .filter(|t| t.unwrap_or_default() != 0)
+                .count()
+        )
+        .unwrap();
+
+        for (line, count) in &self.lines {
+            writeln!(out, "DA:{},{}", line, count).unwrap();
+        }
+        writeln!(out, "LF:{}", self.lines.len()).unwrap();
+        writeln!(
+            out,
+            "LH:{}",
+            self.lines.values().filter(|c| **c != 0).count()
+        )
+        .unwrap();
+
+        writeln!(out, "end_of_record").unwrap();
+    }
+}
+
+impl LcovData {
+    /// Get or create the coverage of a file.
+    pub(crate) fn file(&mut self, filename: &str) -> &mut LcovFile {
+        if !self.files.contains_key(filename) {
+            self.files.insert(filename.to_owned(), LcovFile::default());
+        }
+        self.files.get_mut(filename).unwrap()
+    }
+
+    pub(crate) fn write(&self) -> String {
+        let mut out = String::new();
+        for (filename, file) in &self.files {
+            file.write(filename, &mut out);
+        }
+        out
+    }
+
+    /// Merge coverage, e.g. of several modules which executed the same files.
+    pub(crate) fn merge<'a>(datas: impl IntoIterator<Item = &'a LcovData>) -> LcovData {
+        let mut result = LcovData::default();
+        for data in datas {
+            for (filename, file) in &data.files {
+                result.file(filename).merge(file);
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::eval::runtime::profile::lcov::LcovData;
+
+    #[test]
+    fn test_write() {
+        let mut data = LcovData::default();
+        let file = data.file("a.bzl");
+        file.add_line(1, 1);
+        file.add_line(2, 0);
+        file.add_line(3, 2);
+        file.add_function(1, "f", 2);
+        file.add_branch(3, 0, 0, Some(2));
+        file.add_branch(3, 0, 1, Some(0));
+        assert_eq!(
+            "\
+TN:
+SF:a.bzl
+FN:1,f
+FNDA:2,f
+FNF:1
+FNH:1
+BRDA:3,0,0,2
+BRDA:3,0,1,0
+BRF:2
+BRH:1
+DA:1,1
+DA:2,0
+DA:3,2
+LF:3
+LH:2
+end_of_record
+",
+            data.write()
+        );
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut a = LcovData::default();
+        a.file("a.bzl").add_line(1, 1);
+        a.file("a.bzl").add_line(2, 0);
+        a.file("a.bzl").add_branch(2, 0, 0, None);
+        let mut b = LcovData::default();
+        b.file("a.bzl").add_line(2, 3);
+        b.file("a.bzl").add_branch(2, 0, 0, Some(3));
+        b.file("b.bzl").add_line(1, 1);
+
+        let c = LcovData::merge([&a, &b]);
+
+        let mut expected = LcovData::default();
+        expected.file("a.bzl").add_line(1, 1);
+        expected.file("a.bzl").add_line(2, 3);
+        expected.file("a.bzl").add_branch(2, 0, 0, Some(3));
+        expected.file("b.bzl").add_line(1, 1);
+
+        assert_eq!(expected, c);
+    }
+}
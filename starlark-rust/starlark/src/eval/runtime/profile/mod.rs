@@ -26,6 +26,8 @@ pub(crate) mod csv;
 pub(crate) mod data;
 pub(crate) mod flamegraph;
 pub(crate) mod heap;
+pub(crate) mod heap_diff;
+pub(crate) mod lcov;
 pub(crate) mod or_instrumentation;
 pub(crate) mod stmt;
 pub(crate) mod time_flame;
@@ -48,7 +50,8 @@ pub enum ProfileMode {
     HeapFlameRetained,
     /// The statement profile mode provides information about time spent in each statement.
     Statement,
-    /// Code coverage.
+    /// Statement and branch coverage, written in
+    /// [LCOV](https://github.com/linux-test-project/lcov) format.
     Coverage,
     /// The bytecode profile mode provides information about bytecode instructions.
     Bytecode,
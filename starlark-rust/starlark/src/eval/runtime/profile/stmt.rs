@@ -22,6 +22,13 @@ use std::iter;
 use std::time::Instant;
 
 use dupe::Dupe;
+use starlark_syntax::syntax::ast::AstLiteral;
+use starlark_syntax::syntax::ast::AstStmt;
+use starlark_syntax::syntax::ast::DefP;
+use starlark_syntax::syntax::ast::ExprP;
+use starlark_syntax::syntax::ast::ForP;
+use starlark_syntax::syntax::ast::StmtP;
+use starlark_syntax::syntax::module::AstModuleFields;
 
 use crate::codemap::CodeMap;
 use crate::codemap::CodeMapId;
@@ -31,8 +38,13 @@ use crate::codemap::ResolvedFileSpan;
 use crate::codemap::Span;
 use crate::eval::runtime::profile::csv::CsvWriter;
 use crate::eval::runtime::profile::data::ProfileData;
+use crate::eval::runtime::profile::data::ProfileDataImpl;
+use crate::eval::runtime::profile::lcov::LcovData;
+use crate::eval::runtime::profile::lcov::LcovFile;
 use crate::eval::runtime::small_duration::SmallDuration;
 use crate::eval::ProfileMode;
+use crate::syntax::AstModule;
+use crate::syntax::Dialect;
 
 #[derive(Debug, thiserror::Error)]
 enum StmtProfileError {
@@ -48,6 +60,9 @@ pub(crate) struct StmtProfile(Option<Box<StmtProfileData>>);
 #[derive(Clone)]
 struct StmtProfileData {
     files: HashMap<CodeMapId, CodeMap>,
+    /// Dialects of the modules evaluated by this evaluator,
+    /// used to re-parse them for the LCOV report.
+    dialects: HashMap<CodeMapId, Dialect>,
     stmts: HashMap<(CodeMapId, Span), (usize, SmallDuration)>,
     next_file: CodeMapId,
     last_span: (CodeMapId, Span),
@@ -58,6 +73,7 @@ impl StmtProfileData {
     fn new() -> Self {
         StmtProfileData {
             files: HashMap::new(),
+            dialects: HashMap::new(),
             stmts: HashMap::new(),
             next_file: CodeMapId::EMPTY,
             last_span: (CodeMapId::EMPTY, Span::default()),
@@ -165,6 +181,138 @@ impl StmtProfileData {
             })
             .collect()
     }
+
+    fn lcov(&self, now: Instant) -> LcovData {
+        // Account for the statement which is still running, as in `write_to_string`.
+        let mut data = self.clone();
+        data.add_last(now);
+
+        let mut hits: HashMap<CodeMapId, HashMap<Span, usize>> = HashMap::new();
+        for ((file, span), (count, _)) in data.stmts {
+            if file != CodeMapId::EMPTY {
+                hits.entry(file).or_default().insert(span, count);
+            }
+        }
+
+        let mut lcov = LcovData::default();
+        for (file, hits) in hits {
+            let codemap = &data.files[&file];
+            let lcov_file = lcov.file(codemap.filename());
+            // We only record the statements which were executed, so re-parse the file
+            // to find the ones which never were. We can only do that for the modules
+            // evaluated here, for others (e.g. loaded modules whose functions were called)
+            // we don't know the dialect they were parsed with.
+            let ast = data.dialects.get(&file).and_then(|dialect| {
+                AstModule::parse(codemap.filename(), codemap.source().to_owned(), dialect).ok()
+            });
+            match ast {
+                Some(ast) => {
+                    let mut visitor = LcovVisitor {
+                        codemap,
+                        hits: &hits,
+                        file: lcov_file,
+                        next_block: 0,
+                    };
+                    visitor.visit(ast.statement());
+                }
+                None => {
+                    for (span, count) in &hits {
+                        lcov_file.add_line(lcov_line(codemap, *span), *count as u64);
+                    }
+                }
+            }
+        }
+        lcov
+    }
+}
+
+/// LCOV lines are 1-based.
+fn lcov_line(codemap: &CodeMap, span: Span) -> u32 {
+    codemap.find_line(span.begin()) as u32 + 1
+}
+
+/// Walk the AST of a file, recording statements, functions and `if` branches.
+struct LcovVisitor<'a> {
+    codemap: &'a CodeMap,
+    hits: &'a HashMap<Span, usize>,
+    file: &'a mut LcovFile,
+    next_block: u32,
+}
+
+impl<'a> LcovVisitor<'a> {
+    /// Statements which reach `before_stmt` when executed.
+    fn is_executable(stmt: &AstStmt) -> bool {
+        match &stmt.node {
+            StmtP::Statements(_) | StmtP::Pass | StmtP::Load(_) => false,
+            // Docstrings.
+            StmtP::Expression(e) => !matches!(e.node, ExprP::Literal(AstLiteral::String(_))),
+            _ => true,
+        }
+    }
+
+    fn count(&self, stmt: &AstStmt) -> u64 {
+        self.hits.get(&stmt.span).copied().unwrap_or_default() as u64
+    }
+
+    /// Number of times the block was entered, if it has any executable statement.
+    fn block_count(&self, stmt: &AstStmt) -> Option<u64> {
+        match &stmt.node {
+            StmtP::Statements(xs) => xs.iter().find_map(|x| self.block_count(x)),
+            _ if Self::is_executable(stmt) => Some(self.count(stmt)),
+            _ => None,
+        }
+    }
+
+    fn add_branches(&mut self, stmt: &AstStmt, then_block: &AstStmt, else_block: Option<&AstStmt>) {
+        let count = self.count(stmt);
+        let (then_count, else_count) = match (
+            self.block_count(then_block),
+            else_block.and_then(|e| self.block_count(e)),
+        ) {
+            (Some(t), _) => (t, count.saturating_sub(t)),
+            (None, Some(f)) => (count.saturating_sub(f), f),
+            (None, None) => return,
+        };
+        let line = lcov_line(self.codemap, stmt.span);
+        let block = self.next_block;
+        self.next_block += 1;
+        let taken = |c| if count == 0 { None } else { Some(c) };
+        self.file.add_branch(line, block, 0, taken(then_count));
+        self.file.add_branch(line, block, 1, taken(else_count));
+    }
+
+    fn visit(&mut self, stmt: &AstStmt) {
+        if Self::is_executable(stmt) {
+            self.file
+                .add_line(lcov_line(self.codemap, stmt.span), self.count(stmt));
+        }
+        match &stmt.node {
+            StmtP::Statements(xs) => {
+                for x in xs {
+                    self.visit(x);
+                }
+            }
+            StmtP::If(_, then_block) => {
+                self.add_branches(stmt, then_block, None);
+                self.visit(then_block);
+            }
+            StmtP::IfElse(_, then_else) => {
+                let (then_block, else_block) = &**then_else;
+                self.add_branches(stmt, then_block, Some(else_block));
+                self.visit(then_block);
+                self.visit(else_block);
+            }
+            StmtP::For(ForP { body, .. }) => self.visit(body),
+            StmtP::Def(DefP { name, body, .. }) => {
+                if let Some(count) = self.block_count(body) {
+                    self.file
+                        .add_function(lcov_line(self.codemap, stmt.span), &name.ident, count);
+                }
+                self.visit(body);
+            }
+            _ => {}
+        }
+    }
 }
 
 impl StmtProfile {
@@ -176,6 +324,13 @@ impl StmtProfile {
         self.0 = Some(Box::new(StmtProfileData::new()))
     }
 
+    /// Record the dialect a module evaluated with this profile enabled was parsed with.
+    pub(crate) fn add_module(&mut self, codemap: &CodeMap, dialect: &Dialect) {
+        if let Some(data) = &mut self.0 {
+            data.dialects.insert(codemap.id(), dialect.clone());
+        }
+    }
+
     pub(crate) fn before_stmt(&mut self, span: FileSpanRef) {
         if let Some(data) = &mut self.0 {
             data.before_stmt(span.span, span.file)
@@ -194,6 +349,17 @@ impl StmtProfile {
         }
     }
 
+    pub(crate) fn gen_lcov(&self) -> anyhow::Result<ProfileData> {
+        let now = Instant::now();
+        match &self.0 {
+            Some(data) => Ok(ProfileData {
+                profile_mode: ProfileMode::Coverage,
+                profile: ProfileDataImpl::Coverage(data.lcov(now)),
+            }),
+            None => Err(StmtProfileError::NotEnabled.into()),
+        }
+    }
+
     pub(crate) fn coverage(&self) -> anyhow::Result<HashSet<ResolvedFileSpan>> {
         Ok(self
             .0
@@ -205,12 +371,15 @@ impl StmtProfile {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
 
     use crate::assert::test_functions;
+    use crate::environment::Globals;
     use crate::environment::GlobalsBuilder;
     use crate::environment::Module;
     use crate::eval::Evaluator;
     use crate::eval::ProfileMode;
+    use crate::eval::ReturnFileLoader;
     use crate::syntax::AstModule;
     use crate::syntax::Dialect;
 
@@ -255,4 +424,138 @@ xx(*[2])
             coverage
         );
     }
+
+    #[test]
+    fn test_coverage_lcov() {
+        let module = Module::new();
+        let mut eval = Evaluator::new(&module);
+
+        let module = AstModule::parse(
+            "cov.star",
+            r#"
+def f(x):
+    """Docstring."""
+    if x:
+        return 1
+    return 2
+
+def g():
+    pass
+
+def h(x):
+    if x:
+        pass
+    else:
+        return 3
+
+for x in [True, False]:
+    f(x)
+h(True)
+if h(True):
+    g()
+"#
+            .to_owned(),
+            &Dialect::Extended,
+        )
+        .unwrap();
+        eval.enable_profile(&ProfileMode::Coverage).unwrap();
+        eval.eval_module(module, &Globals::standard()).unwrap();
+
+        assert_eq!(
+            "\
+TN:
+SF:cov.star
+FN:2,f
+FN:11,h
+FNDA:2,f
+FNDA:2,h
+FNF:2
+FNH:2
+BRDA:4,0,0,1
+BRDA:4,0,1,1
+BRDA:12,1,0,2
+BRDA:12,1,1,0
+BRDA:20,2,0,0
+BRDA:20,2,1,1
+BRF:6
+BRH:4
+DA:2,1
+DA:4,2
+DA:5,1
+DA:6,1
+DA:8,1
+DA:11,1
+DA:12,2
+DA:15,0
+DA:17,1
+DA:18,2
+DA:19,1
+DA:20,1
+DA:21,0
+LF:13
+LH:11
+end_of_record
+",
+            eval.gen_profile().unwrap().gen().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_coverage_lcov_loaded_module() {
+        let lib = Module::new();
+        {
+            let mut eval = Evaluator::new(&lib);
+            let ast = AstModule::parse(
+                "lib.star",
+                r#"
+def f(x):
+    if x:
+        return 1
+    return 2
+"#
+                .to_owned(),
+                &Dialect::Extended,
+            )
+            .unwrap();
+            eval.eval_module(ast, &Globals::standard()).unwrap();
+        }
+        let lib = lib.freeze().unwrap();
+
+        let modules = HashMap::from([("lib.star", &lib)]);
+        let loader = ReturnFileLoader { modules: &modules };
+        let module = Module::new();
+        let mut eval = Evaluator::new(&module);
+        eval.set_loader(&loader);
+        eval.enable_profile(&ProfileMode::Coverage).unwrap();
+        let ast = AstModule::parse(
+            "main.star",
+            "load('lib.star', 'f')\nf(True)\n".to_owned(),
+            &Dialect::Standard,
+        )
+        .unwrap();
+        eval.eval_module(ast, &Globals::standard()).unwrap();
+
+        // We don't know how `lib.star` was parsed, so only the lines which were executed
+        // are reported for it.
+        let lcov = eval.gen_profile().unwrap().gen().unwrap();
+        let lib_record = lcov
+            .split("end_of_record\n")
+            .find(|x| x.contains("SF:lib.star"))
+            .unwrap();
+        assert_eq!(
+            "\
+TN:
+SF:lib.star
+FNF:0
+FNH:0
+BRF:0
+BRH:0
+DA:3,1
+DA:4,1
+LF:2
+LH:2
+",
+            lib_record
+        );
+    }
 }
@@ -18,6 +18,7 @@
 // https://github.com/rust-lang/rust-clippy/issues/11142
 #![allow(clippy::needless_borrow)]
 
+use std::borrow::Cow;
 use std::str::FromStr;
 
 use either::Either;
@@ -196,6 +197,109 @@ impl AllocFrozenValue for serde_json::Value {
     }
 }
 
+#[derive(Debug, thiserror::Error)]
+enum JsonDecodeError {
+    #[error("Unterminated comment starting at line {0} column {1}")]
+    UnterminatedComment(usize, usize),
+}
+
+/// Line and column of a byte offset, both 1-based, like `serde_json` errors.
+fn line_column(x: &str, offset: usize) -> (usize, usize) {
+    let before = &x.as_bytes()[..offset];
+    let line = before.iter().filter(|c| **c == b'\n').count() + 1;
+    let column = offset
+        - before
+            .iter()
+            .rposition(|c| *c == b'\n')
+            .map_or(0, |i| i + 1)
+        + 1;
+    (line, column)
+}
+
+/// Indices of the bytes of `x`, which has no comments, that are outside string literals.
+fn outside_strings(x: &[u8]) -> impl Iterator<Item = usize> + '_ {
+    let mut in_string = false;
+    let mut escaped = false;
+    (0..x.len()).filter(move |i| {
+        let c = x[*i];
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == b'\\' {
+                escaped = true;
+            } else if c == b'"' {
+                in_string = false;
+            }
+            false
+        } else {
+            in_string = c == b'"';
+            !in_string
+        }
+    })
+}
+
+/// Replace the comments with spaces, keeping the newlines.
+fn blank_comments(x: &str, res: &mut [u8]) -> anyhow::Result<()> {
+    let bytes = x.as_bytes();
+    let mut in_string = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if in_string => i += 1,
+            b'"' => in_string = !in_string,
+            b'/' if !in_string && matches!(bytes.get(i + 1), Some(b'/' | b'*')) => {
+                let end = if bytes[i + 1] == b'/' {
+                    x[i..].find('\n').map_or(x.len(), |n| i + n)
+                } else {
+                    match x[i + 2..].find("*/") {
+                        Some(n) => i + 2 + n + 2,
+                        None => {
+                            let (line, column) = line_column(x, i);
+                            return Err(JsonDecodeError::UnterminatedComment(line, column).into());
+                        }
+                    }
+                };
+                for c in &mut res[i..end] {
+                    if *c != b'\n' {
+                        *c = b' ';
+                    }
+                }
+                i = end;
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    Ok(())
+}
+
+/// Replace the comments and trailing commas permitted by the options with spaces,
+/// so positions in the errors reported by `serde_json` still refer to the original text.
+fn relax_json(
+    x: &str,
+    allow_comments: bool,
+    allow_trailing_commas: bool,
+) -> anyhow::Result<String> {
+    let mut res = x.as_bytes().to_vec();
+    if allow_comments {
+        blank_comments(x, &mut res)?;
+    }
+    if allow_trailing_commas {
+        let commas: Vec<usize> = outside_strings(&res).filter(|i| res[*i] == b',').collect();
+        for i in commas {
+            if matches!(
+                res[i + 1..].iter().find(|c| !c.is_ascii_whitespace()),
+                Some(b']' | b'}')
+            ) {
+                res[i] = b' ';
+            }
+        }
+    }
+    // Only ASCII bytes were replaced, and only with ASCII bytes.
+    Ok(String::from_utf8(res)?)
+}
+
 pub(crate) fn json(globals: &mut GlobalsBuilder) {
     #[starlark_module]
     fn json_members(globals: &mut GlobalsBuilder) {
@@ -203,11 +307,35 @@ pub(crate) fn json(globals: &mut GlobalsBuilder) {
             x.to_json()
         }
 
+        /// Decode a JSON string.
+        ///
+        /// With `allow_comments = True`, `//` and `/* */` comments are ignored,
+        /// and with `allow_trailing_commas = True`, a comma may follow the last
+        /// element of an array or object, as in the files some tools write.
+        ///
+        /// With `streaming = True`, `x` is a sequence of JSON values separated by
+        /// whitespace (e.g. [JSON Lines](https://jsonlines.org/)), and the result
+        /// is the list of the decoded values.
         fn decode<'v>(
             #[starlark(require = pos)] x: &str,
+            #[starlark(require = named, default = false)] allow_comments: bool,
+            #[starlark(require = named, default = false)] allow_trailing_commas: bool,
+            #[starlark(require = named, default = false)] streaming: bool,
             heap: &'v Heap,
         ) -> anyhow::Result<Value<'v>> {
-            Ok(heap.alloc(serde_json::from_str::<serde_json::Value>(x)?))
+            let x = if allow_comments || allow_trailing_commas {
+                Cow::Owned(relax_json(x, allow_comments, allow_trailing_commas)?)
+            } else {
+                Cow::Borrowed(x)
+            };
+            if streaming {
+                let values = serde_json::Deserializer::from_str(&x)
+                    .into_iter::<serde_json::Value>()
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(heap.alloc(values))
+            } else {
+                Ok(heap.alloc(serde_json::from_str::<serde_json::Value>(&x)?))
+            }
         }
     }
 
@@ -241,4 +369,55 @@ mod tests {
             "json.decode('123456789123456789123456789')",
         );
     }
+
+    #[test]
+    fn test_json_decode_relaxed() {
+        let a = Assert::new();
+        a.eq(
+            "{'a': [1, 2], 'b': '// not a comment, /* */'}",
+            r#"json.decode("""{
+    // line comment with a "quote
+    "a": [1, /* block comment */ 2,],
+    "b": "// not a comment, /* */",
+}""", allow_comments = True, allow_trailing_commas = True)"#,
+        );
+        a.eq(
+            "['a,]', 'b\\\\']",
+            r#"json.decode('["a,]", "b\\\\",]', allow_trailing_commas = True)"#,
+        );
+        a.fail(
+            "json.decode('[1, 2,]')",
+            "trailing comma at line 1 column 7",
+        );
+        a.fail(
+            "json.decode('[1] // x')",
+            "trailing characters at line 1 column 5",
+        );
+        a.fail(
+            "json.decode('[1,\\n/* x', allow_comments = True)",
+            "Unterminated comment starting at line 2 column 1",
+        );
+        a.fail(
+            "json.decode('{\\n  \"a\": 1, // x\\n  \"b\" 2\\n}', allow_comments = True)",
+            "expected `:` at line 3 column 7",
+        );
+    }
+
+    #[test]
+    fn test_json_decode_streaming() {
+        let a = Assert::new();
+        a.eq(
+            "[{'a': 1}, [2], 3, 'x']",
+            r#"json.decode('{"a": 1}\n[2]\n3 "x"\n', streaming = True)"#,
+        );
+        a.eq("[]", "json.decode(' ', streaming = True)");
+        a.eq(
+            "[[1], [2]]",
+            "json.decode('[1,] // x\\n[2]', streaming = True, allow_comments = True, allow_trailing_commas = True)",
+        );
+        a.fail(
+            "json.decode('[1]\\n[2', streaming = True)",
+            "EOF while parsing a list at line 2 column 2",
+        );
+    }
 }
@@ -31,8 +31,13 @@ pub(crate) mod internal;
 pub(crate) mod json;
 pub(crate) mod list;
 pub(crate) mod partial;
+pub(crate) mod set;
 pub(crate) mod string;
 pub(crate) mod structs;
+#[cfg(feature = "toml")]
+pub(crate) mod toml;
+#[cfg(feature = "yaml")]
+pub(crate) mod yaml;
 
 pub use extra::PrintHandler;
 
@@ -60,6 +65,8 @@ pub enum LibraryExtension {
     RecordType,
     /// Definitions to support the `enum` type, the `enum()` constructor.
     EnumType,
+    /// Definitions to support the `set` type, the `set()` constructor.
+    SetType,
     /// A function `map(f, xs)` which applies `f` to each element of `xs` and returns the result.
     Map,
     /// A function `filter(f, xs)` which applies `f` to each element of `xs` and returns those for which `f` returns `True`.
@@ -83,6 +90,14 @@ pub enum LibraryExtension {
     Breakpoint,
     /// Add a function `json()` which will generate JSON for a module.
     Json,
+    /// Add `toml.decode()`, which turns a TOML document into frozen values.
+    /// Requires the `toml` feature.
+    #[cfg(feature = "toml")]
+    Toml,
+    /// Add `yaml.decode()`, which turns a YAML document into frozen values.
+    /// Requires the `yaml` feature.
+    #[cfg(feature = "yaml")]
+    Yaml,
     /// Provides `typing.All`, `typing.Callable` etc.
     /// Usually used in conjunction with
     /// [`Dialect::enable_types`](crate::syntax::Dialect::enable_types).
@@ -101,8 +116,27 @@ impl LibraryExtension {
     pub(crate) fn all() -> &'static [Self] {
         use LibraryExtension::*;
         &[
-            StructType, RecordType, EnumType, Map, Filter, Partial, Debug, Print, Pprint, Pstr,
-            Prepr, Breakpoint, Json, Typing, Internal, CallStack,
+            StructType,
+            RecordType,
+            EnumType,
+            SetType,
+            Map,
+            Filter,
+            Partial,
+            Debug,
+            Print,
+            Pprint,
+            Pstr,
+            Prepr,
+            Breakpoint,
+            Json,
+            #[cfg(feature = "toml")]
+            Toml,
+            #[cfg(feature = "yaml")]
+            Yaml,
+            Typing,
+            Internal,
+            CallStack,
         ]
     }
 
@@ -113,6 +147,7 @@ impl LibraryExtension {
             StructType => structs::global(builder),
             RecordType => register_record(builder),
             EnumType => register_enum(builder),
+            SetType => set::register_set(builder),
             Map => extra::map(builder),
             Filter => extra::filter(builder),
             Partial => partial::partial(builder),
@@ -123,6 +158,10 @@ impl LibraryExtension {
             Prepr => extra::prepr(builder),
             Breakpoint => breakpoint::global(builder),
             Json => json::json(builder),
+            #[cfg(feature = "toml")]
+            Toml => toml::toml(builder),
+            #[cfg(feature = "yaml")]
+            Yaml => yaml::yaml(builder),
             Typing => typing::globals::register_typing(builder),
             Internal => register_internal(builder),
             CallStack => call_stack::global(builder),
@@ -0,0 +1,424 @@
+/*
+ * Copyright 2018 The Starlark in Rust Authors.
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The `set()` constructor and methods for the `set` type.
+
+use std::mem;
+
+use allocative::Allocative;
+use once_cell::sync::Lazy;
+use starlark_derive::starlark_module;
+use starlark_map::small_set::SmallSet;
+
+use crate as starlark;
+use crate::codemap::Span;
+use crate::codemap::Spanned;
+use crate::environment::GlobalsBuilder;
+use crate::environment::MethodsBuilder;
+use crate::typing::error::TypingOrInternalError;
+use crate::typing::function::TyCustomFunctionImpl;
+use crate::typing::Arg;
+use crate::typing::Param;
+use crate::typing::Ty;
+use crate::typing::TyFunction;
+use crate::typing::TypingOracleCtx;
+use crate::values::function::SpecialBuiltinFunction;
+use crate::values::none::NoneType;
+use crate::values::set::value::FrozenSet;
+use crate::values::set::Set;
+use crate::values::set::SetMut;
+use crate::values::set::SetRef;
+use crate::values::tuple::UnpackTuple;
+use crate::values::typing::StarlarkIter;
+use crate::values::Heap;
+use crate::values::Value;
+use crate::values::ValueOfUnchecked;
+
+/// Collect the elements of an iterable into a set.
+fn iterable_to_set<'v>(iterable: Value<'v>, heap: &'v Heap) -> starlark::Result<Set<'v>> {
+    if let Some(set) = SetRef::from_value(iterable) {
+        return Ok((*set).clone());
+    }
+    let it = iterable.iterate(heap)?;
+    let mut content = SmallSet::with_capacity(it.size_hint().0);
+    for x in it {
+        content.insert_hashed(x.get_hashed()?);
+    }
+    Ok(Set::new(content))
+}
+
+#[derive(Allocative, Hash, Eq, PartialEq, Ord, PartialOrd, Clone, Debug)]
+struct SetType;
+
+impl TyCustomFunctionImpl for SetType {
+    fn has_type_attr(&self) -> bool {
+        true
+    }
+
+    fn validate_call(
+        &self,
+        span: Span,
+        args: &[Spanned<Arg>],
+        oracle: TypingOracleCtx,
+    ) -> Result<Ty, TypingOrInternalError> {
+        static SET: Lazy<TyFunction> = Lazy::new(|| {
+            TyFunction::new_with_type_attr(
+                vec![Param::pos_only(Ty::iter(Ty::any())).optional()],
+                Ty::any_set(),
+                Ty::any_set(),
+            )
+        });
+
+        oracle.validate_fn_call(span, &SET, args)?;
+
+        if let Some(arg) = args.first() {
+            // This is infallible after the check above.
+            if let Arg::Pos(arg_ty) = &arg.node {
+                // This is also infallible.
+                let item = oracle.iter_item(Spanned { span, node: arg_ty })?;
+                return Ok(Ty::set(item));
+            }
+        }
+
+        Ok(Ty::any_set())
+    }
+}
+
+#[starlark_module]
+pub(crate) fn register_set(globals: &mut GlobalsBuilder) {
+    /// `set(x)` returns a new set containing the unique elements of the
+    /// iterable `x`, in the order they first appear.
+    ///
+    /// With no argument, `set()` returns a new empty set.
+    /// Elements must be hashable.
+    ///
+    /// ```
+    /// # starlark::assert::all_true(r#"
+    /// set() == set([])
+    /// list(set([3, 1, 3, 2])) == [3, 1, 2]
+    /// len(set("abc".elems())) == 3
+    /// # "#);
+    /// ```
+    #[starlark(
+    as_type = FrozenSet,
+    speculative_exec_safe,
+    special_builtin_function = SpecialBuiltinFunction::Set,
+    ty_custom_function = SetType,
+    )]
+    fn set<'v>(
+        #[starlark(require = pos)] a: Option<ValueOfUnchecked<'v, StarlarkIter<Value<'v>>>>,
+        heap: &'v Heap,
+    ) -> starlark::Result<Set<'v>> {
+        match a {
+            Some(a) => iterable_to_set(a.get(), heap),
+            None => Ok(Set::default()),
+        }
+    }
+}
+
+#[starlark_module]
+pub(crate) fn set_methods(builder: &mut MethodsBuilder) {
+    /// `S.add(x)` adds `x` to the set S, and returns `None`.
+    /// Adding an element which is already present does nothing.
+    ///
+    /// `add` fails if the set is frozen or has active iterators.
+    ///
+    /// ```
+    /// # starlark::assert::is_true(r#"
+    /// x = set([1])
+    /// x.add(2)
+    /// x.add(1)
+    /// list(x) == [1, 2]
+    /// # "#);
+    /// ```
+    fn add<'v>(
+        this: Value<'v>,
+        #[starlark(require = pos)] el: Value<'v>,
+    ) -> starlark::Result<NoneType> {
+        let el = el.get_hashed()?;
+        let mut this = SetMut::from_value(this)?;
+        this.insert_hashed(el);
+        Ok(NoneType)
+    }
+
+    /// `S.clear()` removes all the elements of the set S and returns `None`.
+    /// It fails if the set is frozen or if there are active iterators.
+    ///
+    /// ```
+    /// # starlark::assert::is_true(r#"
+    /// x = set([1, 2])
+    /// x.clear()
+    /// x == set()
+    /// # "#);
+    /// ```
+    fn clear(this: Value) -> anyhow::Result<NoneType> {
+        let mut this = SetMut::from_value(this)?;
+        this.clear();
+        Ok(NoneType)
+    }
+
+    /// `S.difference(*others)` returns a new set with the elements of S
+    /// which are not in any of the iterables `others`.
+    ///
+    /// ```
+    /// # starlark::assert::is_true(r#"
+    /// set([1, 2, 3]).difference([2], set([3, 4])) == set([1])
+    /// # "#);
+    /// ```
+    fn difference<'v>(
+        this: SetRef<'v>,
+        #[starlark(args)] others: UnpackTuple<Value<'v>>,
+        heap: &'v Heap,
+    ) -> starlark::Result<Set<'v>> {
+        let mut res = (*this).clone();
+        for other in others.items {
+            res = res.difference(&iterable_to_set(other, heap)?);
+        }
+        Ok(res)
+    }
+
+    /// `S.discard(x)` removes `x` from the set S if it is present, and returns `None`.
+    ///
+    /// `discard` fails if the set is frozen or has active iterators.
+    ///
+    /// ```
+    /// # starlark::assert::is_true(r#"
+    /// x = set([1, 2])
+    /// x.discard(2)
+    /// x.discard(3)
+    /// x == set([1])
+    /// # "#);
+    /// ```
+    fn discard<'v>(
+        this: Value<'v>,
+        #[starlark(require = pos)] el: Value<'v>,
+    ) -> starlark::Result<NoneType> {
+        let el = el.get_hashed()?;
+        let mut this = SetMut::from_value(this)?;
+        this.remove_hashed(el);
+        Ok(NoneType)
+    }
+
+    /// `S.intersection(*others)` returns a new set with the elements of S
+    /// which are also in all of the iterables `others`.
+    ///
+    /// ```
+    /// # starlark::assert::is_true(r#"
+    /// set([1, 2, 3]).intersection([3, 2, 5], set([2, 3])) == set([2, 3])
+    /// # "#);
+    /// ```
+    fn intersection<'v>(
+        this: SetRef<'v>,
+        #[starlark(args)] others: UnpackTuple<Value<'v>>,
+        heap: &'v Heap,
+    ) -> starlark::Result<Set<'v>> {
+        let mut res = (*this).clone();
+        for other in others.items {
+            res = res.intersection(&iterable_to_set(other, heap)?);
+        }
+        Ok(res)
+    }
+
+    /// `S.issubset(x)` returns `True` if every element of S is in the iterable `x`.
+    ///
+    /// ```
+    /// # starlark::assert::all_true(r#"
+    /// set([1, 2]).issubset([1, 2, 3])
+    /// not set([1, 4]).issubset(set([1, 2, 3]))
+    /// # "#);
+    /// ```
+    fn issubset<'v>(
+        this: SetRef<'v>,
+        #[starlark(require = pos)] other: Value<'v>,
+        heap: &'v Heap,
+    ) -> starlark::Result<bool> {
+        Ok(this.is_subset(&iterable_to_set(other, heap)?))
+    }
+
+    /// `S.issuperset(x)` returns `True` if every element of the iterable `x` is in S.
+    ///
+    /// ```
+    /// # starlark::assert::all_true(r#"
+    /// set([1, 2, 3]).issuperset([1, 2])
+    /// not set([1, 2, 3]).issuperset(set([1, 4]))
+    /// # "#);
+    /// ```
+    fn issuperset<'v>(
+        this: SetRef<'v>,
+        #[starlark(require = pos)] other: Value<'v>,
+        heap: &'v Heap,
+    ) -> starlark::Result<bool> {
+        Ok(iterable_to_set(other, heap)?.is_subset(&this))
+    }
+
+    /// `S.pop()` removes and returns the first element of the set S.
+    ///
+    /// `pop` fails if the set is empty, frozen, or has active iterators.
+    ///
+    /// ```
+    /// # starlark::assert::is_true(r#"
+    /// x = set([3, 1])
+    /// # (
+    /// x.pop() == 3
+    /// # and
+    /// x == set([1])
+    /// # )"#);
+    /// ```
+    fn pop<'v>(this: Value<'v>) -> anyhow::Result<Value<'v>> {
+        let mut this = SetMut::from_value(this)?;
+        match this.pop_first() {
+            Some(x) => Ok(x),
+            None => Err(anyhow::anyhow!("Cannot .pop() on an empty set")),
+        }
+    }
+
+    /// `S.remove(x)` removes `x` from the set S, and returns `None`.
+    ///
+    /// `remove` fails if `x` is not in the set, or if the set is frozen
+    /// or has active iterators.
+    ///
+    /// ```
+    /// # starlark::assert::is_true(r#"
+    /// x = set([1, 2])
+    /// x.remove(2)
+    /// x == set([1])
+    /// # "#);
+    /// # starlark::assert::fail(r#"
+    /// set([1]).remove(2) # error: not found
+    /// # "#, "not found");
+    /// ```
+    fn remove<'v>(
+        this: Value<'v>,
+        #[starlark(require = pos)] el: Value<'v>,
+    ) -> starlark::Result<NoneType> {
+        let hashed = el.get_hashed()?;
+        let mut me = SetMut::from_value(this)?;
+        if me.remove_hashed(hashed) {
+            Ok(NoneType)
+        } else {
+            mem::drop(me);
+            Err(anyhow::anyhow!(
+                "Element `{}` not found in set `{}`",
+                el.to_repr(),
+                this.to_repr()
+            )
+            .into())
+        }
+    }
+
+    /// `S.union(*others)` returns a new set with the elements of S followed by
+    /// the elements of the iterables `others` which are not already present.
+    ///
+    /// ```
+    /// # starlark::assert::is_true(r#"
+    /// list(set([1, 2]).union([2, 3], set([4]))) == [1, 2, 3, 4]
+    /// # "#);
+    /// ```
+    fn union<'v>(
+        this: SetRef<'v>,
+        #[starlark(args)] others: UnpackTuple<Value<'v>>,
+        heap: &'v Heap,
+    ) -> starlark::Result<Set<'v>> {
+        let mut res = (*this).clone();
+        for other in others.items {
+            for x in other.iterate(heap)? {
+                res.insert_hashed(x.get_hashed()?);
+            }
+        }
+        Ok(res)
+    }
+
+    /// `S.update(*others)` adds the elements of the iterables `others` to the set S,
+    /// and returns `None`.
+    ///
+    /// `update` fails if the set is frozen or has active iterators.
+    ///
+    /// ```
+    /// # starlark::assert::is_true(r#"
+    /// x = set([1])
+    /// x.update([2, 1], set([3]))
+    /// list(x) == [1, 2, 3]
+    /// # "#);
+    /// ```
+    fn update<'v>(
+        this: Value<'v>,
+        #[starlark(args)] others: UnpackTuple<Value<'v>>,
+        heap: &'v Heap,
+    ) -> starlark::Result<NoneType> {
+        // Collect first: `x.update(x)` would otherwise iterate `x` while holding it for mutation.
+        let mut elems = Vec::new();
+        for other in others.items {
+            for x in other.iterate(heap)? {
+                elems.push(x.get_hashed()?);
+            }
+        }
+        let mut this = SetMut::from_value(this)?;
+        for x in elems {
+            this.insert_hashed(x);
+        }
+        Ok(NoneType)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::assert;
+
+    #[test]
+    fn test_set_methods() {
+        assert::is_true(
+            r#"
+s = set([1, 2])
+s.update(s)
+s == set([1, 2])
+"#,
+        );
+        assert::all_true(
+            r#"
+set([1, 2]).union() == set([1, 2])
+set([1, 2, 3]).difference() == set([1, 2, 3])
+type(set()) == "set"
+"#,
+        );
+    }
+
+    #[test]
+    fn test_set_unhashable() {
+        assert::fail("set([[1]])", "not hashable");
+        assert::fail("set().add({})", "not hashable");
+    }
+
+    #[test]
+    fn test_set_type() {
+        assert::pass(
+            r#"
+def f(x: set[str]) -> set[str]:
+    return x | set(["b"])
+f(set(["a"]))
+"#,
+        );
+        assert::fail(
+            r#"
+def f(x: set[str]):
+    pass
+f(set([1]))
+"#,
+            "does not match the type annotation",
+        );
+        assert::is_true("isinstance(set([1]), set)");
+    }
+}
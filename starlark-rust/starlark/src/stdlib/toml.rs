@@ -0,0 +1,111 @@
+/*
+ * Copyright 2019 The Starlark in Rust Authors.
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The `toml` extension.
+
+use starlark_derive::starlark_module;
+
+use crate as starlark;
+use crate::environment::GlobalsBuilder;
+use crate::eval::Evaluator;
+use crate::values::dict::AllocDict;
+use crate::values::list::AllocList;
+use crate::values::FrozenHeap;
+use crate::values::FrozenValue;
+use crate::values::Value;
+
+fn alloc_toml(x: &toml::Value, heap: &FrozenHeap) -> FrozenValue {
+    match x {
+        toml::Value::String(x) => heap.alloc(x.as_str()),
+        toml::Value::Integer(x) => heap.alloc(*x),
+        toml::Value::Float(x) => heap.alloc(*x),
+        toml::Value::Boolean(x) => FrozenValue::new_bool(*x),
+        // Starlark has no date type, so keep the TOML representation.
+        toml::Value::Datetime(x) => heap.alloc(x.to_string()),
+        toml::Value::Array(xs) => heap.alloc(AllocList(xs.iter().map(|x| alloc_toml(x, heap)))),
+        toml::Value::Table(xs) => heap.alloc(AllocDict(
+            xs.iter().map(|(k, v)| (k.as_str(), alloc_toml(v, heap))),
+        )),
+    }
+}
+
+pub(crate) fn toml(globals: &mut GlobalsBuilder) {
+    #[starlark_module]
+    fn toml_members(globals: &mut GlobalsBuilder) {
+        /// Decode a TOML document into a dict.
+        ///
+        /// The result is frozen, so it can be shared without copying.
+        /// Dates and times are returned as strings in TOML syntax.
+        fn decode<'v>(
+            #[starlark(require = pos)] x: &str,
+            eval: &mut Evaluator<'v, '_, '_>,
+        ) -> anyhow::Result<Value<'v>> {
+            let value = toml::from_str::<toml::Value>(x)?;
+            Ok(alloc_toml(&value, eval.frozen_heap()).to_value())
+        }
+    }
+
+    globals.struct_("toml", toml_members);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::assert::Assert;
+
+    #[test]
+    fn test_toml_decode() {
+        let a = Assert::new();
+        a.eq(
+            r#"{
+                "package": {"name": "foo", "version": "0.1.0", "edition": 2021},
+                "dependencies": {"anyhow": "1.0", "serde": {"version": "1.0", "features": ["derive"]}},
+                "ratio": 0.5,
+                "enabled": True,
+                "released": "1979-05-27T07:32:00Z",
+            }"#,
+            r#"toml.decode("""
+ratio = 0.5
+enabled = true
+released = 1979-05-27T07:32:00Z
+
+[package]
+name = "foo"
+version = "0.1.0"
+edition = 2021
+
+[dependencies]
+anyhow = "1.0"
+serde = { version = "1.0", features = ["derive"] }
+""")"#,
+        );
+    }
+
+    #[test]
+    fn test_toml_decode_frozen() {
+        let a = Assert::new();
+        a.fail("toml.decode('x = [1]')['x'].append(2)", "Immutable");
+    }
+
+    #[test]
+    fn test_toml_decode_error() {
+        let a = Assert::new();
+        a.fail(
+            "toml.decode('[a]\\nx = 1\\ny 2')",
+            "expected an equals, found an identifier at line 3 column 3",
+        );
+    }
+}
@@ -0,0 +1,144 @@
+/*
+ * Copyright 2019 The Starlark in Rust Authors.
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The `yaml` extension.
+
+use starlark_derive::starlark_module;
+
+use crate as starlark;
+use crate::environment::GlobalsBuilder;
+use crate::eval::Evaluator;
+use crate::values::dict::AllocDict;
+use crate::values::list::AllocList;
+use crate::values::FrozenHeap;
+use crate::values::FrozenValue;
+use crate::values::Value;
+
+#[derive(Debug, thiserror::Error)]
+enum YamlDecodeError {
+    #[error("YAML mapping keys must be strings, numbers, booleans or null, got `{0}`")]
+    UnhashableKey(String),
+}
+
+fn alloc_yaml_number(x: &serde_yaml::Number, heap: &FrozenHeap) -> FrozenValue {
+    if let Some(x) = x.as_i64() {
+        heap.alloc(x)
+    } else if let Some(x) = x.as_u64() {
+        heap.alloc(x)
+    } else {
+        // YAML numbers are either integers or floats.
+        heap.alloc(x.as_f64().unwrap_or(f64::NAN))
+    }
+}
+
+fn alloc_yaml(x: &serde_yaml::Value, heap: &FrozenHeap) -> anyhow::Result<FrozenValue> {
+    Ok(match x {
+        serde_yaml::Value::Null => FrozenValue::new_none(),
+        serde_yaml::Value::Bool(x) => FrozenValue::new_bool(*x),
+        serde_yaml::Value::Number(x) => alloc_yaml_number(x, heap),
+        serde_yaml::Value::String(x) => heap.alloc(x.as_str()),
+        serde_yaml::Value::Sequence(xs) => heap.alloc(AllocList(
+            xs.iter()
+                .map(|x| alloc_yaml(x, heap))
+                .collect::<anyhow::Result<Vec<_>>>()?,
+        )),
+        serde_yaml::Value::Mapping(xs) => heap.alloc(AllocDict(
+            xs.iter()
+                .map(|(k, v)| match k {
+                    serde_yaml::Value::Sequence(_)
+                    | serde_yaml::Value::Mapping(_)
+                    | serde_yaml::Value::Tagged(_) => Err(YamlDecodeError::UnhashableKey(
+                        serde_yaml::to_string(k)?.trim_end().to_owned(),
+                    )
+                    .into()),
+                    _ => Ok((alloc_yaml(k, heap)?, alloc_yaml(v, heap)?)),
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?,
+        )),
+        // Tags like `!Ref x` are application specific, so only the value is kept.
+        serde_yaml::Value::Tagged(x) => alloc_yaml(&x.value, heap)?,
+    })
+}
+
+pub(crate) fn yaml(globals: &mut GlobalsBuilder) {
+    #[starlark_module]
+    fn yaml_members(globals: &mut GlobalsBuilder) {
+        /// Decode a YAML document.
+        ///
+        /// The result is frozen, so it can be shared without copying.
+        /// Tags are ignored, only the tagged values are kept.
+        fn decode<'v>(
+            #[starlark(require = pos)] x: &str,
+            eval: &mut Evaluator<'v, '_, '_>,
+        ) -> anyhow::Result<Value<'v>> {
+            let value = serde_yaml::from_str::<serde_yaml::Value>(x)?;
+            Ok(alloc_yaml(&value, eval.frozen_heap())?.to_value())
+        }
+    }
+
+    globals.struct_("yaml", yaml_members);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::assert::Assert;
+
+    #[test]
+    fn test_yaml_decode() {
+        let a = Assert::new();
+        a.eq(
+            r#"{
+                "name": "foo",
+                "version": 3,
+                "ratio": 0.5,
+                "enabled": True,
+                "parent": None,
+                "deps": ["a", "b"],
+                "env": {"CI": "1", 1: "one"},
+                "ref": "bar",
+            }"#,
+            r#"yaml.decode("""
+name: foo
+version: 3
+ratio: 0.5
+enabled: true
+parent: null
+deps:
+  - a
+  - b
+env: {CI: "1", 1: one}
+ref: !Ref bar
+""")"#,
+        );
+    }
+
+    #[test]
+    fn test_yaml_decode_frozen() {
+        let a = Assert::new();
+        a.fail("yaml.decode('[1]').append(2)", "Immutable");
+    }
+
+    #[test]
+    fn test_yaml_decode_error() {
+        let a = Assert::new();
+        a.fail("yaml.decode('a: 1\\nb: [2\\n')", "at line 3 column 1");
+        a.fail(
+            "yaml.decode('[1]: x')",
+            "YAML mapping keys must be strings, numbers, booleans or null, got `- 1`",
+        );
+    }
+}
@@ -244,6 +244,20 @@ noop(f)(1)
     );
 }
 
+#[test]
+fn test_bound_method_identity() {
+    // Each evaluation of `x.method` creates a new bound method, even if `x` is a constant.
+    assert::pass(
+        r#"
+m = "".count
+assert_eq(m, m)
+assert_ne("".count, "".count)
+assert_eq(len(set(["".count for _ in range(10)])), 10)
+assert_eq("".count("x"), 0)
+"#,
+    );
+}
+
 // This test relies on stack behavior which does not hold when
 // ASAN is enabled. See D47571173 for more context.
 #[cfg_attr(rust_nightly, cfg(not(sanitize = "address")))]
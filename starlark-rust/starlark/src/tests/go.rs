@@ -58,8 +58,7 @@ fn test_go() {
         test_case!("builtin.star"),
         &[
             "[] not in {123: \"\"}", // We disagree, see test_not_in_unhashable
-            // Set, unsupported
-            "set(",
+            // Set methods differ from the Go implementation
             "(myset)",
             "(myset,",
             // Has fields, unsupported
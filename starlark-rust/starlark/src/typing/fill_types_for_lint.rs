@@ -21,6 +21,7 @@ use std::iter;
 use starlark_map::unordered_map;
 use starlark_map::unordered_map::UnorderedMap;
 use starlark_syntax::slice_vec_ext::SliceExt;
+use starlark_syntax::syntax::ast::ArgumentP;
 use starlark_syntax::syntax::ast::AssignP;
 use starlark_syntax::syntax::ast::AssignTargetP;
 use starlark_syntax::syntax::ast::AstLiteral;
@@ -56,12 +57,16 @@ use crate::typing::error::TypingError;
 use crate::typing::Approximation;
 use crate::typing::Param;
 use crate::typing::Ty;
+use crate::typing::TyTypeAlias;
 use crate::typing::TypingOracleCtx;
 use crate::values::tuple::AllocTuple;
 use crate::values::types::ellipsis::Ellipsis;
 use crate::values::typing::type_compiled::compiled::TypeCompiled;
+use crate::values::typing::type_var::TypeVar;
+use crate::values::typing::type_var::TypingTypeVar;
 use crate::values::Heap;
 use crate::values::Value;
+use crate::values::ValueLike;
 
 /// Value computed during partial evaluation of globals.
 #[derive(Clone)]
@@ -132,9 +137,23 @@ impl<'a, 'v> GlobalTypesBuilder<'a, 'v> {
 
     fn call(
         &mut self,
-        _f: &CstExpr,
-        _args: &[CstArgument],
+        f: &CstExpr,
+        args: &[CstArgument],
     ) -> Result<GlobalValue<'v>, InternalError> {
+        let f = self.expr(f)?;
+        if let Some(f) = f.value {
+            if f.downcast_ref::<TypingTypeVar>().is_some() {
+                if let [arg] = args {
+                    if let ArgumentP::Positional(name) = &arg.node {
+                        if let ExprP::Literal(AstLiteral::String(name)) = &name.node {
+                            return Ok(GlobalValue::value(
+                                self.heap.alloc_simple(TypeVar::new(&name.node)),
+                            ));
+                        }
+                    }
+                }
+            }
+        }
         // TODO(nga): could be a call like `record(...)`, and we need to evaluate it.
         Ok(GlobalValue::any())
     }
@@ -393,7 +412,28 @@ impl<'a, 'v> GlobalTypesBuilder<'a, 'v> {
         let AssignP { lhs, ty, rhs } = assign;
         match ty {
             None => self.assign(lhs, rhs),
-            Some(_ty) => {
+            Some(ty) => {
+                let ty = self.get_ty_expr(ty)?;
+                if ty == Ty::custom(TyTypeAlias) {
+                    // Report invalid aliases here rather than at each use.
+                    let value = self.expr(rhs)?;
+                    let res = match value.value {
+                        Some(v) => TypeCompiled::new(v, self.heap)
+                            .map(|_| ())
+                            .map_err(|e| TypingError::new_anyhow(e, rhs.span, self.ctx.codemap)),
+                        None => self.ctx.validate_type(
+                            Spanned {
+                                span: rhs.span,
+                                node: &value.ty,
+                            },
+                            &ty,
+                        ),
+                    };
+                    if let Err(e) = res {
+                        self.errors.push(e);
+                    }
+                    return self.assign_value(lhs, value);
+                }
                 // TODO(nga): do not ignore type.
                 self.assign(lhs, rhs)
             }
@@ -562,8 +602,11 @@ impl<'a, 'v> GlobalTypesBuilder<'a, 'v> {
             TypeExprUnpackP::Path(first, rem) => self.path_ty(first, rem),
             TypeExprUnpackP::Index(a, i) => {
                 if let Some(a) = self.expr_ident(a)?.value {
-                    if !a.ptr_eq(Constants::get().fn_list.0.to_value()) {
-                        self.approximations.push(Approximation::new("Not list", x));
+                    if !a.ptr_eq(Constants::get().fn_list.0.to_value())
+                        && !a.ptr_eq(Constants::get().fn_set.0.to_value())
+                    {
+                        self.approximations
+                            .push(Approximation::new("Not list or set", x));
                         return Ok(Ty::any());
                     }
                     let i = self.from_type_expr_impl(i)?;
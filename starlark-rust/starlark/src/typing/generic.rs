@@ -0,0 +1,326 @@
+/*
+ * Copyright 2019 The Starlark in Rust Authors.
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Generic functions and type aliases.
+
+use std::iter;
+
+use allocative::Allocative;
+use dupe::Dupe;
+use starlark_map::small_map::SmallMap;
+
+use crate::codemap::Span;
+use crate::codemap::Spanned;
+use crate::typing::custom::TyCustomImpl;
+use crate::typing::error::TypingOrInternalError;
+use crate::typing::tuple::TyTuple;
+use crate::typing::Arg;
+use crate::typing::Ty;
+use crate::typing::TyBasic;
+use crate::typing::TypingBinOp;
+use crate::typing::TypingOracleCtx;
+use crate::values::layout::heap::profile::arc_str::ArcStr;
+use crate::values::types::type_instance_id::TypeInstanceId;
+use crate::values::typing::type_compiled::alloc::TypeMatcherAlloc;
+use crate::values::typing::type_compiled::compiled::TypeCompiled;
+use crate::values::typing::type_compiled::matcher::TypeMatcher;
+use crate::values::Value;
+
+/// Type variable, created with `typing.TypeVar("T")`.
+///
+/// In the body of a function, a type variable is compatible with any type.
+/// When the function is called, type variables are bound to the types of the arguments,
+/// and substituted in the result type.
+#[derive(
+    Debug,
+    Clone,
+    Dupe,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Allocative,
+    derive_more::Display
+)]
+#[display(fmt = "{}", name)]
+pub struct TyTypeVar {
+    name: ArcStr,
+    /// Identifies the `typing.TypeVar` call which created the variable,
+    /// the name is only used for display.
+    id: TypeInstanceId,
+}
+
+impl TyTypeVar {
+    pub(crate) fn new(name: &str, id: TypeInstanceId) -> TyTypeVar {
+        TyTypeVar {
+            name: ArcStr::from(name),
+            id,
+        }
+    }
+}
+
+impl TyCustomImpl for TyTypeVar {
+    fn as_name(&self) -> Option<&str> {
+        None
+    }
+
+    fn validate_call(
+        &self,
+        _span: Span,
+        _args: &[Spanned<Arg>],
+        _oracle: TypingOracleCtx,
+    ) -> Result<Ty, TypingOrInternalError> {
+        Ok(Ty::any())
+    }
+
+    fn is_callable(&self) -> bool {
+        true
+    }
+
+    fn bin_op(
+        &self,
+        _bin_op: TypingBinOp,
+        _rhs: &TyBasic,
+        _ctx: &TypingOracleCtx,
+    ) -> Result<Ty, ()> {
+        Ok(Ty::any())
+    }
+
+    fn iter_item(&self) -> Result<Ty, ()> {
+        Ok(Ty::any())
+    }
+
+    fn index(&self, _item: &TyBasic, _ctx: &TypingOracleCtx) -> Result<Ty, ()> {
+        Ok(Ty::any())
+    }
+
+    fn attribute(&self, _attr: &str) -> Result<Ty, ()> {
+        Ok(Ty::any())
+    }
+
+    fn intersects_with(&self, _other: &TyBasic) -> bool {
+        true
+    }
+
+    fn matcher<T: TypeMatcherAlloc>(&self, factory: T) -> T::Result {
+        factory.any()
+    }
+}
+
+/// Type of variables declared with `typing.TypeAlias`, e.g.
+///
+/// ```python
+/// Names: typing.TypeAlias = list[str] | None
+/// ```
+///
+/// The value must be usable as a type.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Dupe,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Allocative,
+    derive_more::Display
+)]
+#[display(fmt = "typing.TypeAlias")]
+pub struct TyTypeAlias;
+
+impl TyCustomImpl for TyTypeAlias {
+    fn as_name(&self) -> Option<&str> {
+        None
+    }
+
+    fn validate_call(
+        &self,
+        _span: Span,
+        _args: &[Spanned<Arg>],
+        _oracle: TypingOracleCtx,
+    ) -> Result<Ty, TypingOrInternalError> {
+        // Types like records are callable.
+        Ok(Ty::any())
+    }
+
+    fn is_callable(&self) -> bool {
+        true
+    }
+
+    fn bin_op(
+        &self,
+        bin_op: TypingBinOp,
+        _rhs: &TyBasic,
+        _ctx: &TypingOracleCtx,
+    ) -> Result<Ty, ()> {
+        match bin_op {
+            TypingBinOp::BitOr => Ok(Ty::any()),
+            _ => Err(()),
+        }
+    }
+
+    fn index(&self, _item: &TyBasic, _ctx: &TypingOracleCtx) -> Result<Ty, ()> {
+        Ok(Ty::any())
+    }
+
+    fn attribute(&self, _attr: &str) -> Result<Ty, ()> {
+        Ok(Ty::any())
+    }
+
+    fn intersects_with(&self, other: &TyBasic) -> bool {
+        match other {
+            TyBasic::StarlarkValue(x) => {
+                x.is_type()
+                    || other.is_str()
+                    || other.is_tuple()
+                    || other.is_list()
+                    || *other == TyBasic::none()
+            }
+            TyBasic::Dict(..) | TyBasic::Set(..) | TyBasic::Iter(..) => false,
+            // Functions like `list`, unions, custom types and many other values are types.
+            _ => true,
+        }
+    }
+
+    fn matcher<T: TypeMatcherAlloc>(&self, factory: T) -> T::Result {
+        #[derive(Allocative, Eq, PartialEq, Hash, Debug, Clone, Copy, Dupe)]
+        struct TypeAliasMatcher;
+
+        impl TypeMatcher for TypeAliasMatcher {
+            fn matches(&self, value: Value) -> bool {
+                TypeCompiled::is_type(value)
+            }
+        }
+
+        factory.alloc(TypeAliasMatcher)
+    }
+}
+
+impl TyBasic {
+    fn as_type_var(&self) -> Option<&TyTypeVar> {
+        match self {
+            TyBasic::Custom(c) => c.0.as_any().downcast_ref::<TyTypeVar>(),
+            _ => None,
+        }
+    }
+}
+
+impl Ty {
+    /// Type is or contains a type variable.
+    pub(crate) fn has_type_vars(&self) -> bool {
+        self.iter_union().iter().any(|t| match t {
+            TyBasic::List(x) | TyBasic::Set(x) | TyBasic::Iter(x) => x.has_type_vars(),
+            TyBasic::Dict(k, v) => k.has_type_vars() || v.has_type_vars(),
+            TyBasic::Tuple(TyTuple::Elems(xs)) => xs.iter().any(|x| x.has_type_vars()),
+            TyBasic::Tuple(TyTuple::Of(x)) => x.has_type_vars(),
+            t => t.as_type_var().is_some(),
+        })
+    }
+}
+
+/// Types bound to type variables at a call site.
+#[derive(Default)]
+pub(crate) struct TypeVarBindings {
+    bindings: SmallMap<TyTypeVar, Ty>,
+}
+
+impl TypeVarBindings {
+    /// Bind type variables in the parameter type to the corresponding parts of the argument type.
+    pub(crate) fn bind(&mut self, param: &Ty, arg: &Ty, ctx: &TypingOracleCtx) {
+        if arg.is_any() || arg.is_never() {
+            return;
+        }
+        let (vars, rest): (Vec<&TyBasic>, Vec<&TyBasic>) = param
+            .iter_union()
+            .iter()
+            .partition(|p| p.as_type_var().is_some());
+        for a in arg.iter_union() {
+            let mut matched = false;
+            for p in &rest {
+                if ctx.intersects_basic(p, a) {
+                    matched = true;
+                    self.bind_basic(p, a, ctx);
+                }
+            }
+            if !matched {
+                for v in &vars {
+                    let v = v.as_type_var().unwrap();
+                    let ty = match self.bindings.remove(v) {
+                        Some(ty) => Ty::union2(ty, Ty::basic(a.clone())),
+                        None => Ty::basic(a.clone()),
+                    };
+                    self.bindings.insert(v.dupe(), ty);
+                }
+            }
+        }
+    }
+
+    fn bind_basic(&mut self, param: &TyBasic, arg: &TyBasic, ctx: &TypingOracleCtx) {
+        match (param, arg) {
+            (TyBasic::List(p), TyBasic::List(a)) | (TyBasic::Set(p), TyBasic::Set(a)) => {
+                self.bind(p, a, ctx)
+            }
+            (TyBasic::Dict(pk, pv), TyBasic::Dict(ak, av)) => {
+                self.bind(pk, ak, ctx);
+                self.bind(pv, av, ctx);
+            }
+            (TyBasic::Tuple(TyTuple::Elems(ps)), TyBasic::Tuple(TyTuple::Elems(xs))) => {
+                for (p, a) in iter::zip(&**ps, &**xs) {
+                    self.bind(p, a, ctx);
+                }
+            }
+            (TyBasic::Tuple(TyTuple::Of(p)), TyBasic::Tuple(a)) => self.bind(p, &a.item_ty(), ctx),
+            (TyBasic::Iter(p), a) => {
+                if let Ok(item) = ctx.iter_item_basic(a) {
+                    self.bind(p, &item, ctx);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Replace type variables with the types bound to them.
+    /// Unbound type variables become `typing.Any`.
+    pub(crate) fn subst(&self, ty: &Ty) -> Ty {
+        Ty::unions(
+            ty.iter_union()
+                .iter()
+                .map(|t| self.subst_basic(t))
+                .collect(),
+        )
+    }
+
+    fn subst_basic(&self, ty: &TyBasic) -> Ty {
+        match ty {
+            TyBasic::List(x) => Ty::list(self.subst(x)),
+            TyBasic::Set(x) => Ty::set(self.subst(x)),
+            TyBasic::Iter(x) => Ty::iter(self.subst(x)),
+            TyBasic::Dict(k, v) => Ty::dict(self.subst(k), self.subst(v)),
+            TyBasic::Tuple(TyTuple::Elems(xs)) => {
+                Ty::tuple(xs.iter().map(|x| self.subst(x)).collect())
+            }
+            TyBasic::Tuple(TyTuple::Of(x)) => Ty::tuple_of(self.subst(x)),
+            t => match t.as_type_var() {
+                Some(v) => self.bindings.get(v).cloned().unwrap_or_else(Ty::any),
+                None => Ty::basic(t.clone()),
+            },
+        }
+    }
+}
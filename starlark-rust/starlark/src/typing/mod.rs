@@ -31,6 +31,7 @@ pub(crate) mod custom;
 pub(crate) mod error;
 pub(crate) mod fill_types_for_lint;
 pub(crate) mod function;
+pub(crate) mod generic;
 pub(crate) mod interface;
 pub(crate) mod mode;
 pub(crate) mod oracle;
@@ -50,6 +51,8 @@ pub use basic::TyBasic;
 pub use function::Arg;
 pub use function::Param;
 pub use function::TyFunction;
+pub use generic::TyTypeAlias;
+pub use generic::TyTypeVar;
 pub use interface::Interface;
 pub use oracle::ctx::TypingOracleCtx;
 pub use oracle::traits::TypingBinOp;
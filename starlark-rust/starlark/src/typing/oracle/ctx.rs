@@ -31,6 +31,7 @@ use crate::typing::function::Arg;
 use crate::typing::function::Param;
 use crate::typing::function::ParamMode;
 use crate::typing::function::TyFunction;
+use crate::typing::generic::TypeVarBindings;
 use crate::typing::starlark_value::TyStarlarkValue;
 use crate::typing::tuple::TyTuple;
 use crate::typing::Ty;
@@ -39,6 +40,7 @@ use crate::typing::TypingBinOp;
 use crate::typing::TypingUnOp;
 use crate::values::dict::value::MutableDict;
 use crate::values::list::value::List;
+use crate::values::set::value::MutableSet;
 use crate::values::tuple::value::Tuple;
 
 #[derive(Debug, thiserror::Error)]
@@ -133,12 +135,31 @@ impl<'a> TypingOracleCtx<'a> {
         }
     }
 
-    fn validate_args(
+    /// Type of values of `**kwargs` parameter, if it is a `dict`.
+    fn kwargs_value_ty(param: &Param) -> Option<Ty> {
+        let val_types: Vec<_> = param
+            .ty
+            .iter_union()
+            .iter()
+            .filter_map(|x| match x {
+                TyBasic::Dict(_k, v) => Some(v.to_ty()),
+                _ => None,
+            })
+            .collect();
+        if val_types.is_empty() {
+            None
+        } else {
+            Some(Ty::unions(val_types))
+        }
+    }
+
+    /// Validate the arguments, and return the types of arguments assigned to each parameter.
+    fn validate_args<'b>(
         &self,
         params: &[Param],
-        args: &[Spanned<Arg>],
+        args: &'b [Spanned<Arg>],
         span: Span,
-    ) -> Result<(), TypingOrInternalError> {
+    ) -> Result<Vec<Vec<Spanned<&'b Ty>>>, TypingOrInternalError> {
         // Want to figure out which arguments go in which positions
         let mut param_args: Vec<Vec<Spanned<&Ty>>> = vec![vec![]; params.len()];
         // The next index a positional parameter might fill
@@ -201,7 +222,7 @@ impl<'a> TypingOracleCtx<'a> {
             }
         }
 
-        for (param, args) in std::iter::zip(params, param_args) {
+        for (param, args) in std::iter::zip(params, &param_args) {
             if !param.allows_many() && args.len() > 1 {
                 return Err(TypingOrInternalError::Internal(InternalError::msg(
                     "bad",
@@ -229,29 +250,19 @@ impl<'a> TypingOracleCtx<'a> {
                     for ty in args {
                         // For an arg, we require the type annotation to be inner value,
                         // rather than the outer (which is always a tuple)
-                        self.validate_type(ty, &param.ty)?;
+                        self.validate_type(*ty, &param.ty)?;
                     }
                 }
                 ParamMode::Kwargs => {
-                    let val_types: Vec<_> = param
-                        .ty
-                        .iter_union()
-                        .iter()
-                        .filter_map(|x| match x {
-                            TyBasic::Dict(_k, v) => Some(v.to_ty()),
-                            _ => None,
-                        })
-                        .collect();
-                    if !val_types.is_empty() {
-                        let require = Ty::unions(val_types);
+                    if let Some(require) = Self::kwargs_value_ty(param) {
                         for ty in args {
-                            self.validate_type(ty, &require)?;
+                            self.validate_type(*ty, &require)?;
                         }
                     }
                 }
             }
         }
-        Ok(())
+        Ok(param_args)
     }
 
     pub(crate) fn validate_fn_call(
@@ -260,8 +271,26 @@ impl<'a> TypingOracleCtx<'a> {
         fun: &TyFunction,
         args: &[Spanned<Arg>],
     ) -> Result<Ty, TypingOrInternalError> {
-        self.validate_args(&fun.params, args, span)?;
-        Ok(fun.result.clone())
+        let param_args = self.validate_args(&fun.params, args, span)?;
+        if !fun.result.has_type_vars() {
+            return Ok(fun.result.clone());
+        }
+
+        // Generic function: infer type variables from the arguments.
+        let mut bindings = TypeVarBindings::default();
+        for (param, args) in std::iter::zip(&*fun.params, &param_args) {
+            let param_ty = match param.mode {
+                ParamMode::Kwargs => match Self::kwargs_value_ty(param) {
+                    Some(ty) => ty,
+                    None => continue,
+                },
+                _ => param.ty.clone(),
+            };
+            for arg in args {
+                bindings.bind(&param_ty, arg.node, self);
+            }
+        }
+        Ok(bindings.subst(&fun.result))
     }
 
     fn validate_call_for_type_name(
@@ -287,7 +316,7 @@ impl<'a> TypingOracleCtx<'a> {
             TyBasic::Any => Ok(Ty::any()),
             TyBasic::Name(n) => self.validate_call_for_type_name(span, n, args),
             TyBasic::StarlarkValue(t) => Ok(t.validate_call(span, *self)?),
-            TyBasic::List(_) | TyBasic::Dict(..) | TyBasic::Tuple(_) => Err(self
+            TyBasic::List(_) | TyBasic::Dict(..) | TyBasic::Set(_) | TyBasic::Tuple(_) => Err(self
                 .mk_error_as_maybe_internal(
                     span,
                     TypingOracleCtxError::CallToNonCallable {
@@ -339,12 +368,13 @@ impl<'a> TypingOracleCtx<'a> {
         }
     }
 
-    fn iter_item_basic(&self, ty: &TyBasic) -> Result<Ty, ()> {
+    pub(crate) fn iter_item_basic(&self, ty: &TyBasic) -> Result<Ty, ()> {
         match ty {
             TyBasic::Any => Ok(Ty::any()),
             TyBasic::StarlarkValue(ty) => ty.iter_item(),
             TyBasic::List(item) => Ok((**item).dupe()),
             TyBasic::Dict(k, _v) => Ok((**k).dupe()),
+            TyBasic::Set(item) => Ok((**item).dupe()),
             TyBasic::Tuple(tuple) => Ok(tuple.item_ty()),
             TyBasic::Callable => Ok(Ty::any()),
             TyBasic::Type => Ok(Ty::any()),
@@ -394,6 +424,7 @@ impl<'a> TypingOracleCtx<'a> {
                 }
                 Ok(Ok((**v).dupe()))
             }
+            TyBasic::Set(_) => Ok(Err(())),
             TyBasic::StarlarkValue(array) => Ok(array.index(index.node)),
             TyBasic::Custom(c) => Ok(c.0.index_dyn(index.node, self)),
             TyBasic::Name(_) => Ok(Ok(Ty::any())),
@@ -515,6 +546,10 @@ impl<'a> TypingOracleCtx<'a> {
                     attr => TyStarlarkValue::new::<MutableDict>().attr(attr),
                 }
             }
+            TyBasic::Set(elem) => match attr {
+                "pop" => Ok(Ty::function(vec![], (**elem).dupe())),
+                attr => TyStarlarkValue::new::<MutableSet>().attr(attr),
+            },
             TyBasic::Custom(custom) => custom.0.attribute_dyn(attr),
             TyBasic::Name(_) => Ok(Ty::any()),
         }
@@ -625,6 +660,30 @@ impl<'a> TypingOracleCtx<'a> {
                 }
                 bin_op => TyStarlarkValue::new::<MutableDict>().bin_op(bin_op, rhs.node),
             },
+            TyBasic::Set(elem) => match bin_op {
+                TypingBinOp::BitOr => match rhs.node {
+                    TyBasic::Set(rhs_elem) => {
+                        Ok(Ty::set(Ty::union2(elem.to_ty(), rhs_elem.to_ty())))
+                    }
+                    rhs if self.intersects_basic(rhs, &TyBasic::any_set()) => Ok(Ty::any_set()),
+                    _ => Err(()),
+                },
+                TypingBinOp::BitAnd | TypingBinOp::Sub => {
+                    if self.intersects_basic(rhs.node, &TyBasic::any_set()) {
+                        Ok(Ty::set(elem.to_ty()))
+                    } else {
+                        Err(())
+                    }
+                }
+                TypingBinOp::In => {
+                    if self.intersects(elem, &Ty::basic(rhs.node.dupe())) {
+                        Ok(Ty::bool())
+                    } else {
+                        Err(())
+                    }
+                }
+                bin_op => TyStarlarkValue::new::<MutableSet>().bin_op(bin_op, rhs.node),
+            },
             TyBasic::Custom(lhs) => lhs.0.bin_op_dyn(bin_op, rhs.node, self),
             TyBasic::Name(_) => Ok(Ty::any()),
         }
@@ -836,6 +895,8 @@ impl<'a> TypingOracleCtx<'a> {
                 self.intersects(x_k, y_k) && self.intersects(x_v, y_v)
             }
             (TyBasic::Dict(..), TyBasic::StarlarkValue(y)) => y.is_dict(),
+            (TyBasic::Set(x), TyBasic::Set(y)) => self.intersects(x, y),
+            (TyBasic::Set(_), TyBasic::StarlarkValue(y)) => y.is_set(),
             (TyBasic::Tuple(x), TyBasic::Tuple(y)) => TyTuple::intersects(x, y, self),
             (TyBasic::Tuple(_), TyBasic::StarlarkValue(y)) => y.is_tuple(),
             (TyBasic::Iter(x), TyBasic::Iter(y)) => self.intersects(x, y),
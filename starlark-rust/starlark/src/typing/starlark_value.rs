@@ -39,6 +39,7 @@ use crate::values::dict::value::FrozenDict;
 use crate::values::float::StarlarkFloat;
 use crate::values::list::value::FrozenList;
 use crate::values::none::NoneType;
+use crate::values::set::value::FrozenSet;
 use crate::values::starlark_type_id::StarlarkTypeId;
 use crate::values::string::StarlarkStr;
 use crate::values::traits::StarlarkValueVTable;
@@ -192,6 +193,11 @@ impl TyStarlarkValue {
         self == TyStarlarkValue::new::<FrozenDict>()
     }
 
+    pub(crate) fn is_set(self) -> bool {
+        self.self_check();
+        self == TyStarlarkValue::new::<FrozenSet>()
+    }
+
     pub(crate) fn is_tuple(self) -> bool {
         self.self_check();
         self == TyStarlarkValue::new::<Tuple>()
@@ -605,6 +605,104 @@ def f(x: MyList):
     );
 }
 
+#[test]
+fn test_type_alias_declared() {
+    TypeCheck::new().ty("x").check(
+        "type_alias_declared",
+        r#"
+MaybeNames: typing.TypeAlias = list[str] | None
+
+def f(x: MaybeNames):
+    pass
+
+def g():
+    f(["a"])
+    f(None)
+"#,
+    );
+}
+
+#[test]
+fn test_type_alias_invalid() {
+    TypeCheck::new().check(
+        "type_alias_invalid",
+        r#"
+X: typing.TypeAlias = len
+"#,
+    );
+}
+
+#[test]
+fn test_type_var_list() {
+    TypeCheck::new().ty("x").ty("y").check(
+        "type_var_list",
+        r#"
+T = typing.TypeVar("T")
+
+def first(xs: list[T]) -> T:
+    return xs[0]
+
+def test():
+    x = first([1, 2])
+    y = first(["a", "b"])
+"#,
+    );
+}
+
+#[test]
+fn test_type_var_dict() {
+    TypeCheck::new().ty("x").ty("y").check(
+        "type_var_dict",
+        r#"
+K = typing.TypeVar("K")
+V = typing.TypeVar("V")
+
+def swap(d: dict[K, V]) -> dict[V, K]:
+    return {v: k for k, v in d.items()}
+
+def test():
+    x = swap({"a": 1})
+    y = swap({"a": 1})["b"]
+"#,
+    );
+}
+
+#[test]
+fn test_type_var_same_name() {
+    // Type variables are identified by their definition, not by their name.
+    TypeCheck::new().ty("x").check(
+        "type_var_same_name",
+        r#"
+T = typing.TypeVar("T")
+U = typing.TypeVar("T")
+
+def pair(a: T, b: U) -> dict[T, U]:
+    return {a: b}
+
+def test():
+    x = pair(1, "a")
+"#,
+    );
+}
+
+#[test]
+fn test_type_var_result_error() {
+    TypeCheck::new().check(
+        "type_var_result_error",
+        r#"
+T = typing.TypeVar("T")
+
+def first(xs: list[T]) -> T:
+    return xs[0]
+
+def test():
+    first([1]) + "x"
+
+test()
+"#,
+    );
+}
+
 #[test]
 fn test_incorrect_type_dot() {
     TypeCheck::new().check(
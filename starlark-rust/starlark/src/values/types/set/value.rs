@@ -0,0 +1,473 @@
+/*
+ * Copyright 2018 The Starlark in Rust Authors.
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::cell::Ref;
+use std::cell::RefCell;
+use std::fmt;
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::mem;
+use std::ops::Deref;
+
+use allocative::Allocative;
+use display_container::fmt_container;
+use serde::Serialize;
+use starlark_derive::starlark_value;
+use starlark_derive::StarlarkDocs;
+use starlark_map::small_set::SmallSet;
+
+use crate as starlark;
+use crate::any::ProvidesStaticType;
+use crate::coerce::coerce;
+use crate::coerce::Coerce;
+use crate::collections::Hashed;
+use crate::environment::Methods;
+use crate::environment::MethodsStatic;
+use crate::typing::Ty;
+use crate::values::dict::refcell::unleak_borrow;
+use crate::values::error::ValueError;
+use crate::values::set::SetRef;
+use crate::values::type_repr::StarlarkTypeRepr;
+use crate::values::AllocValue;
+use crate::values::Freeze;
+use crate::values::Freezer;
+use crate::values::FrozenStringValue;
+use crate::values::FrozenValue;
+use crate::values::Heap;
+use crate::values::StarlarkValue;
+use crate::values::Trace;
+use crate::values::Value;
+use crate::values::ValueLike;
+
+#[derive(
+    Clone,
+    Default,
+    Trace,
+    Debug,
+    ProvidesStaticType,
+    StarlarkDocs,
+    Allocative
+)]
+#[starlark_docs(builtin = "extension")]
+pub(crate) struct SetGen<T>(pub(crate) T);
+
+fn fmt_set<'v>(f: &mut fmt::Formatter<'_>, set: &Set<'v>) -> fmt::Result {
+    if set.is_empty() {
+        write!(f, "set()")
+    } else {
+        fmt_container(f, "set([", "])", set.iter())
+    }
+}
+
+impl<'v, T: SetLike<'v>> Display for SetGen<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_set(f, &self.0.content())
+    }
+}
+
+impl<'v> Display for Set<'v> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_set(f, self)
+    }
+}
+
+/// Define the set type.
+#[derive(Clone, Default, Trace, Debug, ProvidesStaticType, Allocative)]
+#[repr(transparent)]
+pub struct Set<'v> {
+    /// The elements of the set. The elements must all be hashable values.
+    content: SmallSet<Value<'v>>,
+}
+
+impl<'v> StarlarkTypeRepr for Set<'v> {
+    fn starlark_type_repr() -> Ty {
+        Ty::any_set()
+    }
+}
+
+#[derive(Clone, Default, Debug, ProvidesStaticType, Allocative)]
+#[repr(transparent)]
+pub(crate) struct FrozenSetData {
+    /// The elements of the set. The elements must all be hashable values.
+    content: SmallSet<FrozenValue>,
+}
+
+/// Alias is used in `StarlarkDocs` derive.
+pub(crate) type FrozenSet = SetGen<FrozenSetData>;
+
+pub(crate) type MutableSet<'v> = SetGen<RefCell<Set<'v>>>;
+
+unsafe impl<'v> Coerce<Set<'v>> for FrozenSetData {}
+
+impl<'v> AllocValue<'v> for Set<'v> {
+    fn alloc_value(self, heap: &'v Heap) -> Value<'v> {
+        heap.alloc_complex(SetGen(RefCell::new(self)))
+    }
+}
+
+impl<'v> Set<'v> {
+    /// The result of calling `type()` on sets.
+    pub const TYPE: &'static str = "set";
+
+    /// Set type string as Starlark frozen string value.
+    pub fn get_type_value_static() -> FrozenStringValue {
+        SetGen::<FrozenSetData>::get_type_value_static()
+    }
+
+    /// Create a new set with the given content.
+    pub fn new(content: SmallSet<Value<'v>>) -> Self {
+        Self { content }
+    }
+
+    /// Number of elements in the set.
+    pub fn len(&self) -> usize {
+        self.content.len()
+    }
+
+    /// Is the set empty?
+    pub fn is_empty(&self) -> bool {
+        self.content.is_empty()
+    }
+
+    /// Iterate through the elements of the set, in insertion order.
+    pub fn iter<'a>(&'a self) -> impl ExactSizeIterator<Item = Value<'v>> + 'a {
+        self.content.iter().copied()
+    }
+
+    /// Iterate through the elements of the set, retaining their hashes.
+    pub fn iter_hashed<'a>(&'a self) -> impl ExactSizeIterator<Item = Hashed<Value<'v>>> + 'a
+    where
+        'v: 'a,
+    {
+        self.content.iter_hashed().map(|x| x.copied())
+    }
+
+    /// Check if the set contains the given element. Will be [`Err`] if the value is not hashable.
+    pub fn contains(&self, value: Value<'v>) -> crate::Result<bool> {
+        Ok(self.contains_hashed(value.get_hashed()?))
+    }
+
+    /// Check if the set contains the given prehashed element.
+    pub fn contains_hashed(&self, value: Hashed<Value<'v>>) -> bool {
+        self.content.contains_hashed(value.as_ref())
+    }
+
+    /// Insert an element into the set. Return `true` iff the element was not present.
+    pub fn insert_hashed(&mut self, value: Hashed<Value<'v>>) -> bool {
+        self.content.insert_hashed(value)
+    }
+
+    /// Remove an element from the set. Return `true` iff the element was present.
+    pub fn remove_hashed(&mut self, value: Hashed<Value<'v>>) -> bool {
+        self.content.remove_hashed(value.as_ref())
+    }
+
+    /// Remove and return the first element of the set.
+    pub(crate) fn pop_first(&mut self) -> Option<Value<'v>> {
+        let first = self.content.iter_hashed().next()?.copied();
+        self.content.remove_hashed(first.as_ref());
+        Some(first.into_key())
+    }
+
+    /// Remove all elements from the set.
+    pub fn clear(&mut self) {
+        self.content.clear();
+    }
+
+    /// Every element of this set is in `other`.
+    pub fn is_subset(&self, other: &Set<'v>) -> bool {
+        self.len() <= other.len() && self.iter_hashed().all(|x| other.contains_hashed(x))
+    }
+
+    /// Elements of this set followed by elements of `other` not in this set.
+    pub fn union(&self, other: &Set<'v>) -> Set<'v> {
+        let mut content = self.content.clone();
+        for x in other.iter_hashed() {
+            content.insert_hashed(x);
+        }
+        Set::new(content)
+    }
+
+    /// Elements of this set which are also in `other`, in the order of this set.
+    pub fn intersection(&self, other: &Set<'v>) -> Set<'v> {
+        Set::new(
+            self.iter_hashed()
+                .filter(|x| other.contains_hashed(*x))
+                .fold(SmallSet::new(), |mut content, x| {
+                    content.insert_hashed_unique_unchecked(x);
+                    content
+                }),
+        )
+    }
+
+    /// Elements of this set which are not in `other`, in the order of this set.
+    pub fn difference(&self, other: &Set<'v>) -> Set<'v> {
+        Set::new(
+            self.iter_hashed()
+                .filter(|x| !other.contains_hashed(*x))
+                .fold(SmallSet::new(), |mut content, x| {
+                    content.insert_hashed_unique_unchecked(x);
+                    content
+                }),
+        )
+    }
+}
+
+impl<'v> Freeze for SetGen<RefCell<Set<'v>>> {
+    type Frozen = SetGen<FrozenSetData>;
+    fn freeze(self, freezer: &Freezer) -> anyhow::Result<Self::Frozen> {
+        let content = self.0.into_inner().content.freeze(freezer)?;
+        Ok(SetGen(FrozenSetData { content }))
+    }
+}
+
+trait SetLike<'v>: Debug + Allocative {
+    type ContentRef<'a>: Deref<Target = Set<'v>>
+    where
+        Self: 'a,
+        'v: 'a;
+    fn content<'a>(&'a self) -> Self::ContentRef<'a>;
+    // These functions are unsafe for the same reason
+    // `StarlarkValue` iterator functions are unsafe.
+    unsafe fn iter_start(&self);
+    unsafe fn content_unchecked(&self) -> &Set<'v>;
+    unsafe fn iter_stop(&self);
+}
+
+impl<'v> SetLike<'v> for RefCell<Set<'v>> {
+    type ContentRef<'a> = Ref<'a, Set<'v>> where Self: 'a, 'v: 'a;
+
+    fn content<'a>(&'a self) -> Ref<'a, Set<'v>> {
+        self.borrow()
+    }
+
+    #[inline]
+    unsafe fn iter_start(&self) {
+        mem::forget(self.borrow());
+    }
+
+    #[inline]
+    unsafe fn iter_stop(&self) {
+        unleak_borrow(self);
+    }
+
+    #[inline]
+    unsafe fn content_unchecked(&self) -> &Set<'v> {
+        // SAFETY: this function contract is, caller must ensure that the value is borrowed.
+        self.try_borrow_unguarded().ok().unwrap_unchecked()
+    }
+}
+
+impl<'v> SetLike<'v> for FrozenSetData {
+    type ContentRef<'a> = &'a Set<'v> where Self: 'a, 'v: 'a;
+
+    fn content<'a>(&'a self) -> &'a Set<'v> {
+        coerce(self)
+    }
+
+    unsafe fn iter_start(&self) {}
+
+    unsafe fn iter_stop(&self) {}
+
+    unsafe fn content_unchecked(&self) -> &Set<'v> {
+        coerce(self)
+    }
+}
+
+pub(crate) fn set_methods() -> Option<&'static Methods> {
+    static RES: MethodsStatic = MethodsStatic::new();
+    RES.methods(crate::stdlib::set::set_methods)
+}
+
+#[starlark_value(type = Set::TYPE)]
+impl<'v, T: SetLike<'v> + 'v> StarlarkValue<'v> for SetGen<T>
+where
+    Self: ProvidesStaticType<'v>,
+{
+    type Canonical = FrozenSet;
+
+    fn get_methods() -> Option<&'static Methods> {
+        set_methods()
+    }
+
+    fn collect_repr(&self, r: &mut String) {
+        let content = self.0.content();
+        if content.is_empty() {
+            r.push_str("set()");
+            return;
+        }
+        r.push_str("set([");
+        for (i, x) in content.iter().enumerate() {
+            if i != 0 {
+                r.push_str(", ");
+            }
+            x.collect_repr(r);
+        }
+        r.push_str("])");
+    }
+
+    fn collect_repr_cycle(&self, collector: &mut String) {
+        collector.push_str("set([...])");
+    }
+
+    fn to_bool(&self) -> bool {
+        !self.0.content().is_empty()
+    }
+
+    fn equals(&self, other: Value<'v>) -> crate::Result<bool> {
+        match SetRef::from_value(other) {
+            None => Ok(false),
+            Some(other) => {
+                let this = self.0.content();
+                Ok(this.len() == other.len() && this.is_subset(&other))
+            }
+        }
+    }
+
+    fn length(&self) -> crate::Result<i32> {
+        Ok(self.0.content().len() as i32)
+    }
+
+    fn is_in(&self, other: Value<'v>) -> crate::Result<bool> {
+        self.0.content().contains(other)
+    }
+
+    unsafe fn iterate(&self, me: Value<'v>, _heap: &'v Heap) -> crate::Result<Value<'v>> {
+        self.0.iter_start();
+        Ok(me)
+    }
+
+    unsafe fn iter_size_hint(&self, index: usize) -> (usize, Option<usize>) {
+        debug_assert!(index <= self.0.content().len());
+        let rem = self.0.content().len() - index;
+        (rem, Some(rem))
+    }
+
+    unsafe fn iter_next(&self, index: usize, _heap: &'v Heap) -> Option<Value<'v>> {
+        self.0.content_unchecked().content.get_index(index).copied()
+    }
+
+    unsafe fn iter_stop(&self) {
+        self.0.iter_stop();
+    }
+
+    fn bit_or(&self, rhs: Value<'v>, heap: &'v Heap) -> crate::Result<Value<'v>> {
+        let rhs = SetRef::from_value(rhs)
+            .map_or_else(|| ValueError::unsupported_with(self, "|", rhs), Ok)?;
+        Ok(heap.alloc(self.0.content().union(&rhs)))
+    }
+
+    fn bit_and(&self, rhs: Value<'v>, heap: &'v Heap) -> crate::Result<Value<'v>> {
+        let rhs = SetRef::from_value(rhs)
+            .map_or_else(|| ValueError::unsupported_with(self, "&", rhs), Ok)?;
+        Ok(heap.alloc(self.0.content().intersection(&rhs)))
+    }
+
+    fn sub(&self, rhs: Value<'v>, heap: &'v Heap) -> crate::Result<Value<'v>> {
+        let rhs = SetRef::from_value(rhs)
+            .map_or_else(|| ValueError::unsupported_with(self, "-", rhs), Ok)?;
+        Ok(heap.alloc(self.0.content().difference(&rhs)))
+    }
+
+    fn typechecker_ty(&self) -> Option<Ty> {
+        Some(Ty::any_set())
+    }
+
+    fn get_type_starlark_repr() -> Ty {
+        Ty::any_set()
+    }
+}
+
+impl<'v, T: SetLike<'v>> Serialize for SetGen<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(self.0.content().iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::assert;
+
+    #[test]
+    fn test_repr() {
+        assert::eq("repr(set())", "'set()'");
+        assert::eq(
+            "repr(set([1, 'x', (2, 3)]))",
+            "\"set([1, \\\"x\\\", (2, 3)])\"",
+        );
+        assert::eq("str(set([3, 1, 2, 1]))", "'set([3, 1, 2])'");
+    }
+
+    #[test]
+    fn test_repr_cycle() {
+        assert::eq("s = set(); l = [s]; s.add(len(l)); repr(l)", "'[set([1])]'");
+    }
+
+    #[test]
+    fn test_equals() {
+        assert::all_true(
+            r#"
+set([1, 2, 3]) == set([3, 2, 1])
+set([1, 2]) != set([1, 2, 3])
+set() == set([])
+set([1]) != [1]
+"#,
+        );
+    }
+
+    #[test]
+    fn test_operators() {
+        assert::all_true(
+            r#"
+list(set([1, 2]) | set([3, 2])) == [1, 2, 3]
+list(set([1, 2, 3]) & set([3, 2])) == [2, 3]
+list(set([1, 2, 3]) - set([2])) == [1, 3]
+2 in set([1, 2])
+3 not in set([1, 2])
+"#,
+        );
+        assert::fail("set([1]) | [2]", "not supported");
+    }
+
+    #[test]
+    fn test_mutate_during_iteration() {
+        assert::fail(
+            r#"
+s = set([1, 2])
+for x in s:
+    s.add(x + 10)
+"#,
+            "mutate an iterable for an iterator while iterating",
+        );
+    }
+
+    #[test]
+    fn test_frozen() {
+        let mut a = assert::Assert::new();
+        a.module("m", "s = set([1, 2])");
+        a.pass("load('m', 's'); [x for x in s] == [1, 2] or fail()");
+        a.fail("load('m', 's'); s.add(3)", "Immutable");
+    }
+
+    #[test]
+    fn test_json() {
+        assert::eq("json.encode(set([1, 'a']))", "'[1,\"a\"]'");
+    }
+}
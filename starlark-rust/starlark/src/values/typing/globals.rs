@@ -20,7 +20,9 @@ use crate::values::typing::any::TypingAny;
 use crate::values::typing::callable::TypingCallable;
 use crate::values::typing::iter::TypingIterable;
 use crate::values::typing::never::TypingNever;
+use crate::values::typing::type_alias::TypingTypeAlias;
 use crate::values::typing::type_compiled::globals::register_eval_type;
+use crate::values::typing::type_var::TypingTypeVar;
 
 pub(crate) fn register_typing(globals: &mut GlobalsBuilder) {
     register_eval_type(globals);
@@ -29,5 +31,7 @@ pub(crate) fn register_typing(globals: &mut GlobalsBuilder) {
         globals.set("Never", TypingNever);
         globals.set("Callable", TypingCallable);
         globals.set("Iterable", TypingIterable);
+        globals.set("TypeVar", TypingTypeVar);
+        globals.set("TypeAlias", TypingTypeAlias);
     });
 }
@@ -24,7 +24,9 @@ pub(crate) mod iter;
 pub mod macro_refs;
 pub(crate) mod never;
 pub(crate) mod ty;
+pub(crate) mod type_alias;
 pub(crate) mod type_compiled;
+pub(crate) mod type_var;
 
 pub use crate::values::types::type_instance_id::TypeInstanceId;
 pub use crate::values::typing::callable::FrozenStarlarkCallable;
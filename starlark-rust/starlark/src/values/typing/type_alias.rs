@@ -0,0 +1,90 @@
+/*
+ * Copyright 2019 The Starlark in Rust Authors.
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use allocative::Allocative;
+use starlark_derive::NoSerialize;
+use starlark_derive::ProvidesStaticType;
+
+use crate as starlark;
+use crate::typing::Ty;
+use crate::typing::TyTypeAlias;
+use crate::values::layout::avalue::alloc_static;
+use crate::values::layout::avalue::AValueImpl;
+use crate::values::layout::avalue::Basic;
+use crate::values::layout::heap::repr::AValueRepr;
+use crate::values::starlark_value;
+use crate::values::AllocFrozenValue;
+use crate::values::FrozenHeap;
+use crate::values::FrozenValue;
+use crate::values::StarlarkValue;
+
+/// `typing.TypeAlias`, used to annotate a global which names a type:
+///
+/// ```python
+/// Names: typing.TypeAlias = list[str] | None
+/// ```
+#[derive(
+    Debug,
+    derive_more::Display,
+    Allocative,
+    ProvidesStaticType,
+    NoSerialize
+)]
+#[display(fmt = "{}", Self::TYPE)]
+pub(crate) struct TypingTypeAlias;
+
+#[starlark_value(type = "typing.TypeAlias")]
+impl<'v> StarlarkValue<'v> for TypingTypeAlias {
+    fn eval_type(&self) -> Option<Ty> {
+        Some(Ty::custom(TyTypeAlias))
+    }
+}
+
+impl AllocFrozenValue for TypingTypeAlias {
+    fn alloc_frozen_value(self, _heap: &FrozenHeap) -> FrozenValue {
+        static TYPE_ALIAS: AValueRepr<AValueImpl<Basic, TypingTypeAlias>> =
+            alloc_static(Basic, TypingTypeAlias);
+
+        FrozenValue::new_repr(&TYPE_ALIAS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::assert;
+
+    #[test]
+    fn test_type_alias_runtime() {
+        assert::pass(
+            r#"
+Names: typing.TypeAlias = list[str] | None
+
+def f(x: Names) -> Names:
+    return x
+
+f(["a"])
+f(None)
+"#,
+        );
+        assert::fail(
+            r#"
+X: typing.TypeAlias = 1
+"#,
+            "Value `1` of type `int` does not match the type annotation `typing.TypeAlias`",
+        );
+    }
+}
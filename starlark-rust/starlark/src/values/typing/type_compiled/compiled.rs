@@ -388,6 +388,13 @@ impl<'v> TypeCompiled<Value<'v>> {
         TypeCompiledFactory::alloc_ty(&Ty::list(t.as_ty().clone()), heap)
     }
 
+    pub(crate) fn type_set_of(
+        t: TypeCompiled<Value<'v>>,
+        heap: &'v Heap,
+    ) -> TypeCompiled<Value<'v>> {
+        TypeCompiledFactory::alloc_ty(&Ty::set(t.as_ty().clone()), heap)
+    }
+
     pub(crate) fn type_any_of_two(
         t0: TypeCompiled<Value<'v>>,
         t1: TypeCompiled<Value<'v>>,
@@ -458,6 +465,20 @@ impl<'v> TypeCompiled<Value<'v>> {
             Err(invalid_type_annotation(ty, heap).into())
         }
     }
+
+    /// Check if the value can be used as a type, without allocating it.
+    pub(crate) fn is_type(ty: Value<'v>) -> bool {
+        if ty.unpack_str().is_some() || ty.is_none() {
+            true
+        } else if let Some(t) = Tuple::from_value(ty) {
+            t.content().iter().all(|t| TypeCompiled::is_type(*t))
+        } else if let Some(t) = ListRef::from_value(ty) {
+            t.len() >= 2 && t.content().iter().all(|t| TypeCompiled::is_type(*t))
+        } else {
+            ty.request_value::<&dyn TypeCompiledDyn>().is_some()
+                || ty.get_ref().eval_type().is_some()
+        }
+    }
 }
 
 impl TypeCompiled<FrozenValue> {
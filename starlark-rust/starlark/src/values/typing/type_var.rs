@@ -0,0 +1,135 @@
+/*
+ * Copyright 2019 The Starlark in Rust Authors.
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use allocative::Allocative;
+use starlark_derive::NoSerialize;
+use starlark_derive::ProvidesStaticType;
+
+use crate as starlark;
+use crate::eval::Arguments;
+use crate::eval::Evaluator;
+use crate::typing::Param;
+use crate::typing::Ty;
+use crate::typing::TyTypeVar;
+use crate::values::layout::avalue::alloc_static;
+use crate::values::layout::avalue::AValueImpl;
+use crate::values::layout::avalue::Basic;
+use crate::values::layout::heap::profile::arc_str::ArcStr;
+use crate::values::layout::heap::repr::AValueRepr;
+use crate::values::starlark_value;
+use crate::values::types::type_instance_id::TypeInstanceId;
+use crate::values::AllocFrozenValue;
+use crate::values::FrozenHeap;
+use crate::values::FrozenValue;
+use crate::values::StarlarkValue;
+use crate::values::UnpackValue;
+use crate::values::Value;
+
+/// `typing.TypeVar`, constructor of type variables.
+#[derive(
+    Debug,
+    derive_more::Display,
+    Allocative,
+    ProvidesStaticType,
+    NoSerialize
+)]
+#[display(fmt = "{}", Self::TYPE)]
+pub(crate) struct TypingTypeVar;
+
+#[starlark_value(type = "typing.TypeVar")]
+impl<'v> StarlarkValue<'v> for TypingTypeVar {
+    fn invoke(
+        &self,
+        _me: Value<'v>,
+        args: &Arguments<'v, '_>,
+        eval: &mut Evaluator<'v, '_, '_>,
+    ) -> crate::Result<Value<'v>> {
+        args.no_named_args()?;
+        let name = args.positional1(eval.heap())?;
+        let name = <&str>::unpack_named_param(name, "name")?;
+        Ok(eval.heap().alloc_simple(TypeVar::new(name)))
+    }
+
+    fn typechecker_ty(&self) -> Option<Ty> {
+        Some(Ty::function(
+            vec![Param::pos_only(Ty::string())],
+            Ty::starlark_value::<TypeVar>(),
+        ))
+    }
+}
+
+impl AllocFrozenValue for TypingTypeVar {
+    fn alloc_frozen_value(self, _heap: &FrozenHeap) -> FrozenValue {
+        static TYPE_VAR: AValueRepr<AValueImpl<Basic, TypingTypeVar>> =
+            alloc_static(Basic, TypingTypeVar);
+
+        FrozenValue::new_repr(&TYPE_VAR)
+    }
+}
+
+/// Type variable, result of `typing.TypeVar("T")`.
+#[derive(
+    Debug,
+    derive_more::Display,
+    Allocative,
+    ProvidesStaticType,
+    NoSerialize
+)]
+#[display(fmt = "typing.TypeVar(\"{}\")", name)]
+pub(crate) struct TypeVar {
+    name: ArcStr,
+    /// Type variables with the same name created by different calls are different.
+    id: TypeInstanceId,
+}
+
+impl TypeVar {
+    pub(crate) fn new(name: &str) -> TypeVar {
+        TypeVar {
+            name: ArcStr::from(name),
+            id: TypeInstanceId::gen(),
+        }
+    }
+}
+
+#[starlark_value(type = "TypeVar")]
+impl<'v> StarlarkValue<'v> for TypeVar {
+    fn eval_type(&self) -> Option<Ty> {
+        Some(Ty::custom(TyTypeVar::new(&self.name, self.id)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::assert;
+
+    #[test]
+    fn test_type_var_runtime() {
+        assert::pass(
+            r#"
+T = typing.TypeVar("T")
+
+def first(xs: list[T]) -> T:
+    return xs[0]
+
+assert_eq(1, first([1, 2]))
+assert_eq("x", first(["x"]))
+assert_true(isinstance(1, T))
+assert_eq('typing.TypeVar("T")', repr(T))
+"#,
+        );
+    }
+}
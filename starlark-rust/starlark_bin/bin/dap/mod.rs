@@ -61,6 +61,18 @@ impl DapAdapterClient for Client {
             text: None,
         });
     }
+
+    fn event_output(&self, output: &str) {
+        self.event_output(OutputEventBody {
+            output: format!("{}\n", output),
+            category: Some("console".to_owned()),
+            column: None,
+            data: None,
+            line: None,
+            source: None,
+            variables_reference: None,
+        });
+    }
 }
 
 impl Backend {
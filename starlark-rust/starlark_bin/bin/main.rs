@@ -42,6 +42,7 @@ use starlark::docs::RenderMarkdown;
 use starlark::environment::Globals;
 use starlark::errors::EvalMessage;
 use starlark::errors::EvalSeverity;
+use starlark::eval::HeapProfileDiff;
 use starlark::read_line::ReadLine;
 use starlark::syntax::Dialect;
 use walkdir::WalkDir;
@@ -142,6 +143,15 @@ struct Args {
     )]
     files: Vec<PathBuf>,
 
+    #[arg(
+        long = "heap-profile-diff",
+        value_names = ["BEFORE", "AFTER"],
+        num_args = 2,
+        help = "Compare two heap profiles written in `heap-flame-allocated` or `heap-flame-retained` mode, print the difference as CSV.",
+        conflicts_with_all = &["lsp", "dap", "check", "docs", "evaluate", "files"],
+    )]
+    heap_profile_diff: Vec<PathBuf>,
+
     #[arg(
         long = "bazel",
         help = "Run in Bazel mode (temporary, will be removed)"
@@ -276,7 +286,16 @@ fn main() -> anyhow::Result<()> {
         ArgsDialect::Extended => (Dialect::Extended, Globals::extended_internal()),
     };
 
-    if args.dap {
+    if let [before, after] = args.heap_profile_diff.as_slice() {
+        let read = |path: &PathBuf| {
+            std::fs::read_to_string(path)
+                .with_context(|| format!("reading heap profile `{}`", path.display()))
+        };
+        print!(
+            "{}",
+            HeapProfileDiff::new(&read(before)?, &read(after)?)?.gen_csv()
+        );
+    } else if args.dap {
         dap::server(dialect, globals);
     } else {
         let is_interactive = args.evaluate.is_empty() && args.files.is_empty();
@@ -59,6 +59,12 @@ pub struct Dialect {
     /// Are `f"{expression}"` strings supported?
     /// Disabled in all dialects by default.
     pub enable_f_strings: bool,
+    /// Can global variables be modified at the top level: bound more than once (including
+    /// in the body of a top-level `for` loop, or by augmented assignment like `x += [1]`),
+    /// or mutated (like `x.append(1)` or `x[0] = 1`).
+    /// Disabling it makes globals single-assignment and leaves them unmodified at the top level.
+    /// Enabled in both [`Standard`](Dialect::Standard) and [`Extended`](Dialect::Extended).
+    pub enable_mutable_globals: bool,
     /// Like `#[non_exhaustive]`, but allows struct expression.
     ///
     /// [Explanation](https://github.com/rust-lang/rust-clippy/issues/6559).
@@ -87,6 +93,7 @@ impl Dialect {
         enable_load_reexport: true, // But they plan to change it
         enable_top_level_stmt: false,
         enable_f_strings: false,
+        enable_mutable_globals: true,
         _non_exhaustive: (),
     };
 
@@ -100,6 +107,7 @@ impl Dialect {
         enable_load_reexport: true,
         enable_top_level_stmt: true,
         enable_f_strings: false,
+        enable_mutable_globals: true,
         _non_exhaustive: (),
     };
 }
@@ -170,6 +170,74 @@ fn test_top_level_statements() {
     assert_eq!(parse("pass"), "pass\n");
 }
 
+#[test]
+fn test_global_reassignment() {
+    let dialect = Dialect {
+        enable_mutable_globals: false,
+        ..Dialect::Extended
+    };
+    parse_fails_with_dialect(
+        "global_reassignment",
+        &dialect,
+        &[
+            "x = 1\nx = 2",
+            "x = [1]\nx += [2]",
+            "load('a.bzl', 'x')\n(y, x) = (1, 2)",
+            "def x():\n  pass\nx = 1",
+            "for x in [1, 2]:\n  y = x",
+            "for x in [1, 2]:\n  if x:\n    y = x",
+            "for x in [1, 2]:\n  for y in [3, 4]:\n    pass",
+            "x = 1\nfor x in [1, 2]:\n  f(x)",
+            "for x in [1, 2]:\n  f(x)\nx = 3",
+        ],
+    );
+    // Locals and loop variables are fine.
+    assert_eq!(
+        parse_with_dialect(
+            "def f():\n  x = 1\n  x += 1\nfor x in [1, 2]:\n  f(x)",
+            &dialect
+        ),
+        "def f():\n  x = 1\n  x += 1\nfor x in [1, 2]:\n  f(x)\n"
+    );
+    // Top-level loops may reuse the same variable.
+    assert_eq!(
+        parse_with_dialect(
+            "for name in A:\n  f(name = name)\nfor name in B:\n  g(name = name)",
+            &dialect
+        ),
+        "for name in A:\n  f(name = name)\nfor name in B:\n  g(name = name)\n"
+    );
+}
+
+#[test]
+fn test_global_mutation() {
+    let dialect = Dialect {
+        enable_mutable_globals: false,
+        ..Dialect::Extended
+    };
+    parse_fails_with_dialect(
+        "global_mutation",
+        &dialect,
+        &[
+            "x = []\nx.append('a')",
+            "x = {}\nx['a'] = 1",
+            "x = {}\nx['a'] += 1",
+            "load('a.bzl', 'x')\nx.update(a = 1)",
+            "x = []\ny = [x.pop()]",
+            "x = []\nfor y in [1, 2]:\n  x.extend([y])",
+            "x = struct()\n(x.a, y) = (1, 2)",
+        ],
+    );
+    // Mutating locals, or globals from functions, or values which are not globals, is fine.
+    assert_eq!(
+        parse_with_dialect(
+            "x = []\ndef f():\n  x.append(1)\n  y = {}\n  y['a'] = 1\n[].append(1)\ny = x.index(1)",
+            &dialect
+        ),
+        "x = []\ndef f():\n  x.append(1)\n  y = {}\n  y[\"a\"] = 1\n[].append(1)\ny = x.index(1)\n"
+    );
+}
+
 #[test]
 fn test_top_level_def_with_docstring() {
     assert_eq!(
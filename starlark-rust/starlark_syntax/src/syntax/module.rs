@@ -111,7 +111,9 @@ fn parse_error_add_span(
 ///
 /// The internal details (statements/expressions) are deliberately omitted, as they change
 /// more regularly. A few methods to obtain information about the AST are provided.
-#[derive(Derivative)]
+///
+/// Cloning is cheaper than parsing the source again, so it can be used to cache parsed modules.
+#[derive(Derivative, Clone)]
 #[derivative(Debug)]
 pub struct AstModule {
     #[derivative(Debug = "ignore")]
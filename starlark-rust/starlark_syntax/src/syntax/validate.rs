@@ -22,15 +22,21 @@ use std::collections::HashSet;
 use thiserror::Error;
 
 use crate::codemap::CodeMap;
+use crate::codemap::Span;
 use crate::eval_exception::EvalException;
 use crate::syntax::ast::Argument;
+use crate::syntax::ast::AssignP;
+use crate::syntax::ast::AssignTarget;
 use crate::syntax::ast::AstArgument;
+use crate::syntax::ast::AstAssignIdent;
+use crate::syntax::ast::AstAssignTarget;
 use crate::syntax::ast::AstExpr;
 use crate::syntax::ast::AstLiteral;
 use crate::syntax::ast::AstStmt;
 use crate::syntax::ast::DefP;
 use crate::syntax::ast::Expr;
 use crate::syntax::ast::ForP;
+use crate::syntax::ast::LoadP;
 use crate::syntax::ast::Stmt;
 use crate::syntax::Dialect;
 use crate::syntax::DialectTypes;
@@ -53,6 +59,14 @@ enum ValidateError {
     Load,
     #[error("`...` is not allowed in this dialect")]
     Ellipsis,
+    #[error("Global variable `{0}` is already defined and cannot be reassigned in this dialect")]
+    GlobalReassignment(String),
+    #[error("Global variable `{0}` cannot be modified with augmented assignment in this dialect")]
+    GlobalAugmentedAssignment(String),
+    #[error("Global variable `{0}` cannot be assigned in a top-level `for` loop in this dialect")]
+    GlobalAssignmentInLoop(String),
+    #[error("Global variable `{0}` cannot be mutated in this dialect")]
+    GlobalMutation(String),
 }
 
 #[derive(Eq, PartialEq, PartialOrd, Ord)]
@@ -217,8 +231,181 @@ impl Stmt {
             Ok(())
         }
 
+        // Every name bound at the top level, including by `load` and `def`,
+        // must be bound only once, not in the body of a loop, and never mutated.
+        fn globals(
+            codemap: &CodeMap,
+            stmt: &AstStmt,
+            inside_for: bool,
+            bound: &mut HashSet<String>,
+            loop_vars: &mut HashSet<String>,
+        ) -> Result<(), EvalException> {
+            fn bind(
+                codemap: &CodeMap,
+                ident: &AstAssignIdent,
+                inside_for: bool,
+                bound: &mut HashSet<String>,
+            ) -> Result<(), EvalException> {
+                let name = &ident.node.ident;
+                let error = if inside_for {
+                    ValidateError::GlobalAssignmentInLoop(name.clone())
+                } else if !bound.insert(name.clone()) {
+                    ValidateError::GlobalReassignment(name.clone())
+                } else {
+                    return Ok(());
+                };
+                Err(EvalException::new_anyhow(error.into(), ident.span, codemap))
+            }
+
+            fn bind_lvalue(
+                codemap: &CodeMap,
+                lhs: &AstAssignTarget,
+                inside_for: bool,
+                bound: &mut HashSet<String>,
+            ) -> Result<(), EvalException> {
+                let mut res = Ok(());
+                lhs.visit_lvalue(|x| {
+                    if res.is_ok() {
+                        res = bind(codemap, x, inside_for, bound);
+                    }
+                });
+                res
+            }
+
+            // The global named by `expr`, if it is one.
+            fn global<'a>(expr: &'a AstExpr, bound: &HashSet<String>) -> Option<&'a str> {
+                match &expr.node {
+                    Expr::Identifier(x) if bound.contains(&x.node.ident) => Some(&x.node.ident),
+                    _ => None,
+                }
+            }
+
+            fn mutation(codemap: &CodeMap, name: &str, span: Span) -> EvalException {
+                EvalException::new_anyhow(
+                    ValidateError::GlobalMutation(name.to_owned()).into(),
+                    span,
+                    codemap,
+                )
+            }
+
+            // Assignments like `x[0] = 1` or `x.y = 1`.
+            fn mutated_target(
+                codemap: &CodeMap,
+                lhs: &AstAssignTarget,
+                bound: &HashSet<String>,
+            ) -> Result<(), EvalException> {
+                match &lhs.node {
+                    AssignTarget::Tuple(xs) => xs
+                        .iter()
+                        .try_for_each(|x| mutated_target(codemap, x, bound)),
+                    AssignTarget::Index(a_b) => match global(&a_b.0, bound) {
+                        Some(name) => Err(mutation(codemap, name, lhs.span)),
+                        None => Ok(()),
+                    },
+                    AssignTarget::Dot(a, _) => match global(a, bound) {
+                        Some(name) => Err(mutation(codemap, name, lhs.span)),
+                        None => Ok(()),
+                    },
+                    AssignTarget::Identifier(_) => Ok(()),
+                }
+            }
+
+            // Calls like `x.append(1)`.
+            fn mutating_call(
+                codemap: &CodeMap,
+                expr: &AstExpr,
+                bound: &HashSet<String>,
+            ) -> Result<(), EvalException> {
+                const MUTATING_METHODS: &[&str] = &[
+                    "append",
+                    "clear",
+                    "extend",
+                    "insert",
+                    "pop",
+                    "popitem",
+                    "remove",
+                    "setdefault",
+                    "update",
+                ];
+                if let Expr::Call(f, _) = &expr.node {
+                    if let Expr::Dot(x, method) = &f.node {
+                        if let Some(name) = global(x, bound) {
+                            if MUTATING_METHODS.contains(&method.node.as_str()) {
+                                return Err(mutation(codemap, name, expr.span));
+                            }
+                        }
+                    }
+                }
+                expr.node
+                    .visit_expr_err(|x| mutating_call(codemap, x, bound))
+            }
+
+            match &stmt.node {
+                Stmt::Assign(AssignP { lhs, rhs, .. }) => {
+                    mutating_call(codemap, rhs, bound)?;
+                    mutated_target(codemap, lhs, bound)?;
+                    bind_lvalue(codemap, lhs, inside_for, bound)
+                }
+                Stmt::AssignModify(lhs, _, rhs) => {
+                    mutating_call(codemap, rhs, bound)?;
+                    match &lhs.node {
+                        AssignTarget::Identifier(x) => Err(EvalException::new_anyhow(
+                            ValidateError::GlobalAugmentedAssignment(x.node.ident.clone()).into(),
+                            stmt.span,
+                            codemap,
+                        )),
+                        _ => mutated_target(codemap, lhs, bound),
+                    }
+                }
+                Stmt::Def(DefP { name, .. }) => bind(codemap, name, inside_for, bound),
+                Stmt::Load(LoadP { args, .. }) => args
+                    .iter()
+                    .try_for_each(|arg| bind(codemap, &arg.local, inside_for, bound)),
+                Stmt::For(ForP { var, over, body }) => {
+                    mutating_call(codemap, over, bound)?;
+                    if inside_for {
+                        bind_lvalue(codemap, var, inside_for, bound)?;
+                    } else {
+                        // Top-level loops may reuse the variables of earlier top-level loops.
+                        let mut res = Ok(());
+                        var.visit_lvalue(|x| {
+                            if res.is_ok() && loop_vars.insert(x.node.ident.clone()) {
+                                res = bind(codemap, x, inside_for, bound);
+                            }
+                        });
+                        res?;
+                    }
+                    globals(codemap, body, true, bound, loop_vars)
+                }
+                Stmt::Expression(x) => mutating_call(codemap, x, bound),
+                Stmt::If(x, _) => {
+                    mutating_call(codemap, x, bound)?;
+                    stmt.node
+                        .visit_stmt_result(|x| globals(codemap, x, inside_for, bound, loop_vars))
+                }
+                Stmt::IfElse(x, _) => {
+                    mutating_call(codemap, x, bound)?;
+                    stmt.node
+                        .visit_stmt_result(|x| globals(codemap, x, inside_for, bound, loop_vars))
+                }
+                _ => stmt
+                    .node
+                    .visit_stmt_result(|x| globals(codemap, x, inside_for, bound, loop_vars)),
+            }
+        }
+
         f(codemap, dialect, stmt, true, false, false)?;
 
+        if !dialect.enable_mutable_globals {
+            globals(
+                codemap,
+                stmt,
+                false,
+                &mut HashSet::new(),
+                &mut HashSet::new(),
+            )?;
+        }
+
         stmt.visit_expr_result(|x| expr(x, dialect, codemap))?;
 
         Ok(())
@@ -127,6 +127,10 @@ const manualSidebar = [
           'users/advanced/deferred_materialization',
           'users/advanced/restarter',
           'users/advanced/in_memory_cache',
+          'users/advanced/opentelemetry',
+          'users/advanced/strict_build_files',
+          'users/advanced/starlark_lint',
+          'users/advanced/modifiers',
           isInternal() ? 'users/advanced/offline_build_archives' : [],
           isInternal() ? 'users/advanced/vpnless' : [],
         ],